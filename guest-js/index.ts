@@ -13,6 +13,12 @@ let getExceptionsUnlistenFunction: (() => void) | null = null;
 let injectErrorTrackerUnlistenFunction: (() => void) | null = null;
 let clearExceptionsUnlistenFunction: (() => void) | null = null;
 let inspectStorageUnlistenFunction: (() => void) | null = null;
+let accessibilityTreeUnlistenFunction: (() => void) | null = null;
+let networkMockUnlistenFunction: (() => void) | null = null;
+let waitForCheckUnlistenFunction: (() => void) | null = null;
+let pressKeysUnlistenFunction: (() => void) | null = null;
+let interactiveElementsUnlistenFunction: (() => void) | null = null;
+let networkCaptureControlUnlistenFunctions: (() => void)[] = [];
 
 // Network request tracking
 interface NetworkRequest {
@@ -29,11 +35,33 @@ interface NetworkRequest {
   start_time_ms: number;
   end_time_ms?: number;
   duration_ms?: number;
+  mocked?: boolean;
+  mock_rule_id?: string;
+  command?: string; // Tauri command, for 'ipc' requests
+}
+
+// Rule installed by the network_mock command
+interface NetworkMockRule {
+  id: string;
+  url_pattern: string;
+  method?: string;
+  body_pattern?: string;
+  response?: { status: number; headers: Record<string, string>; body?: string; encoding?: 'base64' };
+  latency_ms?: number;
+  fail?: string;
+  hit_count: number;
 }
 
 const networkRequests: Map<string, NetworkRequest> = new Map();
 let networkCaptureActive = false;
+let fetchHooked = false;
+let xhrHooked = false;
+let networkDroppedCount = 0; // Requests evicted from the buffer since it was last cleared
+let lastNetworkActivityMs = 0; // Last time a request started or finished, captured or not
 const MAX_REQUESTS = 500; // Circular buffer limit
+// Requests seen while capture is stopped, kept only until they complete or a mock applies
+const untrackedRequests: Map<string, NetworkRequest> = new Map();
+let networkMockRules: NetworkMockRule[] = [];
 
 // Exception/Error tracking
 interface StackFrame {
@@ -61,6 +89,23 @@ const exceptions: Map<string, ExceptionEntry> = new Map();
 let errorTrackerActive = false;
 let circularBufferSize = 1000;
 
+// Correlation ID attached by the plugin to every request. Handlers echo it back in
+// their response so the Rust side can route the reply to the request that asked for it.
+function requestIdOf(event: any): string | undefined {
+    return event?.payload?.requestId;
+}
+
+// Report an event to the plugin, which forwards it to socket clients subscribed to the stream
+function pushEvent(stream: 'network' | 'exceptions', data: unknown): void {
+    emit('mcp-push-event', {
+        stream,
+        windowLabel: getCurrentWebviewWindow().label,
+        data,
+    }).catch(() => {
+        // Nothing useful to do if the plugin is gone
+    });
+}
+
 export async function setupPluginListeners() {
     const currentWindow: WebviewWindow = getCurrentWebviewWindow();
     domContentUnlistenFunction = await currentWindow.listen('got-dom-content', handleDomContentRequest);
@@ -74,8 +119,18 @@ export async function setupPluginListeners() {
     injectErrorTrackerUnlistenFunction = await currentWindow.listen('inject-error-tracker', handleInjectErrorTracker);
     clearExceptionsUnlistenFunction = await currentWindow.listen('clear-exceptions', handleClearExceptions);
     inspectStorageUnlistenFunction = await currentWindow.listen('inspect-storage', handleInspectStorageRequest);
+    accessibilityTreeUnlistenFunction = await currentWindow.listen('get-accessibility-tree', handleAccessibilityTreeRequest);
+    networkMockUnlistenFunction = await currentWindow.listen('network-mock', handleNetworkMockRequest);
+    waitForCheckUnlistenFunction = await currentWindow.listen('wait-for-check', handleWaitForCheck);
+    pressKeysUnlistenFunction = await currentWindow.listen('press-keys', handlePressKeysRequest);
+    interactiveElementsUnlistenFunction = await currentWindow.listen('get-interactive-elements', handleInteractiveElementsRequest);
+    networkCaptureControlUnlistenFunctions = await Promise.all(
+        ['start-network-capture', 'stop-network-capture', 'clear-network-requests', 'get-network-capture-status'].map(
+            name => currentWindow.listen(name, handleNetworkCaptureControl)
+        )
+    );
 
-    console.log('TAURI-PLUGIN-MCP: Event listeners for "got-dom-content", "get-local-storage", "execute-js", "get-element-position", "send-text-to-element", network inspection, error tracking, and storage inspection are set up on the current window.');
+    console.log('TAURI-PLUGIN-MCP: Event listeners for "got-dom-content", "get-local-storage", "execute-js", "get-element-position", "send-text-to-element", network inspection and mocking, wait conditions, key presses, error tracking, storage inspection, and accessibility snapshots are set up on the current window.');
 }
 
 export async function cleanupPluginListeners() {
@@ -109,6 +164,12 @@ export async function cleanupPluginListeners() {
         console.log('TAURI-PLUGIN-MCP: Event listener for "send-text-to-element" has been removed.');
     }
 
+    if (accessibilityTreeUnlistenFunction) {
+        accessibilityTreeUnlistenFunction();
+        accessibilityTreeUnlistenFunction = null;
+        console.log('TAURI-PLUGIN-MCP: Event listener for "get-accessibility-tree" has been removed.');
+    }
+
     if (getNetworkRequestsUnlistenFunction) {
         getNetworkRequestsUnlistenFunction();
         getNetworkRequestsUnlistenFunction = null;
@@ -121,6 +182,36 @@ export async function cleanupPluginListeners() {
         console.log('TAURI-PLUGIN-MCP: Event listener for "inject-network-capture" has been removed.');
     }
 
+    if (networkMockUnlistenFunction) {
+        networkMockUnlistenFunction();
+        networkMockUnlistenFunction = null;
+        console.log('TAURI-PLUGIN-MCP: Event listener for "network-mock" has been removed.');
+    }
+
+    if (waitForCheckUnlistenFunction) {
+        waitForCheckUnlistenFunction();
+        waitForCheckUnlistenFunction = null;
+        console.log('TAURI-PLUGIN-MCP: Event listener for "wait-for-check" has been removed.');
+    }
+
+    if (pressKeysUnlistenFunction) {
+        pressKeysUnlistenFunction();
+        pressKeysUnlistenFunction = null;
+        console.log('TAURI-PLUGIN-MCP: Event listener for "press-keys" has been removed.');
+    }
+
+    if (interactiveElementsUnlistenFunction) {
+        interactiveElementsUnlistenFunction();
+        interactiveElementsUnlistenFunction = null;
+        console.log('TAURI-PLUGIN-MCP: Event listener for "get-interactive-elements" has been removed.');
+    }
+
+    if (networkCaptureControlUnlistenFunctions.length > 0) {
+        networkCaptureControlUnlistenFunctions.forEach(unlisten => unlisten());
+        networkCaptureControlUnlistenFunctions = [];
+        console.log('TAURI-PLUGIN-MCP: Event listeners for network capture control have been removed.');
+    }
+
     if (getExceptionsUnlistenFunction) {
         getExceptionsUnlistenFunction();
         getExceptionsUnlistenFunction = null;
@@ -145,8 +236,9 @@ export async function cleanupPluginListeners() {
         console.log('TAURI-PLUGIN-MCP: Event listener for "inspect-storage" has been removed.');
     }
 
-    // Stop network capture
+    // Stop network capture and mocking
     networkCaptureActive = false;
+    networkMockRules = [];
 
     // Stop error tracking
     errorTrackerActive = false;
@@ -154,9 +246,10 @@ export async function cleanupPluginListeners() {
 
 async function handleGetElementPositionRequest(event: any) {
     console.log('TAURI-PLUGIN-MCP: Received get-element-position, payload:', event.payload);
+    const requestId = requestIdOf(event);
 
     try {
-        const { selectorType, selectorValue, shouldClick = false } = event.payload;
+        const { selectorType, selectorValue, shouldClick = false, scrollIntoView = false } = event.payload;
 
         // Find the element based on the selector type
         let element = null;
@@ -203,6 +296,20 @@ async function handleGetElementPositionRequest(event: any) {
                     }
                 }
                 break;
+            case 'ref':
+                // Ref ID from get_accessibility_tree
+                element = findElementByRef(selectorValue);
+                if (!element) {
+                    debugInfo.push(`No element found with ref="${selectorValue}" (take a new accessibility snapshot)`);
+                }
+                break;
+            case 'mark':
+                // Number from take_annotated_screenshot
+                element = findElementByMark(selectorValue);
+                if (!element) {
+                    debugInfo.push(`No element found with mark ${selectorValue} (take a new annotated screenshot)`);
+                }
+                break;
             case 'xpath':
                 // Use XPath to find element
                 try {
@@ -241,6 +348,7 @@ async function handleGetElementPositionRequest(event: any) {
             const currentWindow: WebviewWindow = getCurrentWebviewWindow();
             try {
                 await currentWindow.emit('get-element-position-response', {
+                    requestId,
                     success: false,
                     error: `Element not found. Debug info: ${debugInfo.join('; ')}`,
                 });
@@ -250,6 +358,11 @@ async function handleGetElementPositionRequest(event: any) {
             return;
         }
 
+        if (scrollIntoView) {
+            // The real mouse and screenshots can only reach what is on screen
+            element.scrollIntoView({ block: 'nearest', inline: 'nearest' });
+        }
+
         const rect = element.getBoundingClientRect();
 
         console.log('TAURI-PLUGIN-MCP: Element rect:', {
@@ -274,10 +387,22 @@ async function handleGetElementPositionRequest(event: any) {
         const currentWindow: WebviewWindow = getCurrentWebviewWindow();
         try {
             await currentWindow.emit('get-element-position-response', {
-                x: Math.round(targetX),
-                y: Math.round(targetY),
-                width: Math.round(rect.width),
-                height: Math.round(rect.height),
+                requestId,
+                success: true,
+                data: {
+                    x: Math.round(targetX),
+                    y: Math.round(targetY),
+                    width: Math.round(rect.width),
+                    height: Math.round(rect.height),
+                    rect: { x: rect.left, y: rect.top, width: rect.width, height: rect.height },
+                    element: {
+                        tag: element.tagName.toLowerCase(),
+                        id: element.id || undefined,
+                        classes: typeof element.className === 'string' ? element.className : undefined,
+                    },
+                    clicked: shouldClick,
+                    clickResult: shouldClick ? { success: true } : undefined,
+                },
             });
         } catch (e) {
             console.error('TAURI-PLUGIN-MCP: Error emitting response', e);
@@ -287,6 +412,7 @@ async function handleGetElementPositionRequest(event: any) {
 
         const currentWindow: WebviewWindow = getCurrentWebviewWindow();
         currentWindow.emit('get-element-position-response', {
+            requestId,
             success: false,
             error: `Error occurred: ${error}`,
         }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
@@ -295,17 +421,19 @@ async function handleGetElementPositionRequest(event: any) {
 
 async function handleDomContentRequest(event: any) {
     console.log('TAURI-PLUGIN-MCP: Received got-dom-content, payload:', event.payload);
+    const requestId = requestIdOf(event);
 
     try {
         const domContent = getDomContentRecursive(document.documentElement, 10000);
         const currentWindow: WebviewWindow = getCurrentWebviewWindow();
-        await currentWindow.emit('got-dom-content-response', { domContent });
+        await currentWindow.emit('got-dom-content-response', { requestId, domContent });
         console.log('TAURI-PLUGIN-MCP: Emitted got-dom-content-response');
     } catch (error) {
         console.error('TAURI-PLUGIN-MCP: Error handling dom content request', error);
 
         const currentWindow: WebviewWindow = getCurrentWebviewWindow();
         currentWindow.emit('got-dom-content-response', {
+            requestId,
             domContent: ''
         }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting empty response', e));
     }
@@ -336,8 +464,384 @@ if (document.readyState === 'loading') {
     console.warn('TAURI-PLUGIN-MCP: DOM not fully loaded when got-dom-content received. Returning empty content.');
 }
 
+// Accessibility tree snapshots
+//
+// Every element that appears in a snapshot gets a ref ID ("e1", "e2", ...). The ID stays the
+// same for the element's lifetime, so refs from an earlier snapshot keep working as selectors
+// (selector_type "ref") as long as the element is still in the document.
+const elementRefs = new WeakMap<Element, string>();
+const refElements = new Map<string, WeakRef<Element>>();
+let nextElementRef = 1;
+
+interface AccessibilityNode {
+  ref: string;
+  role: string;
+  name: string;
+  value?: string;
+  level?: number;
+  states?: Record<string, boolean | 'mixed'>;
+  bounds: { x: number; y: number; width: number; height: number };
+  children?: AccessibilityNode[];
+}
+
+interface AccessibilityWalk {
+  maxDepth: number;
+  maxNodes: number;
+  viewportOnly: boolean;
+  interactiveOnly: boolean;
+  nodeCount: number;
+  truncated: boolean;
+}
+
+const INTERACTIVE_ROLES = new Set([
+    'button', 'link', 'textbox', 'searchbox', 'checkbox', 'radio', 'combobox', 'listbox', 'option',
+    'slider', 'spinbutton', 'switch', 'tab', 'menuitem', 'menuitemcheckbox', 'menuitemradio', 'treeitem',
+]);
+
+// Roles whose accessible name comes from their text content
+const NAME_FROM_CONTENT_ROLES = new Set([
+    'button', 'link', 'heading', 'option', 'cell', 'columnheader', 'rowheader', 'checkbox', 'radio',
+    'switch', 'tab', 'menuitem', 'menuitemcheckbox', 'menuitemradio', 'treeitem', 'tooltip', 'listitem',
+]);
+
+const LANDMARK_ROLES: Record<string, string> = {
+    nav: 'navigation', main: 'main', header: 'banner', footer: 'contentinfo', aside: 'complementary',
+    form: 'form', article: 'article', dialog: 'dialog', ul: 'list', ol: 'list', li: 'listitem',
+    table: 'table', tr: 'row', td: 'cell', th: 'columnheader', progress: 'progressbar',
+    fieldset: 'group', details: 'group', summary: 'button', hr: 'separator', p: 'paragraph',
+    textarea: 'textbox', option: 'option', meter: 'meter', figure: 'figure', menu: 'list',
+};
+
+function refForElement(element: Element): string {
+    let ref = elementRefs.get(element);
+    if (!ref) {
+        ref = `e${nextElementRef++}`;
+        elementRefs.set(element, ref);
+        refElements.set(ref, new WeakRef(element));
+    }
+    return ref;
+}
+
+// Element for a ref ID, or null once it has been garbage collected or removed from the document
+function findElementByRef(ref: string): Element | null {
+    const element = refElements.get(ref)?.deref();
+    if (!element || !element.isConnected) {
+        refElements.delete(ref);
+        return null;
+    }
+    return element;
+}
+
+function roleOf(element: Element): string {
+    const explicit = element.getAttribute('role')?.trim().split(/\s+/)[0];
+    if (explicit) {
+        return explicit;
+    }
+
+    const tag = element.tagName.toLowerCase();
+    switch (tag) {
+        case 'a':
+        case 'area':
+            return element.hasAttribute('href') ? 'link' : 'generic';
+        case 'button':
+            return 'button';
+        case 'img':
+            return element.getAttribute('alt') === '' ? 'presentation' : 'img';
+        case 'h1': case 'h2': case 'h3': case 'h4': case 'h5': case 'h6':
+            return 'heading';
+        case 'select':
+            return (element as HTMLSelectElement).multiple || (element as HTMLSelectElement).size > 1 ? 'listbox' : 'combobox';
+        case 'section':
+            return element.hasAttribute('aria-label') || element.hasAttribute('aria-labelledby') ? 'region' : 'generic';
+        case 'input': {
+            const type = ((element as HTMLInputElement).type || 'text').toLowerCase();
+            switch (type) {
+                case 'checkbox': return 'checkbox';
+                case 'radio': return 'radio';
+                case 'range': return 'slider';
+                case 'number': return 'spinbutton';
+                case 'search': return 'searchbox';
+                case 'button': case 'submit': case 'reset': case 'image': return 'button';
+                case 'hidden': return 'none';
+                default: return 'textbox';
+            }
+        }
+        default:
+            if ((element as HTMLElement).isContentEditable && !element.parentElement?.isContentEditable) {
+                return 'textbox';
+            }
+            return LANDMARK_ROLES[tag] ?? 'generic';
+    }
+}
+
+function collapseWhitespace(text: string | null | undefined, limit = 120): string {
+    const collapsed = (text ?? '').replace(/\s+/g, ' ').trim();
+    return collapsed.length > limit ? `${collapsed.slice(0, limit - 1)}…` : collapsed;
+}
+
+function accessibleName(element: Element, role: string): string {
+    const labelledBy = element.getAttribute('aria-labelledby');
+    if (labelledBy) {
+        const text = labelledBy
+            .split(/\s+/)
+            .map((id) => document.getElementById(id)?.textContent ?? '')
+            .join(' ');
+        if (text.trim()) {
+            return collapseWhitespace(text);
+        }
+    }
+
+    const ariaLabel = element.getAttribute('aria-label');
+    if (ariaLabel?.trim()) {
+        return collapseWhitespace(ariaLabel);
+    }
+
+    if (element instanceof HTMLInputElement || element instanceof HTMLTextAreaElement || element instanceof HTMLSelectElement) {
+        const label = element.labels?.[0]?.textContent;
+        if (label?.trim()) {
+            return collapseWhitespace(label);
+        }
+        if (element instanceof HTMLInputElement && ['button', 'submit', 'reset'].includes(element.type)) {
+            return collapseWhitespace(element.value || element.type);
+        }
+    }
+
+    const alt = element.getAttribute('alt');
+    if (alt?.trim()) {
+        return collapseWhitespace(alt);
+    }
+
+    if (NAME_FROM_CONTENT_ROLES.has(role)) {
+        const text = collapseWhitespace((element as HTMLElement).innerText ?? element.textContent);
+        if (text) {
+            return text;
+        }
+    }
+
+    return collapseWhitespace(element.getAttribute('title') || element.getAttribute('placeholder'));
+}
+
+// Text directly inside an element, ignoring the text of its child elements
+function ownText(element: Element): string {
+    let text = '';
+    element.childNodes.forEach((child) => {
+        if (child.nodeType === Node.TEXT_NODE) {
+            text += child.textContent ?? '';
+        }
+    });
+    return collapseWhitespace(text);
+}
+
+function accessibilityStates(element: Element, role: string): Record<string, boolean | 'mixed'> | undefined {
+    const states: Record<string, boolean | 'mixed'> = {};
+    const aria = (name: string) => element.getAttribute(`aria-${name}`);
+
+    if (element instanceof HTMLInputElement && (element.type === 'checkbox' || element.type === 'radio')) {
+        states.checked = element.indeterminate ? 'mixed' : element.checked;
+    } else if (aria('checked') !== null) {
+        states.checked = aria('checked') === 'mixed' ? 'mixed' : aria('checked') === 'true';
+    }
+    if ((element as HTMLButtonElement).disabled || aria('disabled') === 'true') {
+        states.disabled = true;
+    }
+    if (aria('expanded') !== null) {
+        states.expanded = aria('expanded') === 'true';
+    } else if (element instanceof HTMLDetailsElement) {
+        states.expanded = element.open;
+    }
+    if (aria('selected') !== null) {
+        states.selected = aria('selected') === 'true';
+    } else if (element instanceof HTMLOptionElement) {
+        states.selected = element.selected;
+    }
+    if (aria('pressed') !== null) {
+        states.pressed = aria('pressed') === 'mixed' ? 'mixed' : aria('pressed') === 'true';
+    }
+    if ((element as HTMLInputElement).required || aria('required') === 'true') {
+        states.required = true;
+    }
+    if ((element as HTMLInputElement).readOnly || aria('readonly') === 'true') {
+        states.readonly = true;
+    }
+    if (document.activeElement === element) {
+        states.focused = true;
+    }
+    if (role === 'link' && (element as HTMLAnchorElement).href && (element as HTMLAnchorElement).href === location.href) {
+        states.current = true;
+    }
+
+    return Object.keys(states).length > 0 ? states : undefined;
+}
+
+function valueOf(element: Element): string | undefined {
+    if (element instanceof HTMLInputElement) {
+        if (element.type === 'password') {
+            return element.value ? '••••' : undefined;
+        }
+        if (element.type === 'checkbox' || element.type === 'radio') {
+            return undefined;
+        }
+        return element.value || undefined;
+    }
+    if (element instanceof HTMLTextAreaElement || element instanceof HTMLSelectElement) {
+        return collapseWhitespace(element.value) || undefined;
+    }
+    if (element.hasAttribute('aria-valuenow')) {
+        return element.getAttribute('aria-valuetext') ?? element.getAttribute('aria-valuenow') ?? undefined;
+    }
+    return undefined;
+}
+
+function isHidden(element: Element): boolean {
+    if (element.getAttribute('aria-hidden') === 'true' || (element as HTMLElement).hidden) {
+        return true;
+    }
+    const tag = element.tagName.toLowerCase();
+    if (tag === 'script' || tag === 'style' || tag === 'noscript' || tag === 'template' || tag === 'head') {
+        return true;
+    }
+    const style = getComputedStyle(element);
+    return style.display === 'none' || style.visibility === 'hidden';
+}
+
+function isInteractive(element: Element, role: string): boolean {
+    if (INTERACTIVE_ROLES.has(role)) {
+        return true;
+    }
+    const tabIndex = element.getAttribute('tabindex');
+    return (tabIndex !== null && Number(tabIndex) >= 0) || element.hasAttribute('onclick');
+}
+
+function isInViewport(rect: DOMRect): boolean {
+    return rect.bottom > 0 && rect.right > 0 && rect.top < window.innerHeight && rect.left < window.innerWidth;
+}
+
+// Accessibility nodes for an element: the element itself, or its children when the element
+// has no meaning of its own (generic containers, filtered-out nodes)
+function walkAccessibilityTree(element: Element, depth: number, walk: AccessibilityWalk): AccessibilityNode[] {
+    if (walk.nodeCount >= walk.maxNodes) {
+        walk.truncated = true;
+        return [];
+    }
+    if (isHidden(element)) {
+        return [];
+    }
+
+    let role = roleOf(element);
+    const rect = element.getBoundingClientRect();
+    let name = role === 'none' || role === 'presentation' ? '' : accessibleName(element, role);
+
+    // Plain containers only matter when they hold text of their own
+    if (role === 'generic' && !name) {
+        const text = ownText(element);
+        if (text) {
+            role = 'text';
+            name = text;
+        }
+    }
+
+    const meaningful = role !== 'generic' && role !== 'none' && role !== 'presentation';
+    const included =
+        meaningful &&
+        (!walk.interactiveOnly || isInteractive(element, role)) &&
+        (!walk.viewportOnly || isInViewport(rect)) &&
+        rect.width > 0 && rect.height > 0;
+
+    // Count the node before its children so maxNodes is a hard limit on the whole tree
+    if (included) {
+        if (walk.nodeCount >= walk.maxNodes) {
+            walk.truncated = true;
+            return [];
+        }
+        walk.nodeCount++;
+    }
+
+    const childDepth = included ? depth + 1 : depth;
+    const children: AccessibilityNode[] = [];
+    if (childDepth < walk.maxDepth) {
+        for (const child of Array.from(element.children)) {
+            children.push(...walkAccessibilityTree(child, childDepth, walk));
+        }
+        const shadowRoot = (element as HTMLElement).shadowRoot;
+        if (shadowRoot) {
+            for (const child of Array.from(shadowRoot.children)) {
+                children.push(...walkAccessibilityTree(child, childDepth, walk));
+            }
+        }
+    } else if (element.children.length > 0) {
+        walk.truncated = true;
+    }
+
+    if (!included) {
+        return children;
+    }
+
+    const node: AccessibilityNode = {
+        ref: refForElement(element),
+        role,
+        name,
+        value: valueOf(element),
+        level: role === 'heading' ? Number(element.getAttribute('aria-level') ?? element.tagName.slice(1)) || undefined : undefined,
+        states: accessibilityStates(element, role),
+        bounds: {
+            x: Math.round(rect.x),
+            y: Math.round(rect.y),
+            width: Math.round(rect.width),
+            height: Math.round(rect.height),
+        },
+        children: children.length > 0 ? children : undefined,
+    };
+    return [node];
+}
+
+async function handleAccessibilityTreeRequest(event: any) {
+    console.log('TAURI-PLUGIN-MCP: Received get-accessibility-tree, payload:', event.payload);
+    const requestId = requestIdOf(event);
+    const currentWindow: WebviewWindow = getCurrentWebviewWindow();
+
+    try {
+        const { maxDepth, maxNodes, viewportOnly = false, interactiveOnly = false, rootSelector } = event.payload ?? {};
+
+        const root = rootSelector ? document.querySelector(rootSelector) : document.body;
+        if (!root) {
+            throw new Error(`No element matches root selector "${rootSelector}"`);
+        }
+
+        const walk: AccessibilityWalk = {
+            maxDepth: maxDepth ?? Number.MAX_SAFE_INTEGER,
+            maxNodes: maxNodes ?? Number.MAX_SAFE_INTEGER,
+            viewportOnly,
+            interactiveOnly,
+            nodeCount: 0,
+            truncated: false,
+        };
+        const nodes = walkAccessibilityTree(root, 0, walk);
+
+        await currentWindow.emit('get-accessibility-tree-response', {
+            requestId,
+            success: true,
+            data: {
+                url: location.href,
+                title: document.title,
+                viewport: { width: window.innerWidth, height: window.innerHeight },
+                nodes,
+                nodeCount: walk.nodeCount,
+                truncated: walk.truncated,
+            },
+        });
+    } catch (error) {
+        console.error('TAURI-PLUGIN-MCP: Error handling get-accessibility-tree request', error);
+        await currentWindow.emit('get-accessibility-tree-response', {
+            requestId,
+            success: false,
+            error: String(error),
+        });
+    }
+}
+
 async function handleLocalStorageRequest(event: any) {
     console.log('TAURI-PLUGIN-MCP: Received get-local-storage, payload:', event.payload);
+    const requestId = requestIdOf(event);
 
     const currentWindow: WebviewWindow = getCurrentWebviewWindow();
 
@@ -362,7 +866,7 @@ async function handleLocalStorageRequest(event: any) {
     }
 
     try {
-        await currentWindow.emit('get-local-storage-response', storage);
+        await currentWindow.emit('get-local-storage-response', { requestId, data: storage });
     } catch (e) {
         console.error('TAURI-PLUGIN-MCP: Error emitting get-local-storage-response', e);
     }
@@ -370,20 +874,25 @@ async function handleLocalStorageRequest(event: any) {
 
 async function handleJsExecutionRequest(event: any) {
     console.log('TAURI-PLUGIN-MCP: Received execute-js, payload:', event.payload);
+    const requestId = requestIdOf(event);
 
     const currentWindow: WebviewWindow = getCurrentWebviewWindow();
 
     const { code } = event.payload;
     try {
+        // Await so code that evaluates to a Promise (e.g. an async IIFE) reports its settled value
         // eslint-disable-next-line no-eval
-        const result = (0, eval)(code);
+        const result = await (0, eval)(code);
         await currentWindow.emit('execute-js-response', {
+            requestId,
             success: true,
-            result: typeof result === 'object' ? JSON.stringify(result) : String(result)
+            result: typeof result === 'object' ? JSON.stringify(result) : String(result),
+            type: typeof result
         });
     } catch (error) {
         console.error('TAURI-PLUGIN-MCP: Error executing JS:', error);
         await currentWindow.emit('execute-js-response', {
+            requestId,
             success: false,
             error: String(error)
         });
@@ -392,43 +901,14 @@ async function handleJsExecutionRequest(event: any) {
 
 async function handleSendTextToElementRequest(event: any) {
     console.log('TAURI-PLUGIN-MCP: Received send-text-to-element, payload:', event.payload);
+    const requestId = requestIdOf(event);
 
     const currentWindow: WebviewWindow = getCurrentWebviewWindow();
 
     try {
         const { selectorType, selectorValue, text, delayMs = 0 } = event.payload;
 
-        // Find the element
-        let element = null;
-
-        switch (selectorType) {
-            case 'id':
-                element = document.getElementById(selectorValue);
-                break;
-            case 'class':
-                const elemsByClass = document.getElementsByClassName(selectorValue);
-                element = elemsByClass.length > 0 ? (elemsByClass[0] as HTMLElement) : null;
-                break;
-            case 'tag':
-                const elemsByTag = document.getElementsByTagName(selectorValue);
-                element = elemsByTag.length > 0 ? (elemsByTag[0] as HTMLElement) : null;
-                break;
-            case 'text':
-                element = findElementByText(selectorValue);
-                break;
-            case 'xpath':
-                try {
-                    const result = document.evaluate(selectorValue, document, null, XPathResult.FIRST_ORDERED_NODE_TYPE, null);
-                    element = result.singleNodeValue as HTMLElement;
-                } catch (err) {
-                    throw new Error(`Invalid XPath: "${selectorValue}"`);
-                }
-                break;
-            case 'css':
-            default:
-                element = document.querySelector(selectorValue) as HTMLElement;
-                break;
-        }
+        const element = findElement(selectorType, selectorValue);
 
         if (!element) {
             throw new Error(`Element not found: ${selectorValue}`);
@@ -447,17 +927,60 @@ async function handleSendTextToElementRequest(event: any) {
             await typeIntoElement(element as HTMLElement, text, delayMs);
         }
 
-        await currentWindow.emit('send-text-to-element-response', { success: true });
+        await currentWindow.emit('send-text-to-element-response', { requestId, success: true });
     } catch (error) {
         console.error('TAURI-PLUGIN-MCP: Error handling send-text-to-element request', error);
 
         await currentWindow.emit('send-text-to-element-response', {
+            requestId,
             success: false,
             error: String(error)
         });
     }
 }
 
+// Element addressed by a selector_type/selector_value pair, as accepted by the element tools
+function findElement(selectorType: string, selectorValue: string): HTMLElement | null {
+    let element: HTMLElement | null = null;
+
+    switch (selectorType) {
+        case 'id':
+            element = document.getElementById(selectorValue);
+            break;
+        case 'class':
+            const elemsByClass = document.getElementsByClassName(selectorValue);
+            element = elemsByClass.length > 0 ? (elemsByClass[0] as HTMLElement) : null;
+            break;
+        case 'tag':
+            const elemsByTag = document.getElementsByTagName(selectorValue);
+            element = elemsByTag.length > 0 ? (elemsByTag[0] as HTMLElement) : null;
+            break;
+        case 'text':
+            element = findElementByText(selectorValue);
+            break;
+        case 'ref':
+            element = findElementByRef(selectorValue) as HTMLElement | null;
+            break;
+        case 'mark':
+            element = findElementByMark(selectorValue) as HTMLElement | null;
+            break;
+        case 'xpath':
+            try {
+                const result = document.evaluate(selectorValue, document, null, XPathResult.FIRST_ORDERED_NODE_TYPE, null);
+                element = result.singleNodeValue as HTMLElement;
+            } catch (err) {
+                throw new Error(`Invalid XPath: "${selectorValue}"`);
+            }
+            break;
+        case 'css':
+        default:
+            element = document.querySelector(selectorValue) as HTMLElement;
+            break;
+    }
+
+    return element;
+}
+
 // Helper function to find element by text content
 function findElementByText(text: string): HTMLElement | null {
     const elements = document.querySelectorAll('*');
@@ -673,17 +1196,50 @@ function recordNetworkRequest(
         request_body: requestBody,
         start_time_ms: Date.now(),
     };
-    networkRequests.set(id, request);
+    lastNetworkActivityMs = request.start_time_ms;
+
+    if (networkCaptureActive) {
+        storeNetworkRequest(request);
+    } else {
+        untrackedRequests.set(id, request);
+        if (untrackedRequests.size > MAX_REQUESTS) {
+            const firstKey = untrackedRequests.keys().next().value;
+            if (firstKey) {
+                untrackedRequests.delete(firstKey);
+            }
+        }
+    }
+
+    return id;
+}
+
+function storeNetworkRequest(request: NetworkRequest): void {
+    untrackedRequests.delete(request.id);
+    networkRequests.set(request.id, request);
 
     // Maintain circular buffer limit
     if (networkRequests.size > MAX_REQUESTS) {
         const firstKey = networkRequests.keys().next().value;
         if (firstKey) {
             networkRequests.delete(firstKey);
+            networkDroppedCount++;
         }
     }
+}
 
-    return id;
+function findNetworkRequest(id: string): NetworkRequest | undefined {
+    return networkRequests.get(id) ?? untrackedRequests.get(id);
+}
+
+// A request is done: forget it if it was not captured, report it to subscribers if it was
+function finishNetworkRequest(request: NetworkRequest): void {
+    request.end_time_ms = Date.now();
+    request.duration_ms = request.end_time_ms - request.start_time_ms;
+    lastNetworkActivityMs = request.end_time_ms;
+    untrackedRequests.delete(request.id);
+    if (networkCaptureActive && networkRequests.has(request.id)) {
+        pushEvent('network', request);
+    }
 }
 
 function updateNetworkResponse(
@@ -692,22 +1248,36 @@ function updateNetworkResponse(
     responseHeaders: Record<string, string>,
     responseBody?: string
 ): void {
-    const request = networkRequests.get(id);
+    const request = findNetworkRequest(id);
     if (request) {
         request.status_code = statusCode;
         request.response_headers = responseHeaders || {};
         request.response_body = responseBody;
-        request.end_time_ms = Date.now();
-        request.duration_ms = request.end_time_ms - request.start_time_ms;
+        finishNetworkRequest(request);
     }
 }
 
 function recordNetworkError(id: string, error: string): void {
-    const request = networkRequests.get(id);
+    const request = findNetworkRequest(id);
     if (request) {
         request.error = error;
-        request.end_time_ms = Date.now();
-        request.duration_ms = request.end_time_ms - request.start_time_ms;
+        finishNetworkRequest(request);
+    }
+}
+
+// Tauri sends every invoke() as a POST to its IPC custom protocol through window.fetch:
+// ipc://localhost/<command>, or http(s)://ipc.localhost/<command> on Windows and Android
+const IPC_URL = /^(?:ipc:\/\/localhost|https?:\/\/ipc\.localhost)\/([^?#]*)/;
+
+function ipcCommandOf(url: string): string | null {
+    const match = IPC_URL.exec(url);
+    if (!match) {
+        return null;
+    }
+    try {
+        return decodeURIComponent(match[1]);
+    } catch (e) {
+        return match[1];
     }
 }
 
@@ -718,6 +1288,12 @@ function interceptFetch(): void {
         const options = args[1] || {};
         const method = (options.method || 'GET').toUpperCase();
 
+        const command = ipcCommandOf(url);
+        // Event traffic carries the plugin's own replies and push events; recording it would loop
+        if (command !== null && command.startsWith('plugin:event|')) {
+            return originalFetch.apply(this, args);
+        }
+
         const requestHeaders: Record<string, string> = {};
         if (options.headers) {
             if (options.headers instanceof Headers) {
@@ -731,10 +1307,25 @@ function interceptFetch(): void {
             }
         }
 
-        const requestBody = options.body ? String(options.body).substring(0, 10000) : undefined;
-        const requestId = recordNetworkRequest(url, method, 'fetch', requestHeaders, requestBody);
+        let requestBody = options.body ? String(options.body).substring(0, 10000) : undefined;
+        if (command !== null) {
+            // Tauri-Invoke-Key authenticates IPC calls and must not end up in logs or HAR files
+            Object.keys(requestHeaders)
+                .filter(key => key.startsWith('tauri-'))
+                .forEach(key => delete requestHeaders[key]);
+            if (options.body instanceof ArrayBuffer || ArrayBuffer.isView(options.body)) {
+                requestBody = `<binary ${options.body.byteLength} bytes>`;
+            }
+        }
+        const requestId = recordNetworkRequest(url, method, command !== null ? 'ipc' : 'fetch', requestHeaders, requestBody);
+        if (command !== null) {
+            const request = findNetworkRequest(requestId);
+            if (request) {
+                request.command = command;
+            }
+        }
 
-        return originalFetch.apply(this, args)
+        const send = () => originalFetch.apply(this, args)
             .then((response: Response) => {
                 const responseHeaders: Record<string, string> = {};
                 response.headers.forEach((value: string, key: string) => {
@@ -745,6 +1336,11 @@ function interceptFetch(): void {
                 const clonedResponse = response.clone();
                 clonedResponse.text()
                     .then((body: string) => {
+                        // A rejected invoke still answers over HTTP, flagged by this header
+                        const request = findNetworkRequest(requestId);
+                        if (request && command !== null && responseHeaders['tauri-response'] === 'error') {
+                            request.error = body.substring(0, 10000);
+                        }
                         updateNetworkResponse(requestId, response.status, responseHeaders, body.substring(0, 10000));
                     })
                     .catch(() => {
@@ -757,8 +1353,34 @@ function interceptFetch(): void {
                 recordNetworkError(requestId, error.message || 'Unknown fetch error');
                 throw error;
             });
+
+        // IPC is never mocked: a failure would make Tauri fall back to postMessage for good
+        const rule = command === null ? findNetworkMockRule(url, method, requestBody) : null;
+        if (!rule) {
+            return send();
+        }
+
+        markMocked(requestId, rule);
+        return delay(rule.latency_ms).then(() => {
+            if (rule.fail !== undefined) {
+                recordNetworkError(requestId, rule.fail);
+                throw new TypeError(rule.fail);
+            }
+            if (!rule.response) {
+                return send();
+            }
+
+            const { status, headers, body } = rule.response;
+            const bytes = mockBodyBytes(rule.response);
+            // These statuses cannot carry a body
+            const response = new Response([204, 205, 304].includes(status) ? null : bytes ?? body ?? '', { status, headers });
+            const recorded = bytes ? `<binary ${bytes.byteLength} bytes>` : body?.substring(0, 10000);
+            updateNetworkResponse(requestId, status, lowerCaseKeys(headers), recorded);
+            return response;
+        });
     };
 
+    fetchHooked = true;
     console.log('TAURI-PLUGIN-MCP: Fetch interceptor installed');
 }
 
@@ -769,15 +1391,50 @@ function interceptXHR(): void {
 
     XMLHttpRequest.prototype.open = function(method: string, url: string, ...args: any[]): void {
         const requestHeaders: Record<string, string> = {};
-        const requestId = recordNetworkRequest(url, method, 'xhr', requestHeaders);
+        const requestId = recordNetworkRequest(String(url), method, 'xhr', requestHeaders);
         xhrRequestMap.set(this, requestId);
         return originalOpen.apply(this, [method, url, ...args]);
     };
 
     XMLHttpRequest.prototype.send = function(body?: any): void {
         const requestId = xhrRequestMap.get(this);
-        const request = requestId ? networkRequests.get(requestId) : null;
+        const request = requestId ? findNetworkRequest(requestId) : null;
+        const rule = request
+            ? findNetworkMockRule(request.url, request.method, body ? String(body) : undefined)
+            : null;
 
+        if (request && requestId && rule) {
+            if (body) {
+                request.request_body = String(body).substring(0, 10000);
+            }
+            markMocked(requestId, rule);
+
+            if (rule.fail !== undefined || rule.response) {
+                const xhr = this;
+                delay(rule.latency_ms).then(() => {
+                    if (rule.fail !== undefined) {
+                        recordNetworkError(requestId, rule.fail);
+                        completeMockedXhr(xhr, 0, {}, '', ['error']);
+                    } else if (rule.response) {
+                        const { status, headers, body: responseBody = '' } = rule.response;
+                        const bytes = mockBodyBytes(rule.response);
+                        const recorded = bytes ? `<binary ${bytes.byteLength} bytes>` : responseBody.substring(0, 10000);
+                        updateNetworkResponse(requestId, status, lowerCaseKeys(headers), recorded);
+                        completeMockedXhr(xhr, status, headers, bytes ? atob(responseBody) : responseBody, ['load'], bytes);
+                    }
+                });
+                return;
+            }
+
+            // Latency only: the real request goes out once the delay has passed
+            delay(rule.latency_ms).then(() => sendXhr.call(this, requestId, request, body));
+            return;
+        }
+
+        return sendXhr.call(this, requestId, request, body);
+    };
+
+    function sendXhr(this: XMLHttpRequest, requestId: string | undefined, request: NetworkRequest | null | undefined, body?: any): void {
         if (request && this.getAllResponseHeaders) {
             // Capture request headers set via setRequestHeader
             // Note: We can't directly access setRequestHeader calls, but we try to get common ones
@@ -827,33 +1484,243 @@ function interceptXHR(): void {
         };
 
         return originalSend.apply(this, [body]);
-    };
+    }
 
+    xhrHooked = true;
     console.log('TAURI-PLUGIN-MCP: XHR interceptor installed');
 }
 
+// Install the fetch and XHR hooks once; capture and mocking both rely on them
+function installNetworkInterceptors(): void {
+    if (!fetchHooked) {
+        interceptFetch();
+    }
+    if (!xhrHooked) {
+        interceptXHR();
+    }
+}
+
+// Network mocking functions
+
+function delay(ms: number | undefined): Promise<void> {
+    return new Promise(resolve => setTimeout(resolve, ms ?? 0));
+}
+
+function lowerCaseKeys(headers: Record<string, string>): Record<string, string> {
+    const result: Record<string, string> = {};
+    Object.entries(headers).forEach(([key, value]) => {
+        result[key.toLowerCase()] = value;
+    });
+    return result;
+}
+
+// Regex match (case-insensitive), falling back to a substring match like the request filter
+function matchesPattern(pattern: string, value: string): boolean {
+    try {
+        return new RegExp(pattern, 'i').test(value);
+    } catch (e) {
+        return value.toLowerCase().includes(pattern.toLowerCase());
+    }
+}
+
+// The most recently added rule that matches wins
+function findNetworkMockRule(url: string, method: string, body?: string): NetworkMockRule | null {
+    for (let i = networkMockRules.length - 1; i >= 0; i--) {
+        const rule = networkMockRules[i];
+        if (rule.method && rule.method !== method.toUpperCase()) {
+            continue;
+        }
+        if (!matchesPattern(rule.url_pattern, url)) {
+            continue;
+        }
+        if (rule.body_pattern && !matchesPattern(rule.body_pattern, body ?? '')) {
+            continue;
+        }
+        return rule;
+    }
+    return null;
+}
+
+// Bytes of a base64 mock body (binary HAR responses), or null for a text body
+function mockBodyBytes(response: NonNullable<NetworkMockRule['response']>): Uint8Array | null {
+    if (response.encoding !== 'base64') {
+        return null;
+    }
+    return Uint8Array.from(atob(response.body ?? ''), c => c.charCodeAt(0));
+}
+
+// Mocked requests are recorded even while capture is stopped
+function markMocked(requestId: string, rule: NetworkMockRule): void {
+    rule.hit_count++;
+    const request = findNetworkRequest(requestId);
+    if (request) {
+        request.mocked = true;
+        request.mock_rule_id = rule.id;
+        if (!networkRequests.has(requestId)) {
+            storeNetworkRequest(request);
+        }
+    }
+}
+
+// Make a mocked XHR look finished to the page and fire the events a real one would
+function completeMockedXhr(
+    xhr: XMLHttpRequest,
+    status: number,
+    headers: Record<string, string>,
+    body: string,
+    events: string[],
+    bytes: Uint8Array | null = null
+): void {
+    const lowerHeaders = lowerCaseKeys(headers);
+    let response: any = body;
+    if (bytes && xhr.responseType === 'arraybuffer') {
+        response = bytes.buffer;
+    } else if (bytes && xhr.responseType === 'blob') {
+        response = new Blob([bytes], { type: lowerHeaders['content-type'] ?? '' });
+    } else if (xhr.responseType === 'json') {
+        try {
+            response = JSON.parse(body);
+        } catch (e) {
+            response = null;
+        }
+    }
+
+    const define = (name: string, value: unknown) =>
+        Object.defineProperty(xhr, name, { configurable: true, get: () => value });
+    define('readyState', 4);
+    define('status', status);
+    define('statusText', '');
+    define('responseText', body);
+    define('response', response);
+    (xhr as any).getAllResponseHeaders = () =>
+        Object.entries(lowerHeaders).map(([key, value]) => `${key}: ${value}`).join('\r\n');
+    (xhr as any).getResponseHeader = (name: string) => lowerHeaders[name.toLowerCase()] ?? null;
+
+    xhr.dispatchEvent(new Event('readystatechange'));
+    events.forEach(type => xhr.dispatchEvent(new ProgressEvent(type)));
+    xhr.dispatchEvent(new ProgressEvent('loadend'));
+}
+
+async function handleNetworkMockRequest(event: any) {
+    console.log('TAURI-PLUGIN-MCP: Received network-mock, payload:', event.payload);
+    const requestId = requestIdOf(event);
+    const currentWindow = getCurrentWebviewWindow();
+
+    try {
+        const { action, rule, rules, ruleId } = event.payload ?? {};
+
+        switch (action) {
+            case 'add': {
+                // A single rule from network_mock, or a batch from a HAR import
+                const added: NetworkMockRule[] = rule ? [rule] : rules ?? [];
+                installNetworkInterceptors();
+                added.forEach(r => networkMockRules.push({ ...r, hit_count: 0 }));
+                break;
+            }
+            case 'remove': {
+                const index = networkMockRules.findIndex(r => r.id === ruleId);
+                if (index < 0) {
+                    throw new Error(`No mock rule with id "${ruleId}"`);
+                }
+                networkMockRules.splice(index, 1);
+                break;
+            }
+            case 'clear':
+                networkMockRules = [];
+                break;
+            case 'list':
+                break;
+            default:
+                throw new Error(`Unknown network mock action: ${action}`);
+        }
+
+        await currentWindow.emit('network-mock-response', {
+            requestId,
+            success: true,
+            data: { rules: networkMockRules },
+        });
+    } catch (error) {
+        console.error('TAURI-PLUGIN-MCP: Error handling network-mock request', error);
+        await currentWindow.emit('network-mock-response', {
+            requestId,
+            success: false,
+            error: String(error),
+        });
+    }
+}
+
 async function handleInjectNetworkCapture(event: any) {
     console.log('TAURI-PLUGIN-MCP: Injecting network capture');
+    const requestId = requestIdOf(event);
 
     try {
         if (!networkCaptureActive) {
-            interceptFetch();
-            interceptXHR();
+            installNetworkInterceptors();
             networkCaptureActive = true;
             console.log('TAURI-PLUGIN-MCP: Network capture activated');
         }
 
         const currentWindow = getCurrentWebviewWindow();
-        await emit('inject-network-capture-response', { success: true });
+        await emit('inject-network-capture-response', { requestId, success: true });
     } catch (error) {
         console.error('TAURI-PLUGIN-MCP: Error injecting network capture', error);
         const currentWindow = getCurrentWebviewWindow();
-        await emit('inject-network-capture-response', { error: String(error) });
+        await emit('inject-network-capture-response', { requestId, error: String(error) });
+    }
+}
+
+function networkCaptureStatus() {
+    return {
+        capture_active: networkCaptureActive,
+        fetch_hooked: fetchHooked,
+        xhr_hooked: xhrHooked,
+        buffer_size: networkRequests.size,
+        max_buffer_size: MAX_REQUESTS,
+        dropped_count: networkDroppedCount,
+    };
+}
+
+// start-network-capture, stop-network-capture, clear-network-requests and
+// get-network-capture-status all answer with the resulting capture status
+async function handleNetworkCaptureControl(event: any) {
+    console.log(`TAURI-PLUGIN-MCP: Received ${event.event}`);
+    const requestId = requestIdOf(event);
+    const currentWindow = getCurrentWebviewWindow();
+
+    try {
+        switch (event.event) {
+            case 'start-network-capture':
+                installNetworkInterceptors();
+                networkCaptureActive = true;
+                break;
+            case 'stop-network-capture':
+                // The hooks stay installed so mocks keep working; only recording stops
+                networkCaptureActive = false;
+                break;
+            case 'clear-network-requests':
+                networkRequests.clear();
+                networkDroppedCount = 0;
+                break;
+        }
+
+        await currentWindow.emit(`${event.event}-response`, {
+            requestId,
+            success: true,
+            data: networkCaptureStatus(),
+        });
+    } catch (error) {
+        console.error(`TAURI-PLUGIN-MCP: Error handling ${event.event}`, error);
+        await currentWindow.emit(`${event.event}-response`, {
+            requestId,
+            success: false,
+            error: String(error),
+        });
     }
 }
 
 async function handleGetNetworkRequestsRequest(event: any) {
     console.log('TAURI-PLUGIN-MCP: Received get-network-requests, payload:', event.payload);
+    const requestId = requestIdOf(event);
 
     try {
         const filter = event.payload || {};
@@ -880,15 +1747,15 @@ async function handleGetNetworkRequestsRequest(event: any) {
         }
 
         // Filter by status code
-        if (filter.status_code !== undefined) {
+        if (filter.status_code != null) {
             requests = requests.filter(r => r.status_code === filter.status_code);
         }
 
         // Filter by duration
-        if (filter.min_duration_ms !== undefined) {
+        if (filter.min_duration_ms != null) {
             requests = requests.filter(r => r.duration_ms && r.duration_ms >= filter.min_duration_ms);
         }
-        if (filter.max_duration_ms !== undefined) {
+        if (filter.max_duration_ms != null) {
             requests = requests.filter(r => r.duration_ms && r.duration_ms <= filter.max_duration_ms);
         }
 
@@ -897,11 +1764,16 @@ async function handleGetNetworkRequestsRequest(event: any) {
             requests = requests.filter(r => r.request_type === filter.request_type);
         }
 
+        // Filter by whether a mock rule answered
+        if (filter.mocked != null) {
+            requests = requests.filter(r => !!r.mocked === filter.mocked);
+        }
+
         // Filter by time range
-        if (filter.start_time_ms !== undefined) {
+        if (filter.start_time_ms != null) {
             requests = requests.filter(r => r.start_time_ms >= filter.start_time_ms);
         }
-        if (filter.end_time_ms !== undefined) {
+        if (filter.end_time_ms != null) {
             requests = requests.filter(r => r.start_time_ms <= filter.end_time_ms);
         }
 
@@ -914,6 +1786,8 @@ async function handleGetNetworkRequestsRequest(event: any) {
 
         const currentWindow = getCurrentWebviewWindow();
         await emit('get-network-requests-response', {
+            requestId,
+            success: true,
             requests: paginatedRequests,
             total_count: requests.length,
             capture_active: networkCaptureActive,
@@ -922,6 +1796,8 @@ async function handleGetNetworkRequestsRequest(event: any) {
         console.error('TAURI-PLUGIN-MCP: Error handling network requests request', error);
         const currentWindow = getCurrentWebviewWindow();
         await emit('get-network-requests-response', {
+            requestId,
+            success: false,
             error: String(error),
             requests: [],
             total_count: 0,
@@ -990,6 +1866,7 @@ function recordException(errorType: string, message: string, stack: string | und
         const existing = exceptions.get(exceptionKey)!;
         existing.frequency += 1;
         existing.last_occurrence_ms = now;
+        pushEvent('exceptions', existing);
     } else {
         // Create new exception entry
         const entry: ExceptionEntry = {
@@ -1004,6 +1881,7 @@ function recordException(errorType: string, message: string, stack: string | und
         };
 
         exceptions.set(exceptionKey, entry);
+        pushEvent('exceptions', entry);
 
         // Enforce circular buffer limit
         if (exceptions.size > circularBufferSize) {
@@ -1046,6 +1924,7 @@ function installErrorTrackers(): void {
 
 async function handleInjectErrorTracker(event: any) {
     console.log('TAURI-PLUGIN-MCP: Injecting error tracker');
+    const requestId = requestIdOf(event);
 
     try {
         const payload = event.payload || {};
@@ -1058,16 +1937,17 @@ async function handleInjectErrorTracker(event: any) {
         }
 
         const currentWindow = getCurrentWebviewWindow();
-        await emit('inject-error-tracker-response', { success: true });
+        await emit('inject-error-tracker-response', { requestId, success: true });
     } catch (error) {
         console.error('TAURI-PLUGIN-MCP: Error injecting error tracker', error);
         const currentWindow = getCurrentWebviewWindow();
-        await emit('inject-error-tracker-response', { error: String(error) });
+        await emit('inject-error-tracker-response', { requestId, error: String(error) });
     }
 }
 
 async function handleGetExceptionsRequest(event: any) {
     console.log('TAURI-PLUGIN-MCP: Received get-exceptions, payload:', event.payload);
+    const requestId = requestIdOf(event);
 
     try {
         const filter = event.payload || {};
@@ -1109,6 +1989,7 @@ async function handleGetExceptionsRequest(event: any) {
 
         const currentWindow = getCurrentWebviewWindow();
         await emit('get-exceptions-response', {
+            requestId,
             exceptions: paginatedExceptions,
             total_count: exceptionList.length,
         });
@@ -1116,6 +1997,7 @@ async function handleGetExceptionsRequest(event: any) {
         console.error('TAURI-PLUGIN-MCP: Error handling exceptions request', error);
         const currentWindow = getCurrentWebviewWindow();
         await emit('get-exceptions-response', {
+            requestId,
             error: String(error),
             exceptions: [],
             total_count: 0,
@@ -1125,21 +2007,23 @@ async function handleGetExceptionsRequest(event: any) {
 
 async function handleClearExceptions(event: any) {
     console.log('TAURI-PLUGIN-MCP: Clearing exceptions');
+    const requestId = requestIdOf(event);
 
     try {
         exceptions.clear();
         const currentWindow = getCurrentWebviewWindow();
-        await emit('clear-exceptions-response', { success: true });
+        await emit('clear-exceptions-response', { requestId, success: true });
         console.log('TAURI-PLUGIN-MCP: Exceptions cleared');
     } catch (error) {
         console.error('TAURI-PLUGIN-MCP: Error clearing exceptions', error);
         const currentWindow = getCurrentWebviewWindow();
-        await emit('clear-exceptions-response', { error: String(error) });
+        await emit('clear-exceptions-response', { requestId, error: String(error) });
     }
 }
 
 async function handleInspectStorageRequest(event: any) {
     console.log('TAURI-PLUGIN-MCP: Received inspect-storage, payload:', event.payload);
+    const requestId = requestIdOf(event);
 
     const currentWindow: WebviewWindow = getCurrentWebviewWindow();
     const { action, storage_type, key_pattern, page, page_size, db_name, store_name } = event.payload;
@@ -1164,10 +2048,11 @@ async function handleInspectStorageRequest(event: any) {
             result.error = `Unknown action or invalid parameters: ${action}`;
         }
 
-        await currentWindow.emit('inspect-storage-response', result);
+        await currentWindow.emit('inspect-storage-response', { ...result, requestId });
     } catch (error) {
         console.error('TAURI-PLUGIN-MCP: Error handling storage inspection:', error);
         await currentWindow.emit('inspect-storage-response', {
+            requestId,
             error: String(error),
             data: null
         });
@@ -1388,3 +2273,447 @@ async function queryIndexedDB(
         throw new Error(`Failed to query IndexedDB ${dbName}/${storeName}: ${error}`);
     }
 }
+
+// Wait conditions
+//
+// The plugin polls these checks until one is satisfied or its timeout expires, so each
+// check answers immediately with the current state of the page.
+
+function isElementVisible(element: HTMLElement): boolean {
+    if (!element.isConnected) {
+        return false;
+    }
+    const style = window.getComputedStyle(element);
+    if (style.visibility === 'hidden' || style.display === 'none' || parseFloat(style.opacity) === 0) {
+        return false;
+    }
+    // Catches hidden ancestors, which computed styles of the element itself do not reflect
+    if (typeof (element as any).checkVisibility === 'function' && !(element as any).checkVisibility()) {
+        return false;
+    }
+    const rect = element.getBoundingClientRect();
+    return rect.width > 0 && rect.height > 0;
+}
+
+// Requests still in flight and how long ago one last started or finished. The interceptors
+// track in-flight requests whether or not capture is on, so installing them is enough.
+function networkActivity() {
+    installNetworkInterceptors();
+    let pending = 0;
+    [networkRequests, untrackedRequests].forEach(requests => requests.forEach(request => {
+        if (request.end_time_ms === undefined) {
+            pending++;
+        }
+    }));
+    return {
+        pending,
+        idle_for_ms: lastNetworkActivityMs > 0 ? Date.now() - lastNetworkActivityMs : null,
+    };
+}
+
+async function checkWaitCondition(payload: any): Promise<{ satisfied: boolean; detail?: unknown }> {
+    const { mode, selectorType = 'css', selector, text, code } = payload;
+    switch (mode) {
+        case 'selector_visible':
+        case 'selector_hidden': {
+            const element = findElement(selectorType, selector);
+            const visible = element !== null && isElementVisible(element);
+            return { satisfied: mode === 'selector_visible' ? visible : !visible, detail: { found: element !== null, visible } };
+        }
+        case 'text': {
+            const content = document.body?.innerText ?? '';
+            return { satisfied: content.includes(text) };
+        }
+        case 'js': {
+            // eslint-disable-next-line no-eval
+            const result = await (0, eval)(code);
+            return { satisfied: Boolean(result), detail: typeof result === 'object' ? JSON.stringify(result) : String(result) };
+        }
+        case 'network_idle': {
+            const activity = networkActivity();
+            return { satisfied: activity.pending === 0, detail: activity };
+        }
+        default:
+            throw new Error(`Unknown wait mode: ${mode}`);
+    }
+}
+
+// Not logged: every wait polls this many times
+async function handleWaitForCheck(event: any) {
+    const requestId = requestIdOf(event);
+    const currentWindow = getCurrentWebviewWindow();
+
+    try {
+        const data = await checkWaitCondition(event.payload);
+        await currentWindow.emit('wait-for-check-response', { requestId, success: true, data });
+    } catch (error) {
+        await currentWindow.emit('wait-for-check-response', {
+            requestId,
+            success: false,
+            error: String(error),
+        });
+    }
+}
+
+// Key presses
+//
+// DOM mode of press_keys: KeyboardEvents are dispatched to the focused element, and the browser's
+// default action is emulated when no handler prevents it, since synthetic events do not trigger it.
+
+interface KeySpec {
+    key: string;
+    code: string;
+}
+
+interface KeyStep {
+    keys: KeySpec[];
+    action: 'press' | 'down' | 'up';
+    repeat: number;
+    delayMs: number;
+}
+
+// Keys held by "down" steps, kept across calls so a later call can release them
+const heldKeys = new Map<string, KeySpec>();
+
+const MODIFIER_FLAGS: Record<string, 'ctrlKey' | 'shiftKey' | 'altKey' | 'metaKey'> = {
+    Control: 'ctrlKey',
+    Shift: 'shiftKey',
+    Alt: 'altKey',
+    Meta: 'metaKey',
+};
+
+function keyTarget(): HTMLElement {
+    return (document.activeElement as HTMLElement | null) ?? document.body;
+}
+
+function dispatchKey(type: 'keydown' | 'keypress' | 'keyup', spec: KeySpec): boolean {
+    const flags = { ctrlKey: false, shiftKey: false, altKey: false, metaKey: false };
+    heldKeys.forEach(held => {
+        const flag = MODIFIER_FLAGS[held.key];
+        if (flag) {
+            flags[flag] = true;
+        }
+    });
+    const key = spec.key.length === 1 && flags.shiftKey ? spec.key.toUpperCase() : spec.key;
+    return keyTarget().dispatchEvent(new KeyboardEvent(type, {
+        key,
+        code: spec.code,
+        repeat: type === 'keydown' && heldKeys.has(spec.key) && !MODIFIER_FLAGS[spec.key],
+        bubbles: true,
+        cancelable: true,
+        composed: true,
+        ...flags,
+    }));
+}
+
+function isTextField(element: HTMLElement): element is HTMLInputElement | HTMLTextAreaElement {
+    if (element instanceof HTMLTextAreaElement) {
+        return !element.readOnly && !element.disabled;
+    }
+    return element instanceof HTMLInputElement
+        && !element.readOnly
+        && !element.disabled
+        && ['text', 'search', 'email', 'url', 'tel', 'password', 'number', ''].includes(element.type);
+}
+
+function insertIntoField(field: HTMLInputElement | HTMLTextAreaElement, text: string, inputType: string) {
+    field.setRangeText(text, field.selectionStart ?? field.value.length, field.selectionEnd ?? field.value.length, 'end');
+    field.dispatchEvent(new InputEvent('input', { bubbles: true, inputType, data: text || null }));
+}
+
+// What the browser would have done for a key that was not prevented
+function defaultKeyAction(spec: KeySpec) {
+    const target = keyTarget();
+    const modified = ['Control', 'Alt', 'Meta'].some(modifier => heldKeys.has(modifier));
+    if (modified) {
+        return;
+    }
+
+    const shifted = heldKeys.has('Shift');
+    if (spec.key.length === 1 || spec.key === 'Enter' && target instanceof HTMLTextAreaElement) {
+        const text = spec.key === 'Enter' ? '\n' : shifted ? spec.key.toUpperCase() : spec.key;
+        if (isTextField(target)) {
+            insertIntoField(target, text, spec.key === 'Enter' ? 'insertLineBreak' : 'insertText');
+            return;
+        }
+        if (target.isContentEditable) {
+            document.execCommand('insertText', false, text);
+            return;
+        }
+    }
+
+    switch (spec.key) {
+        case 'Backspace':
+        case 'Delete':
+            if (isTextField(target)) {
+                let start = target.selectionStart ?? target.value.length;
+                let end = target.selectionEnd ?? start;
+                if (start === end) {
+                    if (spec.key === 'Backspace') {
+                        start = Math.max(0, start - 1);
+                    } else {
+                        end = Math.min(target.value.length, end + 1);
+                    }
+                }
+                target.setRangeText('', start, end, 'end');
+                target.dispatchEvent(new InputEvent('input', {
+                    bubbles: true,
+                    inputType: spec.key === 'Backspace' ? 'deleteContentBackward' : 'deleteContentForward',
+                }));
+            } else if (target.isContentEditable) {
+                document.execCommand(spec.key === 'Backspace' ? 'delete' : 'forwardDelete', false, undefined);
+            }
+            break;
+        case 'Enter':
+            if (target instanceof HTMLInputElement && target.form) {
+                target.form.requestSubmit();
+            } else if (target instanceof HTMLButtonElement || target instanceof HTMLAnchorElement) {
+                target.click();
+            } else if (target.isContentEditable) {
+                document.execCommand('insertParagraph', false, undefined);
+            }
+            break;
+        case ' ':
+            if (target instanceof HTMLButtonElement
+                || target instanceof HTMLInputElement && ['checkbox', 'radio', 'button', 'submit', 'reset'].includes(target.type)) {
+                target.click();
+            }
+            break;
+        case 'Tab':
+            moveFocus(shifted ? -1 : 1);
+            break;
+    }
+}
+
+function moveFocus(direction: number) {
+    const focusable = Array.from(document.querySelectorAll<HTMLElement>(
+        'a[href], button, input, select, textarea, [tabindex], [contenteditable="true"]'
+    )).filter(element => element.tabIndex >= 0 && !(element as HTMLButtonElement).disabled && isElementVisible(element));
+    if (focusable.length === 0) {
+        return;
+    }
+    const index = focusable.indexOf(document.activeElement as HTMLElement);
+    const next = index === -1
+        ? (direction > 0 ? 0 : focusable.length - 1)
+        : (index + direction + focusable.length) % focusable.length;
+    focusable[next].focus();
+}
+
+function keyDown(spec: KeySpec) {
+    const notPrevented = dispatchKey('keydown', spec);
+    heldKeys.set(spec.key, spec);
+    if (notPrevented && spec.key.length === 1) {
+        // keypress is deprecated but still what some older code listens for
+        dispatchKey('keypress', spec);
+    }
+    if (notPrevented) {
+        defaultKeyAction(spec);
+    }
+}
+
+function keyUp(spec: KeySpec) {
+    dispatchKey('keyup', spec);
+    heldKeys.delete(spec.key);
+}
+
+async function pressKeys(steps: KeyStep[]): Promise<number> {
+    const sleep = (ms: number) => ms > 0 ? new Promise(resolve => setTimeout(resolve, ms)) : Promise.resolve();
+    let pressed = 0;
+
+    for (const step of steps) {
+        switch (step.action) {
+            case 'press': {
+                const modifiers = step.keys.slice(0, -1);
+                const last = step.keys[step.keys.length - 1];
+                modifiers.forEach(spec => { keyDown(spec); pressed++; });
+                for (let i = 0; i < step.repeat; i++) {
+                    if (i > 0) {
+                        await sleep(step.delayMs);
+                    }
+                    keyDown(last);
+                    keyUp(last);
+                    pressed++;
+                }
+                modifiers.reverse().forEach(keyUp);
+                break;
+            }
+            case 'down':
+                step.keys.forEach(spec => { keyDown(spec); pressed++; });
+                break;
+            case 'up':
+                step.keys.slice().reverse().forEach(keyUp);
+                break;
+        }
+        await sleep(step.delayMs);
+    }
+    return pressed;
+}
+
+async function handlePressKeysRequest(event: any) {
+    const requestId = requestIdOf(event);
+    const currentWindow = getCurrentWebviewWindow();
+
+    try {
+        const { steps, selectorType, selectorValue } = event.payload;
+        if (selectorValue) {
+            const element = findElement(selectorType, selectorValue);
+            if (!element) {
+                throw new Error(`Element not found: ${selectorType}="${selectorValue}"`);
+            }
+            element.focus();
+        }
+
+        const keysPressed = await pressKeys(steps);
+        console.log(`TAURI-PLUGIN-MCP: Dispatched ${keysPressed} key press(es) to`, keyTarget());
+        await currentWindow.emit('press-keys-response', {
+            requestId,
+            success: true,
+            data: { keysPressed, heldKeys: Array.from(heldKeys.keys()) },
+        });
+    } catch (error) {
+        console.error('TAURI-PLUGIN-MCP: Error pressing keys:', error);
+        await currentWindow.emit('press-keys-response', {
+            requestId,
+            success: false,
+            error: String(error),
+        });
+    }
+}
+
+// Set-of-marks
+//
+// take_annotated_screenshot numbers the visible interactive elements and draws the numbers onto
+// the screenshot. The numbers of the latest annotated screenshot work as selectors
+// (selector_type "mark") until the next one replaces them.
+let markedRefs: string[] = [];
+
+interface MarkedElement {
+  mark: number;
+  ref: string;
+  selector: string;
+  role: string;
+  text: string;
+  rect: { x: number; y: number; width: number; height: number };
+}
+
+function findElementByMark(mark: string): Element | null {
+    const ref = markedRefs[Number(mark) - 1];
+    return ref ? findElementByRef(ref) : null;
+}
+
+function uniqueIdSelector(element: Element): string | null {
+    if (!element.id) {
+        return null;
+    }
+    const selector = `#${CSS.escape(element.id)}`;
+    return document.querySelectorAll(selector).length === 1 ? selector : null;
+}
+
+// CSS selector matching only this element: its ID, or a tag path from the nearest unique ID
+function cssSelectorFor(element: Element): string {
+    const parts: string[] = [];
+    let current: Element | null = element;
+    while (current && current !== document.documentElement) {
+        const idSelector = uniqueIdSelector(current);
+        if (idSelector) {
+            parts.unshift(idSelector);
+            break;
+        }
+        const tag = current.tagName.toLowerCase();
+        const parent: Element | null = current.parentElement;
+        const sameTag = parent ? Array.from(parent.children).filter(child => child.tagName === current!.tagName) : [];
+        parts.unshift(sameTag.length > 1 ? `${tag}:nth-of-type(${sameTag.indexOf(current) + 1})` : tag);
+        current = parent;
+    }
+    return parts.join(' > ');
+}
+
+// Whether any of a few points in the visible part of the element hit the element itself,
+// rather than something covering it such as a modal or a sticky header
+function isUncovered(element: Element, rect: { x: number; y: number; width: number; height: number }): boolean {
+    const root = element.getRootNode() as Document | ShadowRoot;
+    const inset = (size: number) => Math.min(4, size / 4);
+    const points = [
+        [rect.x + rect.width / 2, rect.y + rect.height / 2],
+        [rect.x + inset(rect.width), rect.y + inset(rect.height)],
+        [rect.x + rect.width - inset(rect.width), rect.y + rect.height - inset(rect.height)],
+    ];
+    return points.some(([x, y]) => {
+        const hit = root.elementFromPoint(x, y);
+        return hit !== null && (hit === element || element.contains(hit));
+    });
+}
+
+function collectMarkedElements(maxElements: number): MarkedElement[] {
+    const walk: AccessibilityWalk = {
+        maxDepth: Number.MAX_SAFE_INTEGER,
+        maxNodes: Number.MAX_SAFE_INTEGER,
+        viewportOnly: true,
+        interactiveOnly: true,
+        nodeCount: 0,
+        truncated: false,
+    };
+    const flatten = (nodes: AccessibilityNode[]): AccessibilityNode[] =>
+        nodes.flatMap(node => [node, ...flatten(node.children ?? [])]);
+
+    const marked: MarkedElement[] = [];
+    for (const node of flatten(walkAccessibilityTree(document.body, 0, walk))) {
+        if (marked.length >= maxElements) {
+            break;
+        }
+        const element = findElementByRef(node.ref);
+        if (!element) {
+            continue;
+        }
+        const bounds = element.getBoundingClientRect();
+        // Only the part inside the viewport can be seen, and clicked
+        const left = Math.max(bounds.left, 0);
+        const top = Math.max(bounds.top, 0);
+        const right = Math.min(bounds.right, window.innerWidth);
+        const bottom = Math.min(bounds.bottom, window.innerHeight);
+        if (right - left < 1 || bottom - top < 1) {
+            continue;
+        }
+        const rect = { x: left, y: top, width: right - left, height: bottom - top };
+        if (!isUncovered(element, rect)) {
+            continue;
+        }
+        marked.push({
+            mark: marked.length + 1,
+            ref: node.ref,
+            selector: cssSelectorFor(element),
+            role: node.role,
+            text: node.name || node.value || '',
+            rect,
+        });
+    }
+    return marked;
+}
+
+async function handleInteractiveElementsRequest(event: any) {
+    const requestId = requestIdOf(event);
+    const currentWindow = getCurrentWebviewWindow();
+
+    try {
+        const { maxElements = 200 } = event.payload ?? {};
+        const elements = collectMarkedElements(maxElements);
+        markedRefs = elements.map(element => element.ref);
+
+        await currentWindow.emit('get-interactive-elements-response', {
+            requestId,
+            success: true,
+            data: {
+                url: location.href,
+                viewport: { width: window.innerWidth, height: window.innerHeight },
+                elements,
+            },
+        });
+    } catch (error) {
+        console.error('TAURI-PLUGIN-MCP: Error collecting interactive elements:', error);
+        await currentWindow.emit('get-interactive-elements-response', {
+            requestId,
+            success: false,
+            error: String(error),
+        });
+    }
+}
@@ -0,0 +1,147 @@
+// Compares the parameters of every MCP tool with the JSON Schema the plugin publishes for
+// the socket command behind it (see describe_commands), so the hand-written zod schemas
+// cannot drift from the Rust request types unnoticed. Needs a running app with the plugin:
+//   npm run build && npm run check-schemas
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { ZodRawShape } from "zod";
+import { registerAllTools, socketClient } from "./tools/index.js";
+
+type JsonSchema = { [key: string]: any };
+
+interface CommandDescription {
+  name: string;
+  input_schema: JsonSchema;
+}
+
+// Tools whose command has a different name
+const TOOL_COMMANDS: Record<string, string> = {
+  dump_application_state: "state_dump",
+  query_devtools_hierarchy: "devtools_bridge",
+  devtools_inspect_component: "devtools_bridge",
+  check_devtools_availability: "devtools_bridge",
+};
+
+// Tool parameters that end up somewhere else in the payload, as a path of payload properties
+const nestedUnder = (parent: string[], names: string[]) =>
+  Object.fromEntries(names.map((name) => [name, [...parent, name]]));
+
+const PARAMETER_PATHS: Record<string, Record<string, string[]>> = {
+  network_inspector: nestedUnder(["filter"], [
+    "url_pattern", "method", "status_code", "min_duration_ms", "max_duration_ms",
+    "request_type", "start_time_ms", "end_time_ms", "mocked", "limit",
+  ]),
+  network_mock: {
+    ...nestedUnder(["rule"], ["url_pattern", "method", "body_pattern", "latency_ms", "fail"]),
+    ...nestedUnder(["rule", "response"], ["status", "headers", "body"]),
+  },
+  get_performance_metrics: {
+    ...nestedUnder(["resource_filter"], ["min_duration_ms", "max_duration_ms"]),
+    resource_types: ["resource_filter", "resource_type"],
+  },
+  devtools_inspect_component: { component_name: ["component_filter"] },
+};
+
+// Commands the MCP server deliberately does not offer as tools
+const UNEXPOSED_COMMANDS = new Set(["ping", "hot_reload"]);
+
+// Properties of an object schema, following references and combined schemas
+function propertiesOf(schema: JsonSchema, root: JsonSchema): Map<string, JsonSchema> {
+  const properties = new Map<string, JsonSchema>();
+  const visit = (current: JsonSchema | undefined) => {
+    if (!current || typeof current !== "object") {
+      return;
+    }
+    if (typeof current.$ref === "string") {
+      visit(root.definitions?.[current.$ref.split("/").pop()!]);
+    }
+    for (const [name, property] of Object.entries(current.properties ?? {})) {
+      properties.set(name, property as JsonSchema);
+    }
+    for (const combined of ["allOf", "anyOf", "oneOf"]) {
+      for (const branch of current[combined] ?? []) {
+        visit(branch);
+      }
+    }
+  };
+  visit(schema);
+  return properties;
+}
+
+// Required properties of an object schema. Alternatives (anyOf, oneOf) are not required.
+function requiredOf(schema: JsonSchema, root: JsonSchema): string[] {
+  const required: string[] = [...(schema.required ?? [])];
+  if (typeof schema.$ref === "string") {
+    required.push(...requiredOf(root.definitions?.[schema.$ref.split("/").pop()!] ?? {}, root));
+  }
+  for (const branch of schema.allOf ?? []) {
+    required.push(...requiredOf(branch, root));
+  }
+  return required;
+}
+
+async function main() {
+  const tools: { name: string; shape: ZodRawShape }[] = [];
+  const recorder = {
+    tool(name: string, _description: string, shape: ZodRawShape) {
+      tools.push({ name, shape });
+    },
+  };
+  registerAllTools(recorder as unknown as McpServer);
+
+  await socketClient.connect();
+  const { commands } = (await socketClient.sendCommand("describe_commands", {})) as {
+    commands: CommandDescription[];
+  };
+  const commandsByName = new Map(commands.map((command) => [command.name, command]));
+
+  const problems: string[] = [];
+  const covered = new Set<string>();
+  for (const { name, shape } of tools) {
+    const commandName = TOOL_COMMANDS[name] ?? name;
+    const command = commandsByName.get(commandName);
+    if (!command) {
+      problems.push(`${name}: the plugin has no "${commandName}" command`);
+      continue;
+    }
+    covered.add(commandName);
+
+    const root = command.input_schema;
+    const paths = PARAMETER_PATHS[name] ?? {};
+    for (const parameter of Object.keys(shape)) {
+      const path = paths[parameter] ?? [parameter];
+      let schema: JsonSchema | undefined = root;
+      for (const property of path) {
+        schema = schema && propertiesOf(schema, root).get(property);
+      }
+      if (!schema) {
+        problems.push(`${name}: parameter "${parameter}" is sent as "${path.join(".")}", which ${commandName} does not accept`);
+      }
+    }
+
+    const sent = new Set(Object.keys(shape).map((parameter) => (paths[parameter] ?? [parameter])[0]));
+    for (const property of requiredOf(root, root)) {
+      if (!sent.has(property)) {
+        problems.push(`${name}: ${commandName} requires "${property}", which the tool does not take`);
+      }
+    }
+  }
+
+  // Apps can register their own commands, so these are only reported
+  for (const command of commands) {
+    if (!covered.has(command.name) && !UNEXPOSED_COMMANDS.has(command.name)) {
+      console.warn(`${command.name}: no MCP tool sends this command`);
+    }
+  }
+
+  if (problems.length > 0) {
+    console.error(`Tool schemas differ from the plugin's command schemas:\n  ${problems.join("\n  ")}`);
+    process.exit(1);
+  }
+  console.log(`Checked ${tools.length} tools against ${commands.length} commands`);
+  process.exit(0);
+}
+
+main().catch((error) => {
+  console.error("Schema check failed:", error);
+  process.exit(1);
+});
@@ -20,6 +20,19 @@ export interface TcpConfig {
 
 export type ConnectionConfig = IpcConfig | TcpConfig;
 
+// Token for servers that require authentication, read on every connect so a
+// per-launch token written by a restarted app is picked up
+function readAuthToken(): string | undefined {
+  if (process.env.TAURI_MCP_AUTH_TOKEN) {
+    return process.env.TAURI_MCP_AUTH_TOKEN;
+  }
+  const tokenFile = process.env.TAURI_MCP_AUTH_TOKEN_FILE;
+  if (tokenFile) {
+    return fs.readFileSync(tokenFile, 'utf8').trim();
+  }
+  return undefined;
+}
+
 // Socket client for Tauri IPC/TCP
 export class TauriSocketClient {
   private config: ConnectionConfig;
@@ -79,7 +92,12 @@ export class TauriSocketClient {
           this.handleData(data);
         });
 
-        resolve();
+        // The handshake must be the first line on the connection
+        this.authenticate().then(resolve, (error) => {
+          this.isConnected = false;
+          this.client?.destroy();
+          reject(error);
+        });
       });
 
       this.client!.on('error', (err) => {
@@ -123,6 +141,11 @@ export class TauriSocketClient {
       try {
         const response = JSON.parse(jsonStr);
 
+        // Pushed notifications carry a `type` and never answer a request
+        if (response.type !== undefined) {
+          continue;
+        }
+
         // Process all matching callbacks that might be waiting for this response
         // Rather than just taking the first one, match based on timestamps (oldest first)
         const callbackIds = Array.from(this.responseCallbacks.keys());
@@ -177,6 +200,20 @@ export class TauriSocketClient {
     }
   }
 
+  private async authenticate(): Promise<void> {
+    const token = readAuthToken();
+    if (!token) return;
+
+    try {
+      await this.request('authenticate', { token });
+      console.error('Authenticated with Tauri socket server');
+    } catch (error) {
+      // Don't retry with a token the server rejected
+      this.reconnectAttempts = Number.MAX_SAFE_INTEGER;
+      throw new Error(`Authentication failed: ${(error as Error).message}`);
+    }
+  }
+
   async sendCommand(command: string, payload: Record<string, any> | string = {}): Promise<any> {
     if (!this.isConnected) {
       try {
@@ -186,6 +223,10 @@ export class TauriSocketClient {
       }
     }
 
+    return this.request(command, payload);
+  }
+
+  private request(command: string, payload: Record<string, any> | string): Promise<any> {
     if (!this.client) {
       throw new Error('Socket client not initialized');
     }
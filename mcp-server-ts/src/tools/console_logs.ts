@@ -4,14 +4,16 @@ import { socketClient } from "./client.js";
 import { createErrorResponse, createSuccessResponse, formatResultAsText, logCommandParams } from "./response-helpers.js";
 
 // Define the log levels
-const LOG_LEVELS = ["debug", "info", "warn", "error", "all"] as const;
+const LOG_LEVELS = ["trace", "debug", "info", "warn", "error", "all"] as const;
+const LOG_SOURCES = ["webview", "rust", "all"] as const;
 
 export function registerConsoleLogsTool(server: McpServer) {
   server.tool(
     "get_console_logs",
-    "Retrieves console output messages (console.log, console.error, console.warn, console.info, console.debug) captured from the webview. Allows filtering by log level and time range. Useful for debugging and monitoring runtime behavior of the application.",
+    "Retrieves console output messages (console.log, console.error, console.warn, console.info, console.debug) captured from the webview since the page started; capture is automatic and only needs the app's capability to grant core:event:allow-emit (included in core:default). When the app installs the plugin's log tee, its Rust log records are merged in as well. Allows filtering by log level, source and time range. Useful for debugging and monitoring runtime behavior of the application.",
     {
-      level: z.enum(LOG_LEVELS).optional().describe("Optional. Filter logs by level: 'debug', 'info', 'warn', 'error', or 'all' (default). Use 'all' or omit to get all log levels."),
+      level: z.enum(LOG_LEVELS).optional().describe("Optional. Filter logs by level: 'trace' (Rust records only), 'debug', 'info', 'warn', 'error', or 'all' (default). Use 'all' or omit to get all log levels."),
+      source: z.enum(LOG_SOURCES).optional().describe("Optional. 'webview' for console output, 'rust' for the app's Rust log records, or 'all' (default) for both in time order."),
       start_time_ms: z.number().int().nonnegative().optional().describe("Optional. Only return logs after this Unix timestamp in milliseconds. Use for time range filtering."),
       end_time_ms: z.number().int().nonnegative().optional().describe("Optional. Only return logs before this Unix timestamp in milliseconds. Use for time range filtering."),
       limit: z.number().int().positive().optional().describe("Optional. Maximum number of log entries to return. Defaults to 1000. Use for pagination or limiting output size."),
@@ -24,10 +26,11 @@ export function registerConsoleLogsTool(server: McpServer) {
       idempotentHint: true,
       openWorldHint: false,
     },
-    async ({ level, start_time_ms, end_time_ms, limit, window_label }) => {
+    async ({ level, source, start_time_ms, end_time_ms, limit, window_label }) => {
       try {
         const params = {
           level: level || "all",
+          source: source || "all",
           start_time_ms,
           end_time_ms,
           limit: limit || 1000,
@@ -38,6 +41,7 @@ export function registerConsoleLogsTool(server: McpServer) {
 
         const result = await socketClient.sendCommand('get_console_logs', {
           level: params.level,
+          source: params.source,
           start_time_ms: params.start_time_ms,
           end_time_ms: params.end_time_ms,
           limit: params.limit,
@@ -48,7 +52,7 @@ export function registerConsoleLogsTool(server: McpServer) {
 
         // Format the result as text for display
         if (typeof result === 'object' && result && 'logs' in result) {
-          const logsData = result as { logs: Array<{ timestamp: number; level: string; message: string; args: string[] }>; total_count: number; returned_count: number };
+          const logsData = result as { logs: Array<{ timestamp: number; level: string; message: string; args: string[]; source?: string; target?: string }>; total_count: number; returned_count: number };
 
           if (logsData.logs.length === 0) {
             return createSuccessResponse("No console logs found matching the specified criteria.");
@@ -59,6 +63,9 @@ export function registerConsoleLogsTool(server: McpServer) {
             .map(log => {
               const time = new Date(log.timestamp).toISOString();
               const level = log.level.toUpperCase().padEnd(6);
+              if (log.source === 'rust') {
+                return `[${time}] ${level} [rust ${log.target ?? ''}] ${log.message}`;
+              }
               const args = log.args.length > 0 ? ` ${log.args.join(', ')}` : '';
               return `[${time}] ${level} ${log.message}${args}`;
             })
@@ -80,7 +87,7 @@ export function registerConsoleLogsTool(server: McpServer) {
 export function registerInjectConsoleCaptureTool(server: McpServer) {
   server.tool(
     "inject_console_capture",
-    "Injects the console capture script into the webview to start capturing console.log, console.error, console.warn, console.info, and console.debug calls. This must be called once when the application starts to enable console log retrieval. Subsequent calls re-inject the capture mechanism.",
+    "Kept for compatibility. Console output is captured automatically in every webview from page start, so calling this is not required; it only confirms that the window exists.",
     {
       window_label: z.string().optional().describe("Optional. The identifier of the application window to inject the capture script into. Defaults to 'main' if not specified."),
     },
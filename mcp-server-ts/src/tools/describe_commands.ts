@@ -0,0 +1,32 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse } from "./response-helpers.js";
+
+export function registerDescribeCommandsTool(server: McpServer) {
+  server.tool(
+    "describe_commands",
+    "Lists the plugin's commands with the JSON Schemas of their payloads and responses, as derived from the Rust types. Use this to discover the exact parameters a command accepts and the shape of its result.",
+    {
+      commands: z.array(z.string()).optional().describe("Only describe these commands. All commands are described when omitted."),
+    },
+    {
+      title: "Describe Plugin Commands",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async ({ commands }) => {
+      try {
+        const result = await socketClient.sendCommand("describe_commands", { commands });
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("describe_commands error:", error);
+        return createErrorResponse(
+          `Failed to describe commands: ${(error as Error).message}`
+        );
+      }
+    }
+  );
+}
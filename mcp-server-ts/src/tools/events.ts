@@ -0,0 +1,72 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+const ACTIONS = ["start", "get", "stop", "clear"] as const;
+
+export function registerEmitEventTool(server: McpServer) {
+  server.tool(
+    "emit_event",
+    "Emits a Tauri event with a JSON payload, as if the application had emitted it. Reaches Rust and frontend listeners, either everywhere or only those of one window or webview. Use it to trigger event-driven background flows.",
+    {
+      event: z.string().describe("Event name. Alphanumeric characters, '-', '/', ':' and '_' only."),
+      payload: z.any().optional().describe("Optional. JSON payload delivered with the event. Defaults to null."),
+      target: z.string().optional().describe("Optional. Only deliver to listeners of this window or webview label. Omit to emit globally."),
+    },
+    {
+      title: "Emit Tauri Event in Application",
+      readOnlyHint: false,
+      destructiveHint: false,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async ({ event, payload, target }) => {
+      try {
+        const params = { event, payload: payload ?? null, target };
+
+        logCommandParams("emit_event", params);
+
+        const result = await socketClient.sendCommand("emit_event", params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("emit_event error:", error);
+        return createErrorResponse(`Failed to emit event: ${(error as Error).message}`);
+      }
+    }
+  );
+}
+
+export function registerListenEventsTool(server: McpServer) {
+  server.tool(
+    "listen_events",
+    "Records Tauri events emitted in the application so you can check which events fired and with what payload. 'start' begins recording events whose names match the patterns ('*' is a wildcard), 'get' returns recorded events, 'stop' stops listening and 'clear' empties the buffer. Wildcards only match events the app declared, Tauri's own 'tauri://' events and events sent with emit_event; name other events exactly.",
+    {
+      action: z.enum(ACTIONS).describe("'start', 'get', 'stop' or 'clear'. Every action returns the recording state and the recorded events."),
+      patterns: z.array(z.string()).optional().describe("Required for 'start'. Event names to record, e.g. ['download-progress', 'sync-*', 'tauri://focus']."),
+      since: z.number().int().nonnegative().optional().describe("Optional. Only return events with a sequence number greater than this, to poll for new events."),
+      event: z.string().optional().describe("Optional. Only return events whose name matches this pattern."),
+      limit: z.number().int().positive().optional().describe("Optional. Only return the most recent events, up to this many."),
+    },
+    {
+      title: "Record Tauri Events in Application",
+      readOnlyHint: false,
+      destructiveHint: false,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async ({ action, patterns, since, event, limit }) => {
+      try {
+        const params = { action, patterns, since, event, limit };
+
+        logCommandParams("listen_events", params);
+
+        const result = await socketClient.sendCommand("listen_events", params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("listen_events error:", error);
+        return createErrorResponse(`Failed to ${action} event recording: ${(error as Error).message}`);
+      }
+    }
+  );
+}
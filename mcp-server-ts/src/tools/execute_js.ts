@@ -10,6 +10,7 @@ export function registerExecuteJsTool(server: McpServer) {
     {
       code: z.string().describe("Required. The string of JavaScript code to be executed in the target window's webview context. Ensure the code is safe and achieves the intended purpose. Malformed or malicious code can lead to errors or unwanted behavior."),
       window_label: z.string().default("main").describe("The identifier (e.g., visible title or internal label) of the application window where the JavaScript code will be executed. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Label of a webview inside a multi-webview window. Use list_windows to find it. Defaults to the window's first webview."),
       timeout_ms: z.number().int().positive().optional().describe("The maximum time in milliseconds to allow for the JavaScript execution. If the script exceeds this timeout, its execution will be terminated, and an error may be returned."),
     },
     {
@@ -19,14 +20,14 @@ export function registerExecuteJsTool(server: McpServer) {
       idempotentHint: false,
       openWorldHint: false,
     },
-    async ({ code, window_label, timeout_ms }) => {
+    async ({ code, window_label, webview_label, timeout_ms }) => {
       try {
         // Validate required parameters
         if (!code || code.trim() === '') {
           return createErrorResponse("The code parameter is required and cannot be empty");
         }
         
-        const params = { code, window_label, timeout_ms };
+        const params = { code, window_label, webview_label, timeout_ms };
         logCommandParams('execute_js', params);
         
         // Use default window label if not provided
@@ -35,6 +36,7 @@ export function registerExecuteJsTool(server: McpServer) {
         const result = await socketClient.sendCommand('execute_js', {
           code,
           window_label: effectiveWindowLabel,
+          webview_label,
           timeout_ms
         });
         
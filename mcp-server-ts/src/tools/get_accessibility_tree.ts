@@ -0,0 +1,39 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+export function registerGetAccessibilityTreeTool(server: McpServer) {
+  server.tool(
+    "get_accessibility_tree",
+    "Returns a compact accessibility snapshot of a window's page: each node's role, accessible name, value, states (checked, disabled, expanded, ...) and bounding box in CSS pixels. Every node has a stable ref ID (e.g. 'e12') that get_element_position and send_text_to_element accept with selector_type 'ref'. Prefer this over get_dom to understand a page; use the filters to keep the result small.",
+    {
+      window_label: z.string().default("main").describe("The window to inspect. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Label of a webview inside a multi-webview window."),
+      max_depth: z.number().int().positive().optional().describe("Levels of nodes to return below the root. Unlimited if omitted."),
+      max_nodes: z.number().int().positive().optional().describe("Maximum number of nodes to return (default 1000). The result is marked truncated when nodes were left out."),
+      viewport_only: z.boolean().default(false).describe("Only return nodes that are currently visible in the viewport."),
+      interactive_only: z.boolean().default(false).describe("Only return interactive nodes such as buttons, links and form fields."),
+      root_selector: z.string().optional().describe("CSS selector of the element to snapshot. The whole page body if omitted."),
+    },
+    {
+      title: "Get Accessibility Tree Snapshot",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async (params) => {
+      try {
+        logCommandParams("get_accessibility_tree", params);
+        const result = await socketClient.sendCommand("get_accessibility_tree", params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("get_accessibility_tree error:", error);
+        return createErrorResponse(
+          `Failed to get accessibility tree: ${(error as Error).message}`
+        );
+      }
+    }
+  );
+}
@@ -8,6 +8,7 @@ export function registerGetDomTool(server: McpServer) {
     "Retrieves the full HTML Document Object Model (DOM) content from the specified application window as a string. This tool is read-only and provides a snapshot of the window's current HTML structure. Useful for parsing, analysis, or data extraction.",
     {
       window_label: z.string().default("main").describe("The identifier (e.g., visible title or internal label) of the application window from which to retrieve the DOM content. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Label of a webview inside a multi-webview window. Use list_windows to find it. Defaults to the window's first webview."),
     },
     {
       title: "Retrieve HTML DOM Content from Application Window",
@@ -16,14 +17,18 @@ export function registerGetDomTool(server: McpServer) {
       idempotentHint: true,
       openWorldHint: false,
     },
-    async ({ window_label }) => {
+    async ({ window_label, webview_label }) => {
       try {
         console.error(`Getting DOM with params: ${JSON.stringify({
-          window_label
+          window_label,
+          webview_label
         })}`);
         
-        // The server expects just a string, not an object
-        const result = await socketClient.sendCommand('get_dom', window_label);
+        // A bare window label is enough unless a specific webview is requested
+        const result = await socketClient.sendCommand(
+          'get_dom',
+          webview_label ? { window_label, webview_label } : window_label
+        );
         
         console.error(`Got DOM result type: ${typeof result}, length: ${
           typeof result === 'string' ? result.length : 'unknown'
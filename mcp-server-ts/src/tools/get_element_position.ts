@@ -13,9 +13,9 @@ ${clickInfo}`;
 export function registerGetElementPositionTool(server: McpServer) {
   server.tool(
     "get_element_position",
-    "Finds an HTML element on the page by ID, class, tag name, or text content, and returns its raw position coordinates for use with mouse_movement. Can optionally click the element.",
+    "Finds an HTML element on the page by ID, class, tag name, text content, CSS selector, XPath, or accessibility ref ID, and returns its raw position coordinates for use with mouse_movement. Can optionally click the element.",
     {
-      selector_type: z.enum(["id", "class", "tag", "text"]).describe("The type of selector to use: 'id', 'class', 'tag', or 'text'."),
+      selector_type: z.enum(["id", "class", "tag", "text", "css", "xpath", "ref", "mark"]).describe("The type of selector to use: 'id', 'class', 'tag', 'text', 'css', 'xpath', 'ref' (a ref ID from get_accessibility_tree, e.g. 'e12'), or 'mark' (a number from take_annotated_screenshot, e.g. '17')."),
       selector_value: z.string().describe("The value to search for based on the selector type."),
       window_label: z.string().default("main").describe("The identifier of the application window to search in. Defaults to 'main' if not specified."),
       should_click: z.boolean().default(false).describe("Whether to click the element once found. Default is false."),
@@ -60,6 +60,7 @@ export function registerHealthCheckTool(server: McpServer) {
           webviewStatus: {
             webviewAvailable: boolean;
             mainWindowAvailable: boolean;
+            windowLabels: string[];
           };
         };
 
@@ -86,6 +87,7 @@ export function registerHealthCheckTool(server: McpServer) {
           `Webview Status:`,
           `  - Webview Available: ${healthInfo.webviewStatus.webviewAvailable ? "Yes" : "No"}`,
           `  - Main Window Available: ${healthInfo.webviewStatus.mainWindowAvailable ? "Yes" : "No"}`,
+          `  - Windows: ${healthInfo.webviewStatus.windowLabels.join(", ") || "none"}`,
           ``,
           `Available Capabilities (${healthInfo.capabilities.length} tools):`,
           ...healthInfo.capabilities.map((cap) => `  - ${cap}`),
@@ -2,6 +2,7 @@ import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
 import { registerTakeScreenshotTool } from "./take_screenshot.js";
 import { registerExecuteJsTool } from "./execute_js.js";
 import { registerGetDomTool } from "./get_dom.js";
+import { registerGetAccessibilityTreeTool } from "./get_accessibility_tree.js";
 import { registerManageWindowTool } from "./manage_window.js";
 import { registerManageLocalStorageTool } from "./manage_local_storage.js";
 import { registerTextInputTool } from "./text_input.js";
@@ -10,12 +11,22 @@ import { registerGetElementPositionTool } from "./get_element_position.js";
 import { registerSendTextToElementTool } from "./send_text_to_element.js";
 import { registerConsoleLogsTool, registerInjectConsoleCaptureTool } from "./console_logs.js";
 import { registerNetworkInspectorTool, registerInjectNetworkCaptureTool } from "./network_inspector.js";
+import { registerNetworkMockTool } from "./network_mock.js";
 import { registerStateDumpTool } from "./state_dump.js";
 import { registerDevToolsBridgeTool } from "./devtools_bridge.js";
 import { registerGetExceptionsTool, registerInjectErrorTrackerTool, registerClearExceptionsTool } from "./error_tracker.js";
 import { registerPerformanceMetricsTool } from "./performance.js";
 import { registerStorageInspectorTool } from "./storage_inspector.js";
 import { registerHealthCheckTool } from "./health_check.js";
+import { registerDescribeCommandsTool } from "./describe_commands.js";
+import { registerListWindowsTool } from "./list_windows.js";
+import { registerInvokeCommandTool, registerListCommandsTool } from "./invoke_command.js";
+import { registerEmitEventTool, registerListenEventsTool } from "./events.js";
+import { registerWaitForTool } from "./wait_for.js";
+import { registerPressKeysTool } from "./press_keys.js";
+import { registerMouseActionTool } from "./mouse_action.js";
+import { registerVisualCompareTool } from "./visual_compare.js";
+import { registerTakeAnnotatedScreenshotTool } from "./take_annotated_screenshot.js";
 import { socketClient } from "./client.js";
 
 // Re-export the socket client for direct use
@@ -24,9 +35,12 @@ export { socketClient } from "./client.js";
 // Function to register all tools with a server instance
 export function registerAllTools(server: McpServer) {
   registerHealthCheckTool(server);
+  registerDescribeCommandsTool(server);
+  registerListWindowsTool(server);
   registerTakeScreenshotTool(server);
   registerExecuteJsTool(server);
   registerGetDomTool(server);
+  registerGetAccessibilityTreeTool(server);
   registerManageWindowTool(server);
   registerManageLocalStorageTool(server);
   registerTextInputTool(server);
@@ -37,6 +51,7 @@ export function registerAllTools(server: McpServer) {
   registerInjectConsoleCaptureTool(server);
   registerNetworkInspectorTool(server);
   registerInjectNetworkCaptureTool(server);
+  registerNetworkMockTool(server);
   registerStateDumpTool(server);
   registerDevToolsBridgeTool(server);
   registerGetExceptionsTool(server);
@@ -44,6 +59,15 @@ export function registerAllTools(server: McpServer) {
   registerClearExceptionsTool(server);
   registerPerformanceMetricsTool(server);
   registerStorageInspectorTool(server);
+  registerInvokeCommandTool(server);
+  registerListCommandsTool(server);
+  registerEmitEventTool(server);
+  registerListenEventsTool(server);
+  registerWaitForTool(server);
+  registerPressKeysTool(server);
+  registerMouseActionTool(server);
+  registerVisualCompareTool(server);
+  registerTakeAnnotatedScreenshotTool(server);
 }
 
 // Function to initialize socket connection (can be awaited before registering tools)
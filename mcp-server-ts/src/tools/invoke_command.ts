@@ -0,0 +1,80 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+export function registerInvokeCommandTool(server: McpServer) {
+  server.tool(
+    "invoke_command",
+    "Runs one of the application's Tauri commands (the backend functions the frontend calls with invoke) directly, through the app's real invoke handler, as if called from the chosen webview. The app's capabilities apply: commands the webview is not allowed to call are rejected. Returns the command's result, or the error it rejected with. Use list_commands to see what can be called.",
+    {
+      command: z.string().describe("Command name, e.g. 'greet' for an app command or 'plugin:fs|read_text_file' for a plugin command."),
+      args: z.record(z.any()).optional().describe("Optional. Arguments as a JSON object, keyed exactly like the frontend's invoke call (camelCase by default)."),
+      window_label: z.string().optional().describe("Optional. The identifier of the application window to invoke from. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Optional. Webview to invoke from inside a multi-webview window."),
+      timeout_ms: z.number().int().positive().optional().describe("Optional. How long to wait for the command to finish. Defaults to 10000."),
+    },
+    {
+      title: "Invoke Tauri Command in Application",
+      readOnlyHint: false,
+      destructiveHint: true,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async ({ command, args, window_label, webview_label, timeout_ms }) => {
+      try {
+        const params = {
+          command,
+          args: args ?? {},
+          window_label: window_label || "main",
+          webview_label,
+          timeout_ms,
+        };
+
+        logCommandParams("invoke_command", params);
+
+        const result = await socketClient.sendCommand("invoke_command", params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("invoke_command error:", error);
+        return createErrorResponse(`Command ${command} failed: ${(error as Error).message}`);
+      }
+    }
+  );
+}
+
+export function registerListCommandsTool(server: McpServer) {
+  server.tool(
+    "list_commands",
+    "Lists the application and plugin commands known to the MCP plugin and whether the webview is allowed to call them under the app's capabilities. App commands appear only if the app declared them in the plugin configuration; 'allowed' is null if the app did not provide its ACL.",
+    {
+      filter: z.string().optional().describe("Optional. Only list commands containing this text, e.g. 'fs' or 'plugin:'."),
+      window_label: z.string().optional().describe("Optional. The identifier of the application window. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Optional. Webview to check inside a multi-webview window."),
+    },
+    {
+      title: "List Invokable Tauri Commands",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async ({ filter, window_label, webview_label }) => {
+      try {
+        const params = {
+          filter,
+          window_label: window_label || "main",
+          webview_label,
+        };
+
+        logCommandParams("list_commands", params);
+
+        const result = await socketClient.sendCommand("list_commands", params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("list_commands error:", error);
+        return createErrorResponse(`Failed to list commands: ${(error as Error).message}`);
+      }
+    }
+  );
+}
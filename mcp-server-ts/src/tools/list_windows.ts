@@ -0,0 +1,29 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse } from "./response-helpers.js";
+
+export function registerListWindowsTool(server: McpServer) {
+  server.tool(
+    "list_windows",
+    "Lists the application's windows with their label, title, URL, visibility, focus, bounds, scale factor and webviews. Use the returned labels as window_label (and webview_label for multi-webview windows) in other tools.",
+    {},
+    {
+      title: "List Application Windows",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async () => {
+      try {
+        const result = await socketClient.sendCommand("list_windows", {});
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("list_windows error:", error);
+        return createErrorResponse(
+          `Failed to list windows: ${(error as Error).message}`
+        );
+      }
+    }
+  );
+}
@@ -0,0 +1,72 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+const ACTIONS = ["click", "double_click", "triple_click", "down", "up", "drag", "scroll", "hover"] as const;
+
+const SELECTOR_TYPES = ["id", "class", "tag", "text", "xpath", "css", "ref", "mark"] as const;
+
+export function registerMouseActionTool(server: McpServer) {
+  server.tool(
+    "mouse_action",
+    "Performs real mouse actions in the application window: click, double_click, triple_click (e.g. to select a word or line), down/up to press and release separately, drag from one point or element to another with intermediate moves (for drag-and-drop and sliders), scroll the wheel vertically or horizontally, and hover along a smooth path. Targets are CSS pixel coordinates relative to the webview, or an element found by selector (its center, scrolled into view first). Without a target, the action happens where the mouse is.",
+    {
+      action: z.enum(ACTIONS).describe("Required. What to do."),
+      x: z.number().optional().describe("Optional. Target X in CSS pixels from the webview's left edge. Use together with y."),
+      y: z.number().optional().describe("Optional. Target Y in CSS pixels from the webview's top edge. Use together with x."),
+      selector_value: z.string().optional().describe("Optional. Target the center of this element instead of x/y."),
+      selector_type: z.enum(SELECTOR_TYPES).optional().describe("Optional. How selector_value is interpreted. Defaults to 'css'; 'ref' takes a ref ID from get_accessibility_tree, 'mark' a number from take_annotated_screenshot."),
+      button: z.enum(["left", "right", "middle"]).optional().describe("Optional. Mouse button. Defaults to 'left'."),
+      hold_ms: z.number().int().nonnegative().optional().describe("Optional for 'click'. Keep the button pressed this long (long press)."),
+      to_x: z.number().optional().describe("For 'drag': drop X in CSS pixels."),
+      to_y: z.number().optional().describe("For 'drag': drop Y in CSS pixels."),
+      to_selector_value: z.string().optional().describe("For 'drag': drop on the center of this element instead of to_x/to_y."),
+      to_selector_type: z.enum(SELECTOR_TYPES).optional().describe("Optional. How to_selector_value is interpreted. Defaults to 'css'."),
+      steps: z.number().int().positive().optional().describe("Optional for 'drag' and 'hover'. Intermediate moves along the path. Defaults to 10."),
+      duration_ms: z.number().int().nonnegative().optional().describe("Optional for 'drag' and 'hover'. Time the path takes. Defaults to 300."),
+      delta_y: z.number().int().optional().describe("For 'scroll': wheel clicks, positive scrolls down."),
+      delta_x: z.number().int().optional().describe("For 'scroll': wheel clicks, positive scrolls right."),
+      window_label: z.string().optional().describe("Optional. The identifier of the application window. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Optional. Webview the coordinates and selectors refer to inside a multi-webview window."),
+    },
+    {
+      title: "Perform Mouse Action in Application",
+      readOnlyHint: false,
+      destructiveHint: true,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async ({ action, x, y, selector_value, selector_type, button, hold_ms, to_x, to_y, to_selector_value, to_selector_type, steps, duration_ms, delta_y, delta_x, window_label, webview_label }) => {
+      try {
+        const params = {
+          action,
+          x,
+          y,
+          selector_value,
+          selector_type,
+          button,
+          hold_ms,
+          to_x,
+          to_y,
+          to_selector_value,
+          to_selector_type,
+          steps,
+          duration_ms,
+          delta_y,
+          delta_x,
+          window_label: window_label || "main",
+          webview_label,
+        };
+
+        logCommandParams("mouse_action", params);
+
+        const result = await socketClient.sendCommand("mouse_action", params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("mouse_action error:", error);
+        return createErrorResponse(`Mouse ${action} failed: ${(error as Error).message}`);
+      }
+    }
+  );
+}
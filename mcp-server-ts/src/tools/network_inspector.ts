@@ -7,28 +7,30 @@ import { createErrorResponse, createSuccessResponse, formatResultAsText, logComm
 const HTTP_METHODS = ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "TRACE", "CONNECT"] as const;
 
 // Define request types
-const REQUEST_TYPES = ["fetch", "xhr"] as const;
+const REQUEST_TYPES = ["fetch", "xhr", "ipc"] as const;
 
 // Define actions
-const ACTIONS = ["get_requests", "clear_requests", "start_capture", "stop_capture"] as const;
+const ACTIONS = ["get_requests", "clear_requests", "start_capture", "stop_capture", "get_capture_status", "export_har", "import_har"] as const;
 
 export function registerNetworkInspectorTool(server: McpServer) {
   server.tool(
     "network_inspector",
-    "Inspects and retrieves network requests (fetch/XHR) made by the application. Allows querying request/response headers, bodies, status codes, and timing information. Useful for debugging API integration issues and understanding network behavior.",
+    "Inspects and retrieves network requests (fetch/XHR) and Tauri invoke() calls made by the application. invoke() calls are seen through the fetch hook on Tauri's IPC protocol, so calls Tauri sends over its postMessage fallback, and commands run through invoke_command, are not recorded. Allows querying request/response headers, bodies, status codes, and timing information. Can export the captured requests as a HAR 1.2 file and import a HAR file as replay mocks so a recorded session runs offline. Useful for debugging API integration issues and understanding network behavior.",
     {
       action: z.enum(ACTIONS).describe(
-        "The action to perform: 'get_requests' to retrieve captured requests, 'clear_requests' to clear the capture buffer, 'start_capture' to start capturing, or 'stop_capture' to stop capturing."
+        "The action to perform: 'get_requests' to retrieve captured requests, 'clear_requests' to clear the capture buffer, 'start_capture' to start capturing, 'stop_capture' to stop capturing, 'get_capture_status' to report whether capture is on, the buffer size, dropped entries and which APIs are hooked, 'export_har' to export the matching requests as HAR 1.2, or 'import_har' to replay a HAR file with network mocks."
       ),
       url_pattern: z.string().optional().describe("Optional. Filter requests by URL pattern (regex or substring match). Use to focus on specific endpoints."),
       method: z.enum(HTTP_METHODS).optional().describe("Optional. Filter requests by HTTP method (GET, POST, PUT, DELETE, PATCH, HEAD, OPTIONS, TRACE, CONNECT)."),
       status_code: z.number().int().min(100).max(599).optional().describe("Optional. Filter requests by HTTP status code (e.g., 200, 404, 500)."),
       min_duration_ms: z.number().int().nonnegative().optional().describe("Optional. Only return requests that took at least this many milliseconds."),
       max_duration_ms: z.number().int().nonnegative().optional().describe("Optional. Only return requests that took at most this many milliseconds."),
-      request_type: z.enum(REQUEST_TYPES).optional().describe("Optional. Filter by request type: 'fetch' for Fetch API or 'xhr' for XMLHttpRequest."),
+      request_type: z.enum(REQUEST_TYPES).optional().describe("Optional. Filter by request type: 'fetch' for Fetch API, 'xhr' for XMLHttpRequest, or 'ipc' for Tauri invoke() calls (command in url and command, arguments in request body, result or error in response body). Calls over the postMessage fallback and invoke_command calls are missing."),
       start_time_ms: z.number().int().nonnegative().optional().describe("Optional. Only return requests that started after this Unix timestamp in milliseconds."),
       end_time_ms: z.number().int().nonnegative().optional().describe("Optional. Only return requests that started before this Unix timestamp in milliseconds."),
       limit: z.number().int().positive().optional().describe("Optional. Maximum number of requests to return. Defaults to 100. Use for pagination."),
+      mocked: z.boolean().optional().describe("Optional. true for requests answered, delayed or failed by a network_mock rule only, false for real requests only."),
+      path: z.string().optional().describe("Optional. For 'export_har', the file to write (the HAR is returned inline if omitted). Required for 'import_har': the HAR file to load."),
       window_label: z.string().optional().describe("Optional. The identifier of the application window to inspect. Defaults to 'main' if not specified."),
     },
     {
@@ -49,12 +51,15 @@ export function registerNetworkInspectorTool(server: McpServer) {
       start_time_ms,
       end_time_ms,
       limit,
+      mocked,
+      path,
       window_label,
     }) => {
       try {
         const params = {
           action,
           window_label: window_label || "main",
+          path,
           filter: {
             url_pattern,
             method,
@@ -64,6 +69,7 @@ export function registerNetworkInspectorTool(server: McpServer) {
             request_type,
             start_time_ms,
             end_time_ms,
+            mocked,
             limit: limit || 100,
           },
         };
@@ -74,6 +80,25 @@ export function registerNetworkInspectorTool(server: McpServer) {
 
         console.error(`Got network inspector result: ${typeof result}`);
 
+        // Capture control actions answer with the state the webview ended up in
+        if (typeof result === "object" && result && "status" in result) {
+          const { status } = result as {
+            status: {
+              capture_active: boolean;
+              fetch_hooked: boolean;
+              xhr_hooked: boolean;
+              buffer_size: number;
+              max_buffer_size: number;
+              dropped_count: number;
+            };
+          };
+          return createSuccessResponse(
+            `Network capture: ${status.capture_active ? "ACTIVE" : "INACTIVE"}\n` +
+              `Hooks: fetch ${status.fetch_hooked ? "yes" : "no"}, XHR ${status.xhr_hooked ? "yes" : "no"}\n` +
+              `Buffer: ${status.buffer_size} of ${status.max_buffer_size} requests, ${status.dropped_count} dropped`
+          );
+        }
+
         // Format the result for display
         if (typeof result === "object" && result && "requests" in result) {
           const requestsData = result as {
@@ -91,6 +116,9 @@ export function registerNetworkInspectorTool(server: McpServer) {
               start_time_ms: number;
               end_time_ms?: number;
               duration_ms?: number;
+              mocked?: boolean;
+              mock_rule_id?: string;
+              command?: string;
             }>;
             total_count: number;
             returned_count: number;
@@ -107,9 +135,11 @@ export function registerNetworkInspectorTool(server: McpServer) {
               const time = new Date(req.start_time_ms).toISOString();
               const duration = req.duration_ms ? `${req.duration_ms}ms` : "pending";
               const status = req.status_code ? `[${req.status_code}]` : "[pending]";
-              const type = req.request_type.toUpperCase();
+              const type = req.request_type.toUpperCase() + (req.mocked ? `, MOCKED by ${req.mock_rule_id}` : "");
 
-              let summary = `[${time}] ${req.method.padEnd(7)} ${status} (${type}, ${duration}) ${req.url}`;
+              let summary = req.command
+                ? `[${time}] INVOKE  ${status} (${type}, ${duration}) ${req.command}`
+                : `[${time}] ${req.method.padEnd(7)} ${status} (${type}, ${duration}) ${req.url}`;
 
               if (req.error) {
                 summary += `\n    ERROR: ${req.error}`;
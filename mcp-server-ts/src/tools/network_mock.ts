@@ -0,0 +1,67 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+const HTTP_METHODS = ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "TRACE", "CONNECT"] as const;
+
+const ACTIONS = ["add", "list", "remove", "clear"] as const;
+
+export function registerNetworkMockTool(server: McpServer) {
+  server.tool(
+    "network_mock",
+    "Installs rules that intercept fetch/XHR requests in the webview to force error states: answer with a canned status, headers and body, add latency, or fail the request like a network error (offline mode). Rules match on URL pattern, method and optionally request body; the newest matching rule wins. Mocked requests show up in network_inspector with mocked: true. Rules are dropped when the page reloads.",
+    {
+      action: z.enum(ACTIONS).describe(
+        "'add' to install a rule, 'list' to show active rules with their hit counts, 'remove' to delete one rule by rule_id, or 'clear' to delete all rules."
+      ),
+      url_pattern: z.string().optional().describe("Required for 'add'. Regex (case-insensitive) or substring the request URL must match."),
+      method: z.enum(HTTP_METHODS).optional().describe("Optional. Only match this HTTP method."),
+      body_pattern: z.string().optional().describe("Optional. Regex (case-insensitive) or substring the request body must match."),
+      status: z.number().int().min(200).max(599).optional().describe("Optional. Answer with this HTTP status (a canned response is returned if status, headers or body is set)."),
+      headers: z.record(z.string()).optional().describe("Optional. Response headers of the canned response."),
+      body: z.string().optional().describe("Optional. Response body of the canned response."),
+      latency_ms: z.number().int().nonnegative().optional().describe("Optional. Delay before answering. On its own, delays the real request."),
+      fail: z.string().optional().describe("Optional. Fail the request like a network error with this message, e.g. 'Failed to fetch'. Cannot be combined with a canned response."),
+      rule_id: z.string().optional().describe("Required for 'remove'. ID returned by 'add' or 'list'."),
+      window_label: z.string().optional().describe("Optional. The identifier of the application window. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Optional. Webview to use inside a multi-webview window."),
+    },
+    {
+      title: "Mock Network Requests in Application",
+      readOnlyHint: false,
+      destructiveHint: false,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async ({ action, url_pattern, method, body_pattern, status, headers, body, latency_ms, fail, rule_id, window_label, webview_label }) => {
+      try {
+        const hasResponse = status !== undefined || headers !== undefined || body !== undefined;
+        const params = {
+          action,
+          window_label: window_label || "main",
+          webview_label,
+          rule_id,
+          rule: action === "add"
+            ? {
+                url_pattern: url_pattern ?? "",
+                method,
+                body_pattern,
+                response: hasResponse ? { status: status ?? 200, headers: headers ?? {}, body } : undefined,
+                latency_ms,
+                fail,
+              }
+            : undefined,
+        };
+
+        logCommandParams("network_mock", params);
+
+        const result = await socketClient.sendCommand("network_mock", params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("network_mock error:", error);
+        return createErrorResponse(`Failed to update network mocks: ${(error as Error).message}`);
+      }
+    }
+  );
+}
@@ -0,0 +1,62 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+const SELECTOR_TYPES = ["id", "class", "tag", "text", "xpath", "css", "ref", "mark"] as const;
+
+const keyStepSchema = z.union([
+  z.string(),
+  z.object({
+    chord: z.string().describe("Keys joined with '+', e.g. 'Control+Shift+P', 'Enter' or 'a'."),
+    action: z.enum(["press", "down", "up"]).optional().describe("'press' (default) presses and releases, 'down' holds the keys, 'up' releases them."),
+    repeat: z.number().int().positive().optional().describe("Press the chord's last key this many times while its modifiers are held. Defaults to 1."),
+    delay_ms: z.number().int().nonnegative().optional().describe("Pause after this step, overriding the request's delay_ms."),
+  }),
+]);
+
+export function registerPressKeysTool(server: McpServer) {
+  server.tool(
+    "press_keys",
+    "Presses keys and keyboard shortcuts that simulate_text_input cannot type: chords like 'Control+Shift+P' or 'CmdOrCtrl+S' (Command on macOS, Control elsewhere), Enter, Escape, Tab, arrows, Home/End, PageUp/PageDown, F1-F20. Steps run in order; each can hold keys down, release them, or repeat a key. In 'os' mode real key presses go to the focused window; 'dom' mode dispatches KeyboardEvents in the webview instead, which works headless and without window focus.",
+    {
+      keys: z.array(keyStepSchema).min(1).describe("Required. Chords pressed one after another, e.g. ['Control+A', 'Backspace'] or [{ chord: 'ArrowDown', repeat: 3 }, 'Enter']. Write the plus key as '+' or 'Control++'."),
+      mode: z.enum(["os", "dom"]).optional().describe("Optional. 'os' (default) sends real key presses; 'dom' dispatches KeyboardEvents to the focused element of the webview."),
+      delay_ms: z.number().int().nonnegative().optional().describe("Optional. Pause after each step and between repeats. Defaults to 50."),
+      initial_delay_ms: z.number().int().nonnegative().optional().describe("Optional. For 'os': pause after focusing the window before the first key. Defaults to 200."),
+      selector_value: z.string().optional().describe("Optional. For 'dom': focus this element before pressing keys."),
+      selector_type: z.enum(SELECTOR_TYPES).optional().describe("Optional. How selector_value is interpreted. Defaults to 'css'; 'ref' takes a ref ID from get_accessibility_tree, 'mark' a number from take_annotated_screenshot."),
+      window_label: z.string().optional().describe("Optional. The identifier of the application window. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Optional. Webview to send DOM key events to inside a multi-webview window."),
+    },
+    {
+      title: "Press Keys and Shortcuts in Application",
+      readOnlyHint: false,
+      destructiveHint: true,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async ({ keys, mode, delay_ms, initial_delay_ms, selector_value, selector_type, window_label, webview_label }) => {
+      try {
+        const params = {
+          keys,
+          mode,
+          delay_ms,
+          initial_delay_ms,
+          selector_value,
+          selector_type,
+          window_label: window_label || "main",
+          webview_label,
+        };
+
+        logCommandParams("press_keys", params);
+
+        const result = await socketClient.sendCommand("press_keys", params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("press_keys error:", error);
+        return createErrorResponse(`Failed to press keys: ${(error as Error).message}`);
+      }
+    }
+  );
+}
@@ -7,7 +7,7 @@ export function registerSendTextToElementTool(server: McpServer) {
     "send_text_to_element",
     "Finds an HTML element by selector and sends text input to it, suitable for inputs, textareas, and contentEditable elements. Note: While this tool updates DOM content, it may not trigger React state updates in applications using React - visual changes appear but application state may not reflect the changes.",
     {
-      selector_type: z.enum(["id", "class", "tag", "text"]).describe("The type of selector to use: 'id', 'class', 'tag', or 'text'."),
+      selector_type: z.enum(["id", "class", "tag", "text", "css", "xpath", "ref", "mark"]).describe("The type of selector to use: 'id', 'class', 'tag', 'text', 'css', 'xpath', 'ref' (a ref ID from get_accessibility_tree, e.g. 'e12'), or 'mark' (a number from take_annotated_screenshot, e.g. '17')."),
       selector_value: z.string().describe("The value to search for based on the selector type."),
       text: z.string().describe("The text to input into the element."),
       window_label: z.string().default("main").describe("The identifier of the application window to search in. Defaults to 'main' if not specified."),
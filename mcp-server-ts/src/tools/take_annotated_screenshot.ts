@@ -0,0 +1,62 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createImageResponse, extractBase64Data, logCommandParams } from "./response-helpers.js";
+
+export function registerTakeAnnotatedScreenshotTool(server: McpServer) {
+  server.tool(
+    "take_annotated_screenshot",
+    "Captures the application webview with a numbered box drawn around every visible interactive element (buttons, links, form fields, focusable elements), and returns the image with a JSON legend mapping each number to the element's selector, role, text and rect. Use the numbers to act on what you see: pass selector_type 'mark' and the number as selector_value to mouse_action, get_element_position, send_text_to_element and the other element tools, e.g. mouse_action { action: 'click', selector_type: 'mark', selector_value: '17' }. Numbers stay valid until the next annotated screenshot.",
+    {
+      window_label: z.string().optional().describe("Optional. The identifier of the application window. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Optional. Webview to capture inside a multi-webview window."),
+      max_elements: z.number().int().positive().optional().describe("Optional. Number at most this many elements, in document order. Defaults to 200."),
+      format: z.enum(["jpeg", "png", "webp"]).optional().describe("Optional. Image encoding. Defaults to 'jpeg'."),
+      quality: z.number().int().min(1).max(100).optional().describe("Optional. JPEG quality. Defaults to 85."),
+      max_width: z.number().int().positive().optional().describe("Optional. Downscale wider images to this width in pixels. Defaults to 1920."),
+      max_size_mb: z.number().positive().optional().describe("Optional. Shrink the encoded image until it fits this size. Defaults to 2."),
+      size_fallback: z.enum(["quality_first", "downscale_first"]).optional().describe("Optional. For JPEG over max_size_mb: lower the quality before downscaling ('quality_first', default) or downscale first ('downscale_first')."),
+      timeout_ms: z.number().int().positive().optional().describe("Optional. How long to wait for the page to list its elements. Defaults to 10000."),
+    },
+    {
+      title: "Capture Annotated Screenshot with Numbered Elements",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async ({ window_label, webview_label, max_elements, format, quality, max_width, max_size_mb, size_fallback, timeout_ms }) => {
+      try {
+        const params = {
+          window_label: window_label || "main",
+          webview_label,
+          max_elements,
+          format,
+          quality,
+          max_width,
+          max_size_mb,
+          size_fallback,
+          timeout_ms,
+        };
+
+        logCommandParams("take_annotated_screenshot", params);
+
+        const result = await socketClient.sendCommand("take_annotated_screenshot", params);
+        const base64Data = extractBase64Data(result);
+        if (!base64Data) {
+          return createErrorResponse(`Failed to extract image data from response: ${JSON.stringify(result).substring(0, 100)}...`);
+        }
+
+        const { url, viewport, elements } = result as Record<string, any>;
+        const response = createImageResponse(base64Data, (result as Record<string, any>)?.mimeType ?? "image/jpeg");
+        return {
+          ...response,
+          content: [...response.content, { type: "text" as const, text: JSON.stringify({ url, viewport, elements }, null, 2) }],
+        };
+      } catch (error) {
+        console.error("take_annotated_screenshot error:", error);
+        return createErrorResponse(`Failed to take annotated screenshot: ${(error as Error).message}`);
+      }
+    }
+  );
+}
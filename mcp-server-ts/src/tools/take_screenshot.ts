@@ -6,9 +6,22 @@ import { createErrorResponse, createImageResponse, extractBase64Data, logCommand
 export function registerTakeScreenshotTool(server: McpServer) {
   server.tool(
     "take_screenshot",
-    "Captures a still image (screenshot) of a designated application window and returns it, typically as a JPEG image. This tool is read-only and does not modify any application or system state. Useful for visual inspection or documentation.",
+    "Captures a still image (screenshot) of a designated application window, or of one element or region of it, and returns it as a JPEG, or as a lossless PNG or WebP image. This tool is read-only and does not modify any application or system state. Useful for visual inspection or documentation.",
     {
       window_label: z.string().default("main").describe("The identifier for the window to capture. This could be the window's visible title text or a unique internal label if available. Ensure this label accurately targets the desired window. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Label of a webview inside a multi-webview window. Use list_windows to find it. Defaults to the window's first webview."),
+      selector_value: z.string().optional().describe("Optional. Capture only this element, scrolled into view first. Much smaller than a whole-window image."),
+      selector_type: z.enum(["id", "class", "tag", "text", "xpath", "css", "ref", "mark"]).optional().describe("Optional. How selector_value is interpreted. Defaults to 'css'; 'ref' takes a ref ID from get_accessibility_tree, 'mark' a number from take_annotated_screenshot."),
+      x: z.number().optional().describe("Optional. Left edge of a region to capture, in CSS pixels from the webview's left edge. Use with y, width and height."),
+      y: z.number().optional().describe("Optional. Top edge of the region, in CSS pixels."),
+      width: z.number().positive().optional().describe("Optional. Width of the region, in CSS pixels."),
+      height: z.number().positive().optional().describe("Optional. Height of the region, in CSS pixels."),
+      padding: z.number().nonnegative().optional().describe("Optional. CSS pixels of surrounding context to include on every side of the element or region."),
+      format: z.enum(["jpeg", "png", "webp"]).optional().describe("Optional. Image encoding. Defaults to 'jpeg'. 'png' and 'webp' are lossless: use them for crisp text and pixel comparisons."),
+      quality: z.number().int().min(1).max(100).optional().describe("Optional. JPEG quality. Defaults to 85."),
+      max_width: z.number().int().positive().optional().describe("Optional. Downscale wider images to this width in pixels. Defaults to 1920."),
+      max_size_mb: z.number().positive().optional().describe("Optional. Shrink the encoded image until it fits this size. Defaults to 2."),
+      size_fallback: z.enum(["quality_first", "downscale_first"]).optional().describe("Optional. For JPEG over max_size_mb: lower the quality before downscaling ('quality_first', default) or downscale first and keep text sharp ('downscale_first'). Lossless formats only downscale."),
     },
     {
       title: "Capture Screenshot of a Specific Application Window",
@@ -17,7 +30,7 @@ export function registerTakeScreenshotTool(server: McpServer) {
       idempotentHint: true,
       openWorldHint: false,
     },
-    async ({ window_label }) => {
+    async ({ window_label, webview_label, selector_value, selector_type, x, y, width, height, padding, format, quality, max_width, max_size_mb, size_fallback }) => {
       try {
         // The window_label now has a default value in the schema, so this check is redundant
         // But we'll keep it for extra safety
@@ -25,7 +38,22 @@ export function registerTakeScreenshotTool(server: McpServer) {
           window_label = "main";
         }
         
-        const params = { window_label };
+        const params = {
+          window_label,
+          webview_label,
+          selector_value,
+          selector_type,
+          x,
+          y,
+          width,
+          height,
+          padding,
+          format,
+          quality,
+          max_width,
+          max_size_mb,
+          size_fallback,
+        };
         logCommandParams('take_screenshot', params);
         
         const result = await socketClient.sendCommand('take_screenshot', params);
@@ -40,7 +68,17 @@ export function registerTakeScreenshotTool(server: McpServer) {
           return createErrorResponse(`Failed to extract image data from response: ${JSON.stringify(result).substring(0, 100)}...`);
         }
         
-        return createImageResponse(base64Data, 'image/jpeg');
+        const mimeType = (result as Record<string, any>)?.mimeType ?? 'image/jpeg';
+        const response = createImageResponse(base64Data, mimeType);
+        const region = (result as Record<string, any>)?.region;
+        if (region) {
+          // Lets the caller map points in the image back to the page
+          return {
+            ...response,
+            content: [...response.content, { type: "text" as const, text: JSON.stringify({ region }, null, 2) }],
+          };
+        }
+        return response;
       } catch (error) {
         console.error('Screenshot error:', error);
         return createErrorResponse(`Failed to take screenshot: ${(error as Error).message}`);
@@ -0,0 +1,80 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+const pixelRect = z.object({
+  x: z.number().int().nonnegative(),
+  y: z.number().int().nonnegative(),
+  width: z.number().int().positive(),
+  height: z.number().int().positive(),
+});
+
+export function registerVisualCompareTool(server: McpServer) {
+  server.tool(
+    "visual_compare",
+    "Captures a window, element or region as a lossless image and compares it pixel by pixel with a named baseline PNG stored by the app. Returns whether it matches, the mismatch percentage, bounding boxes of changed regions and a diff image with changes in red and ignored anti-aliasing in yellow. The first run, or a run with update_baseline, saves the capture as the baseline instead. Use it to catch visual regressions after a change.",
+    {
+      name: z.string().describe("Required. Baseline name, e.g. 'login-form'. Letters, digits, '-', '_' and '.', not ending in '.diff' or '.actual'; stored as <name>.png in the baseline directory."),
+      window_label: z.string().optional().describe("Optional. The identifier of the application window. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Optional. Webview to capture inside a multi-webview window."),
+      selector_value: z.string().optional().describe("Optional. Compare only this element, scrolled into view first."),
+      selector_type: z.enum(["id", "class", "tag", "text", "xpath", "css", "ref", "mark"]).optional().describe("Optional. How selector_value is interpreted. Defaults to 'css'; 'ref' takes a ref ID from get_accessibility_tree, 'mark' a number from take_annotated_screenshot."),
+      x: z.number().optional().describe("Optional. Left edge of a region to compare, in CSS pixels. Use with y, width and height."),
+      y: z.number().optional().describe("Optional. Top edge of the region, in CSS pixels."),
+      width: z.number().positive().optional().describe("Optional. Width of the region, in CSS pixels."),
+      height: z.number().positive().optional().describe("Optional. Height of the region, in CSS pixels."),
+      padding: z.number().nonnegative().optional().describe("Optional. CSS pixels of surrounding context to include on every side of the element or region."),
+      threshold: z.number().min(0).max(1).optional().describe("Optional. Color difference a pixel may have before it counts as changed, from 0 (exact) to 1. Defaults to 0.1."),
+      ignore_antialiasing: z.boolean().optional().describe("Optional. Ignore differences on anti-aliased edges, which vary between renders. Defaults to true."),
+      ignore_regions: z.array(pixelRect).optional().describe("Optional. Areas to leave out, such as clocks or animations, in pixels of the compared image (the same space as changed_regions)."),
+      max_mismatch_percentage: z.number().min(0).max(100).optional().describe("Optional. Highest mismatch percentage that still counts as a match. Defaults to 0."),
+      update_baseline: z.boolean().optional().describe("Optional. Save the capture as the new baseline instead of comparing."),
+    },
+    {
+      title: "Compare Application Window with a Baseline Image",
+      readOnlyHint: false,
+      destructiveHint: false,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async ({ name, window_label, webview_label, selector_value, selector_type, x, y, width, height, padding, threshold, ignore_antialiasing, ignore_regions, max_mismatch_percentage, update_baseline }) => {
+      try {
+        const params = {
+          name,
+          window_label: window_label || "main",
+          webview_label,
+          selector_value,
+          selector_type,
+          x,
+          y,
+          width,
+          height,
+          padding,
+          threshold,
+          ignore_antialiasing,
+          ignore_regions,
+          max_mismatch_percentage,
+          update_baseline,
+        };
+
+        logCommandParams("visual_compare", params);
+
+        const result = await socketClient.sendCommand("visual_compare", params);
+        const { diff_image, ...summary } = (result ?? {}) as Record<string, any>;
+        const response = createSuccessResponse(JSON.stringify(summary, null, 2));
+        const base64Data = typeof diff_image === "string" ? diff_image.split(",").pop() : undefined;
+        if (base64Data) {
+          return {
+            ...response,
+            content: [...response.content, { type: "image" as const, data: base64Data, mimeType: "image/png" }],
+          };
+        }
+        return response;
+      } catch (error) {
+        console.error("visual_compare error:", error);
+        return createErrorResponse(`Visual comparison failed: ${(error as Error).message}`);
+      }
+    }
+  );
+}
@@ -0,0 +1,60 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+const MODES = ["selector_visible", "selector_hidden", "text", "url_change", "network_idle", "js"] as const;
+
+const SELECTOR_TYPES = ["id", "class", "tag", "text", "xpath", "css", "ref", "mark"] as const;
+
+export function registerWaitForTool(server: McpServer) {
+  server.tool(
+    "wait_for",
+    "Waits until a condition holds in the webview instead of sleeping and retrying: an element becomes visible or hidden, text appears on the page, the URL changes, the network goes idle (no fetch/XHR/invoke in flight for idle_ms), or a JavaScript expression becomes truthy. Returns how long it waited; fails with a timeout error if the condition does not hold in time.",
+    {
+      mode: z.enum(MODES).describe("Condition to wait for."),
+      selector_value: z.string().optional().describe("Required for 'selector_visible' and 'selector_hidden'. The element to wait for."),
+      selector_type: z.enum(SELECTOR_TYPES).optional().describe("Optional. How selector_value is interpreted. Defaults to 'css'; 'ref' takes a ref ID from get_accessibility_tree, 'mark' a number from take_annotated_screenshot."),
+      text: z.string().optional().describe("Required for 'text'. Text that must appear in the page's visible text."),
+      url_contains: z.string().optional().describe("Optional for 'url_change'. Wait until the URL contains this text instead of for any change."),
+      idle_ms: z.number().int().nonnegative().optional().describe("Optional for 'network_idle'. How long no request may start or finish. Defaults to 500."),
+      code: z.string().optional().describe("Required for 'js'. JavaScript expression that must become truthy, e.g. 'window.appReady === true'. Promises are awaited."),
+      timeout_ms: z.number().int().positive().optional().describe("Optional. Give up after this long. Defaults to 10000."),
+      interval_ms: z.number().int().positive().optional().describe("Optional. Time between checks. Defaults to 100."),
+      window_label: z.string().optional().describe("Optional. The identifier of the application window. Defaults to 'main' if not specified."),
+      webview_label: z.string().optional().describe("Optional. Webview to use inside a multi-webview window."),
+    },
+    {
+      title: "Wait for Condition in Application",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async ({ mode, selector_value, selector_type, text, url_contains, idle_ms, code, timeout_ms, interval_ms, window_label, webview_label }) => {
+      try {
+        const params = {
+          mode,
+          selector_value,
+          selector_type,
+          text,
+          url_contains,
+          idle_ms,
+          code,
+          timeout_ms,
+          interval_ms,
+          window_label: window_label || "main",
+          webview_label,
+        };
+
+        logCommandParams("wait_for", params);
+
+        const result = await socketClient.sendCommand("wait_for", params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        console.error("wait_for error:", error);
+        return createErrorResponse(`Wait for ${mode} failed: ${(error as Error).message}`);
+      }
+    }
+  );
+}
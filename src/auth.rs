@@ -0,0 +1,220 @@
+// Token authentication for socket connections.
+//
+// When enabled, a client must send
+//   {"command": "authenticate", "payload": {"token": "<token>"}}
+// as its first line. Anything else closes the connection. The token is written to a
+// file so local clients can pick it up. On Unix the file is readable only by the current
+// user; on Windows it inherits the ACL of its directory.
+
+use log::info;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::SocketAuth;
+use crate::error::Error;
+
+/// File name of the token file when no path is configured
+const DEFAULT_TOKEN_FILE: &str = "tauri-mcp.token";
+
+/// Number of random bytes in a generated token
+const RANDOM_TOKEN_BYTES: usize = 32;
+
+/// Payload of the `authenticate` handshake
+#[derive(Debug, Deserialize)]
+pub struct AuthenticateRequest {
+    pub token: String,
+}
+
+/// Token every connection must present before sending commands
+pub struct AuthToken {
+    token: String,
+    file: PathBuf,
+}
+
+impl AuthToken {
+    /// Resolve the configured authentication mode into a token.
+    /// Returns `None` when authentication is disabled.
+    pub fn from_config(auth: &SocketAuth, file: Option<&Path>) -> crate::Result<Option<Self>> {
+        let token = match auth {
+            SocketAuth::None => return Ok(None),
+            SocketAuth::Token(token) if token.is_empty() => {
+                return Err(Error::invalid_parameter(
+                    "auth_token",
+                    "a non-empty token",
+                    "an empty string",
+                ));
+            }
+            SocketAuth::Token(token) => token.clone(),
+            SocketAuth::RandomToken => random_token()?,
+        };
+
+        let file = file
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| default_token_dir().join(DEFAULT_TOKEN_FILE));
+
+        Ok(Some(Self { token, file }))
+    }
+
+    /// Check a presented token without leaking its length or prefix through timing
+    pub fn verify(&self, presented: &str) -> bool {
+        let expected = self.token.as_bytes();
+        let presented = presented.as_bytes();
+
+        let mut diff = expected.len() ^ presented.len();
+        for (i, byte) in expected.iter().enumerate() {
+            diff |= usize::from(byte ^ presented.get(i).copied().unwrap_or(0));
+        }
+        diff == 0
+    }
+
+    /// Write the token file, readable and writable by the current user only (mode 0600).
+    /// On Windows no ACL is set: the file is as private as its directory, which for the
+    /// default temp directory in the user profile means the current user and administrators.
+    /// An existing file is replaced rather than reused, since whoever created it may still
+    /// hold it open or have made it a symlink.
+    pub fn write_file(&self) -> crate::Result<()> {
+        use std::io::Write;
+
+        match std::fs::remove_file(&self.file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(Error::Io {
+                    message: format!("Failed to replace token file {}: {}", self.file.display(), e),
+                });
+            }
+        }
+
+        // create_new fails instead of following a symlink or opening a file someone
+        // recreated in the meantime
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(&self.file).map_err(|e| Error::Io {
+            message: format!("Failed to create token file {}: {}", self.file.display(), e),
+        })?;
+
+        file.write_all(self.token.as_bytes()).map_err(|e| Error::Io {
+            message: format!("Failed to write token file {}: {}", self.file.display(), e),
+        })?;
+
+        info!("[TAURI_MCP] Wrote socket auth token to {}", self.file.display());
+        Ok(())
+    }
+
+    /// Remove the token file, ignoring a file that is already gone
+    pub fn remove_file(&self) {
+        match std::fs::remove_file(&self.file) {
+            Ok(()) => info!("[TAURI_MCP] Removed token file {}", self.file.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => info!("[TAURI_MCP] Failed to remove token file {}: {}", self.file.display(), e),
+        }
+    }
+}
+
+// Per-user runtime directory where available, so the default file is not in a shared /tmp
+fn default_token_dir() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+// Hex encoded random token from the OS random number generator
+fn random_token() -> crate::Result<String> {
+    let mut bytes = [0u8; RANDOM_TOKEN_BYTES];
+    getrandom::getrandom(&mut bytes).map_err(|e| Error::PluginInit {
+        message: format!("Failed to generate auth token: {}", e),
+    })?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(value: &str) -> AuthToken {
+        AuthToken::from_config(&SocketAuth::Token(value.to_string()), None)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[cfg(unix)]
+    fn token_in_temp_dir(value: &str, name: &str) -> AuthToken {
+        let file = std::env::temp_dir().join(format!("tauri-mcp-{}-{}.token", name, std::process::id()));
+        AuthToken::from_config(&SocketAuth::Token(value.to_string()), Some(&file))
+            .unwrap()
+            .unwrap()
+    }
+
+    #[cfg(unix)]
+    fn mode(path: &Path) -> u32 {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::symlink_metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn token_file_is_private_to_the_user() {
+        let auth = token_in_temp_dir("s3cret-token", "private");
+        auth.write_file().unwrap();
+
+        assert_eq!(mode(&auth.file), 0o600);
+        assert_eq!(std::fs::read_to_string(&auth.file).unwrap(), "s3cret-token");
+
+        auth.remove_file();
+        assert!(!auth.file.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn existing_token_files_are_replaced_not_reused() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // A readable leftover keeps neither its mode nor its contents
+        let auth = token_in_temp_dir("s3cret-token", "leftover");
+        std::fs::write(&auth.file, "old token that is longer").unwrap();
+        std::fs::set_permissions(&auth.file, std::fs::Permissions::from_mode(0o644)).unwrap();
+        auth.write_file().unwrap();
+        assert_eq!(mode(&auth.file), 0o600);
+        assert_eq!(std::fs::read_to_string(&auth.file).unwrap(), "s3cret-token");
+        auth.remove_file();
+
+        // A symlink is replaced by a file instead of writing the token through it
+        let auth = token_in_temp_dir("s3cret-token", "symlink");
+        let target = auth.file.with_extension("target");
+        std::fs::write(&target, "untouched").unwrap();
+        std::os::unix::fs::symlink(&target, &auth.file).unwrap();
+        auth.write_file().unwrap();
+        assert!(!std::fs::symlink_metadata(&auth.file).unwrap().file_type().is_symlink());
+        assert_eq!(mode(&auth.file), 0o600);
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "untouched");
+        auth.remove_file();
+        std::fs::remove_file(&target).unwrap();
+    }
+
+    #[test]
+    fn verify_accepts_the_same_token() {
+        assert!(token("s3cret-token").verify("s3cret-token"));
+    }
+
+    #[test]
+    fn verify_rejects_a_different_length() {
+        let auth = token("s3cret-token");
+        assert!(!auth.verify("s3cret"));
+        assert!(!auth.verify("s3cret-token-and-more"));
+        assert!(!auth.verify(""));
+    }
+
+    #[test]
+    fn verify_rejects_a_shared_prefix() {
+        let auth = token("s3cret-token");
+        assert!(!auth.verify("s3cret-tokem"));
+        assert!(!auth.verify("s3cret-token\0"));
+    }
+}
@@ -0,0 +1,195 @@
+// Stdio transport for the plugin's built-in MCP server.
+//
+// MCP clients that launch servers as subprocesses (Claude Desktop, Cursor, ...) talk
+// JSON-RPC over stdin/stdout. This binary forwards those lines to the running app's
+// socket and copies the replies back, so no Node runtime is needed.
+//
+// The connection is configured with the same environment variables as mcp-server-ts:
+//   TAURI_MCP_CONNECTION_TYPE  "ipc" (default) or "tcp"
+//   TAURI_MCP_IPC_PATH         socket path (default: <temp dir>/tauri-mcp.sock)
+//   TAURI_MCP_TCP_HOST         TCP host (default: 127.0.0.1)
+//   TAURI_MCP_TCP_PORT         TCP port (default: 9999)
+//   TAURI_MCP_AUTH_TOKEN       token for servers that require authentication
+//   TAURI_MCP_AUTH_TOKEN_FILE  file to read the token from instead
+
+use interprocess::local_socket::{GenericFilePath, GenericNamespaced, Stream as IpcStream, ToFsName, ToNsName, prelude::*};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long replies may keep arriving after stdin has closed
+const REPLY_GRACE: Duration = Duration::from_secs(10);
+
+fn main() -> ExitCode {
+    let Connection { reader, writer, tcp } = match connect() {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("[TAURI_MCP] Failed to connect to the Tauri app: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut reader = BufReader::new(reader);
+    let mut writer = writer;
+    if let Err(e) = authenticate(&mut reader, &mut writer) {
+        eprintln!("[TAURI_MCP] Authentication with the Tauri app failed: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    // Requests sent to the app that have not been answered yet
+    let pending = Arc::new(AtomicUsize::new(0));
+    // Nudged after every reply, and disconnected when the forwarding thread ends
+    let (wake, woken) = mpsc::sync_channel::<()>(1);
+
+    // Socket -> stdout
+    let answered = pending.clone();
+    thread::spawn(move || {
+        for line in reader.lines() {
+            let Ok(line) = line else { break };
+            // Locked per line, since parse errors are written to stdout from the main thread
+            let mut stdout = io::stdout().lock();
+            if writeln!(stdout, "{}", line).and_then(|_| stdout.flush()).is_err() {
+                break;
+            }
+            drop(stdout);
+
+            if let Ok(message) = serde_json::from_str::<serde_json::Value>(&line) {
+                let replies = count_messages(&message, false);
+                let _ = answered.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| Some(n.saturating_sub(replies)));
+            }
+            let _ = wake.try_send(());
+        }
+    });
+
+    // Stdin -> socket
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Everything on stdin is JSON-RPC, so malformed lines are answered here with a parse error
+        let message = match serde_json::from_str::<serde_json::Value>(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                let reply = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": -32700, "message": format!("Parse error: {}", e) },
+                });
+                let mut stdout = io::stdout().lock();
+                if writeln!(stdout, "{}", reply).and_then(|_| stdout.flush()).is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+        pending.fetch_add(count_messages(&message, true), Ordering::SeqCst);
+        if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+            eprintln!("[TAURI_MCP] Lost connection to the Tauri app: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    // Stdin closed: dropping the writer does not half-close the connection, since the reader
+    // shares the socket. Shut down TCP's write side so the server sees the end of input; an IPC
+    // stream cannot be half-closed, so wait for the outstanding replies, for a bounded time.
+    drop(writer);
+    if let Some(stream) = tcp {
+        let _ = stream.shutdown(Shutdown::Write);
+    }
+    let deadline = Instant::now() + REPLY_GRACE;
+    while pending.load(Ordering::SeqCst) > 0 {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if woken.recv_timeout(left).is_err() {
+            break;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+// JSON-RPC messages with an ID in a message or batch: requests, or replies to them
+fn count_messages(message: &serde_json::Value, requests: bool) -> usize {
+    match message {
+        serde_json::Value::Array(batch) => batch.iter().map(|message| count_messages(message, requests)).sum(),
+        serde_json::Value::Object(object) => {
+            let has_id = object.get("id").is_some_and(|id| !id.is_null());
+            usize::from(has_id && object.contains_key("method") == requests)
+        }
+        _ => 0,
+    }
+}
+
+// Send the token handshake when one is configured and wait for the server to accept it
+fn authenticate(reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<()> {
+    let token = match std::env::var("TAURI_MCP_AUTH_TOKEN") {
+        Ok(token) => token,
+        Err(_) => match std::env::var("TAURI_MCP_AUTH_TOKEN_FILE") {
+            Ok(path) => std::fs::read_to_string(path)?.trim().to_string(),
+            Err(_) => return Ok(()),
+        },
+    };
+
+    let handshake = serde_json::json!({ "command": "authenticate", "payload": { "token": token } });
+    writeln!(writer, "{}", handshake)?;
+    writer.flush()?;
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let reply: serde_json::Value = serde_json::from_str(&line)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "connection closed during handshake"))?;
+    if reply.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, "token rejected"));
+    }
+    Ok(())
+}
+
+struct Connection {
+    reader: Box<dyn Read + Send>,
+    writer: Box<dyn Write + Send>,
+    // The TCP socket, kept to half-close it once stdin ends
+    tcp: Option<TcpStream>,
+}
+
+fn connect() -> io::Result<Connection> {
+    let connection_type = std::env::var("TAURI_MCP_CONNECTION_TYPE").unwrap_or_default();
+
+    if connection_type == "tcp" {
+        let host = std::env::var("TAURI_MCP_TCP_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port = std::env::var("TAURI_MCP_TCP_PORT").unwrap_or_else(|_| "9999".to_string());
+        eprintln!("[TAURI_MCP] Connecting to TCP socket {}:{}", host, port);
+
+        let stream = TcpStream::connect(format!("{}:{}", host, port))?;
+        return Ok(Connection {
+            reader: Box::new(stream.try_clone()?),
+            writer: Box::new(stream.try_clone()?),
+            tcp: Some(stream),
+        });
+    }
+
+    let path = std::env::var("TAURI_MCP_IPC_PATH").unwrap_or_else(|_| {
+        std::env::temp_dir()
+            .join("tauri-mcp.sock")
+            .to_string_lossy()
+            .to_string()
+    });
+    eprintln!("[TAURI_MCP] Connecting to IPC socket {}", path);
+
+    // Same naming rules as the server: named pipe on Windows, socket file elsewhere
+    let name = if cfg!(target_os = "windows") {
+        path.to_ns_name::<GenericNamespaced>()?
+    } else {
+        path.to_fs_name::<GenericFilePath>()?
+    };
+
+    let stream = IpcStream::connect(name)?;
+    let (reader, writer) = stream.split();
+    Ok(Connection {
+        reader: Box::new(reader),
+        writer: Box::new(writer),
+        tcp: None,
+    })
+}
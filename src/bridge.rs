@@ -0,0 +1,201 @@
+use log::{debug, info};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener, Runtime};
+use tokio::sync::oneshot;
+
+use crate::TauriMcpExt;
+use crate::error::Error;
+
+/// Key under which the correlation ID travels in request and response payloads
+pub const REQUEST_ID_KEY: &str = "requestId";
+
+/// Routes webview responses back to the request that triggered them.
+///
+/// Every request emitted through [`request`] carries a unique `requestId` that the
+/// guest-js handler echoes back in its `<event>-response` payload. One listener per
+/// response event looks the ID up and completes the matching waiter, so concurrent
+/// socket clients (or tools sharing an event such as `execute-js`) never receive
+/// each other's results.
+#[derive(Default)]
+pub struct WebviewBridge {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<String, oneshot::Sender<Value>>>,
+    listening: Mutex<HashSet<String>>,
+}
+
+impl WebviewBridge {
+    /// Register a new waiter, returning its request ID and the receiving end
+    pub fn register(&self) -> (String, oneshot::Receiver<Value>) {
+        let request_id = format!("mcp-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(request_id.clone(), tx);
+        (request_id, rx)
+    }
+
+    /// Hand a response to the waiter that owns its request ID.
+    /// Returns false if the response carries no ID or nobody is waiting for it.
+    pub fn resolve(&self, response: Value) -> bool {
+        let Some(request_id) = response.get(REQUEST_ID_KEY).and_then(|id| id.as_str()) else {
+            debug!("[TAURI_MCP] Ignoring webview response without a request ID");
+            return false;
+        };
+
+        let waiter = self.pending.lock().unwrap().remove(request_id);
+        match waiter {
+            Some(tx) => tx.send(response).is_ok(),
+            None => {
+                debug!("[TAURI_MCP] No waiter for request ID {} (timed out?)", request_id);
+                false
+            }
+        }
+    }
+
+    /// Drop a waiter that will never be resolved (timeout or emit failure)
+    pub fn cancel(&self, request_id: &str) {
+        self.pending.lock().unwrap().remove(request_id);
+    }
+
+    /// Wait for the response to a registered request, dropping its waiter if none arrives in time
+    pub async fn wait(
+        &self,
+        request_id: &str,
+        rx: oneshot::Receiver<Value>,
+        event: &str,
+        timeout: Duration,
+    ) -> crate::Result<Value> {
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(Error::communication_error(format!(
+                "Response channel for {} closed before a reply arrived",
+                event
+            ))),
+            Err(_) => {
+                self.cancel(request_id);
+                Err(Error::timeout_error(event, timeout.as_millis() as u64))
+            }
+        }
+    }
+
+    // Install the dispatcher for a response event the first time it is used
+    fn ensure_listener<R: Runtime>(self: &Arc<Self>, app: &AppHandle<R>, response_event: &str) {
+        let mut listening = self.listening.lock().unwrap();
+        if listening.contains(response_event) {
+            return;
+        }
+
+        let bridge = Arc::clone(self);
+        app.listen_any(response_event.to_string(), move |event| {
+            match serde_json::from_str::<Value>(event.payload()) {
+                Ok(response) => {
+                    bridge.resolve(response);
+                }
+                Err(e) => info!("[TAURI_MCP] Failed to parse webview response: {}", e),
+            }
+        });
+        listening.insert(response_event.to_string());
+        info!("[TAURI_MCP] Bridge dispatcher registered for {}", response_event);
+    }
+}
+
+/// Emit `event` to the webview identified by `target` and wait for the matching
+/// `<event>-response`. The payload must be a JSON object (or null); the request ID
+/// is added to it before emitting.
+pub async fn request<R: Runtime>(
+    app: &AppHandle<R>,
+    target: &str,
+    event: &str,
+    payload: Value,
+    timeout: Duration,
+) -> crate::Result<Value> {
+    let mut payload = match payload {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        other => {
+            return Err(Error::invalid_parameter(
+                "bridge payload",
+                "JSON object",
+                other.to_string(),
+            ));
+        }
+    };
+
+    let bridge = app.tauri_mcp().bridge().clone();
+    let response_event = format!("{}-response", event);
+    bridge.ensure_listener(app, &response_event);
+
+    let (request_id, rx) = bridge.register();
+    payload.insert(REQUEST_ID_KEY.to_string(), Value::String(request_id.clone()));
+
+    if let Err(e) = app.emit_to(target, event, Value::Object(payload)) {
+        bridge.cancel(&request_id);
+        return Err(Error::communication_error_with_context(
+            format!("Failed to emit {} event", event),
+            format!("target: {}, error: {}", target, e),
+        ));
+    }
+
+    bridge.wait(&request_id, rx, event, timeout).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn concurrent_requests_get_their_own_responses() {
+        let bridge = WebviewBridge::default();
+        let (first_id, first_rx) = bridge.register();
+        let (second_id, second_rx) = bridge.register();
+        assert_ne!(first_id, second_id);
+
+        let timeout = Duration::from_secs(1);
+        let waiting = async {
+            tokio::join!(
+                bridge.wait(&first_id, first_rx, "execute-js", timeout),
+                bridge.wait(&second_id, second_rx, "execute-js", timeout),
+            )
+        };
+        // Answer in the opposite order of the requests, as parallel execute_js calls can
+        let answering = async {
+            assert!(bridge.resolve(json!({ REQUEST_ID_KEY: second_id, "result": "second" })));
+            assert!(bridge.resolve(json!({ REQUEST_ID_KEY: first_id, "result": "first" })));
+        };
+        let ((first, second), ()) = tokio::join!(waiting, answering);
+
+        let first = first.unwrap();
+        let second = second.unwrap();
+        assert_eq!(first[REQUEST_ID_KEY], first_id);
+        assert_eq!(first["result"], "first");
+        assert_eq!(second[REQUEST_ID_KEY], second_id);
+        assert_eq!(second["result"], "second");
+        assert!(bridge.pending.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn timeout_removes_the_waiter() {
+        let bridge = WebviewBridge::default();
+        let (request_id, rx) = bridge.register();
+
+        let result = bridge.wait(&request_id, rx, "execute-js", Duration::from_millis(10)).await;
+        assert!(matches!(result, Err(Error::TimeoutError { .. })));
+        assert!(bridge.pending.lock().unwrap().is_empty());
+
+        // A reply that arrives after the timeout has nobody to go to
+        assert!(!bridge.resolve(json!({ REQUEST_ID_KEY: request_id })));
+    }
+
+    #[test]
+    fn responses_without_a_known_request_id_are_ignored() {
+        let bridge = WebviewBridge::default();
+        let (_request_id, _rx) = bridge.register();
+
+        assert!(!bridge.resolve(json!({ "result": "no id" })));
+        assert!(!bridge.resolve(json!({ REQUEST_ID_KEY: "mcp-unknown" })));
+        assert_eq!(bridge.pending.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,286 @@
+// Console capture that does not depend on the app calling into guest-js.
+//
+// The plugin registers an initialization script that hooks `console.*` in every webview as
+// soon as it is created. Entries are batched and emitted to Rust, where they are kept in a
+// bounded ring buffer per window label, so `get_console_logs` answers from memory and sees
+// everything logged since the page started, including before any client connected.
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tauri::{AppHandle, Listener, Manager, Runtime};
+
+use crate::notifications::EventStream;
+
+/// Event the initialization script emits with batches of console entries
+pub const CONSOLE_LOG_EVENT: &str = "mcp-console-log";
+
+/// Entries kept per window when the plugin configuration does not say otherwise
+pub const DEFAULT_CONSOLE_LOG_CAPACITY: usize = 1000;
+
+/// Hooks console methods and forwards their output to the plugin in batches.
+/// The plugin's own guest-js chatter (prefixed "TAURI-PLUGIN-MCP") is left out.
+/// Emitting needs `core:event:allow-emit` in the window's capability; the first failed
+/// emit is reported once on the original `console.error`.
+pub const CONSOLE_CAPTURE_SCRIPT: &str = r#"
+(function () {
+  if (window.__TAURI_MCP_CONSOLE_CAPTURE__ || window.top !== window) {
+    return;
+  }
+  window.__TAURI_MCP_CONSOLE_CAPTURE__ = true;
+
+  var EVENT = 'mcp-console-log';
+  var LEVELS = { debug: 'debug', log: 'info', info: 'info', warn: 'warn', error: 'error' };
+  var MAX_PENDING = 1000;
+  var pending = [];
+  var dropped = 0;
+  var scheduled = false;
+  var emitFailed = false;
+  var originalError = console.error;
+
+  function format(arg) {
+    if (typeof arg === 'string') {
+      return arg;
+    }
+    if (arg instanceof Error) {
+      return arg.stack || (arg.name + ': ' + arg.message);
+    }
+    try {
+      var json = JSON.stringify(arg);
+      return json === undefined ? String(arg) : json;
+    } catch (e) {
+      return String(arg);
+    }
+  }
+
+  function schedule(delay) {
+    if (!scheduled) {
+      scheduled = true;
+      setTimeout(flush, delay);
+    }
+  }
+
+  function flush() {
+    scheduled = false;
+    if (pending.length === 0) {
+      return;
+    }
+    var internals = window.__TAURI_INTERNALS__;
+    if (!internals || typeof internals.invoke !== 'function') {
+      // The IPC bridge is not ready yet, keep the entries until it is
+      schedule(250);
+      return;
+    }
+    var webview = internals.metadata && internals.metadata.currentWebview;
+    var payload = { windowLabel: webview ? webview.label : null, entries: pending, dropped: dropped };
+    pending = [];
+    dropped = 0;
+    internals.invoke('plugin:event|emit', { event: EVENT, payload: payload }).catch(function (e) {
+      // Usually a capability without core:event:allow-emit; say so once, past the hook
+      if (!emitFailed && typeof originalError === 'function') {
+        emitFailed = true;
+        originalError.call(console, 'TAURI-PLUGIN-MCP: console capture cannot emit ' + EVENT +
+          ' (does the capability grant core:event:allow-emit?):', e);
+      }
+    });
+  }
+
+  Object.keys(LEVELS).forEach(function (method) {
+    var original = console[method];
+    if (typeof original !== 'function') {
+      return;
+    }
+    console[method] = function () {
+      var args = Array.prototype.slice.call(arguments);
+      try {
+        var formatted = args.map(format);
+        if (typeof args[0] !== 'string' || args[0].indexOf('TAURI-PLUGIN-MCP') !== 0) {
+          if (pending.length >= MAX_PENDING) {
+            pending.shift();
+            dropped++;
+          }
+          pending.push({ timestamp: Date.now(), level: LEVELS[method], message: formatted.join(' '), args: formatted });
+          schedule(50);
+        }
+      } catch (e) {
+        // Never let capture break the app's own logging
+      }
+      return original.apply(console, args);
+    };
+  });
+
+  window.addEventListener('pagehide', flush);
+})();
+"#;
+
+/// Where a log entry came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSource {
+    /// The webview's console
+    #[default]
+    Webview,
+    /// The host app's `log` records, see [`crate::McpLogTee`]
+    Rust,
+}
+
+/// Single console log entry
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ConsoleLogEntry {
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// "trace" (Rust only), "debug", "info", "warn" or "error"
+    pub level: String,
+    pub message: String,
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub source: LogSource,
+    /// Target of a Rust record
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Module that emitted a Rust record
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+}
+
+/// Filters applied when reading the buffer
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleLogFilter {
+    pub level: Option<String>,
+    pub start_time_ms: Option<u64>,
+    pub end_time_ms: Option<u64>,
+    /// Most recent entries to return
+    pub limit: Option<usize>,
+}
+
+impl ConsoleLogFilter {
+    fn matches(&self, entry: &ConsoleLogEntry) -> bool {
+        self.level.as_ref().is_none_or(|level| *level == entry.level)
+            && self.start_time_ms.is_none_or(|start| entry.timestamp >= start)
+            && self.end_time_ms.is_none_or(|end| entry.timestamp <= end)
+    }
+}
+
+/// Result of a buffer query
+#[derive(Debug, Default)]
+pub struct ConsoleLogQuery {
+    /// Matching entries, oldest first
+    pub logs: Vec<ConsoleLogEntry>,
+    /// Matching entries before the limit was applied
+    pub total_count: usize,
+    /// Entries evicted from the buffers (or the page's queue) of the queried sources
+    pub dropped_count: u64,
+}
+
+#[derive(Default)]
+struct WindowLogs {
+    entries: VecDeque<ConsoleLogEntry>,
+    dropped: u64,
+}
+
+/// Batch emitted by the initialization script
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConsoleLogBatch {
+    window_label: Option<String>,
+    entries: Vec<ConsoleLogEntry>,
+    #[serde(default)]
+    dropped: u64,
+}
+
+/// Ring buffers of console entries, one per window label
+pub struct ConsoleLogStore {
+    capacity: usize,
+    windows: Mutex<HashMap<String, WindowLogs>>,
+}
+
+impl ConsoleLogStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Append entries for a window, evicting the oldest ones beyond the capacity
+    pub fn push(&self, window_label: &str, entries: impl IntoIterator<Item = ConsoleLogEntry>, dropped: u64) {
+        let mut windows = self.windows.lock().unwrap();
+        let logs = windows.entry(window_label.to_string()).or_default();
+        logs.dropped += dropped;
+        for entry in entries {
+            if logs.entries.len() == self.capacity {
+                logs.entries.pop_front();
+                logs.dropped += 1;
+            }
+            logs.entries.push_back(entry);
+        }
+    }
+
+    /// Whether anything was ever logged for this window, even if it has been closed since
+    pub fn has_window(&self, window_label: &str) -> bool {
+        self.windows.lock().unwrap().contains_key(window_label)
+    }
+
+    /// Entries matching the filter from a window's console (if `window_label` is given) and
+    /// from the host app's Rust log (if `include_rust`), merged in time order
+    pub fn query(&self, window_label: Option<&str>, include_rust: bool, filter: &ConsoleLogFilter) -> ConsoleLogQuery {
+        let mut matching = Vec::new();
+        let mut dropped_count = 0;
+
+        if let Some(window_label) = window_label {
+            let windows = self.windows.lock().unwrap();
+            if let Some(logs) = windows.get(window_label) {
+                matching.extend(logs.entries.iter().filter(|entry| filter.matches(entry)).cloned());
+                dropped_count += logs.dropped;
+            }
+        }
+
+        if include_rust {
+            let (entries, dropped) = crate::log_capture::rust_log_entries();
+            matching.extend(entries.into_iter().filter(|entry| filter.matches(entry)));
+            dropped_count += dropped;
+            // Both sources are in time order already; the sort is stable
+            matching.sort_by_key(|entry| entry.timestamp);
+        }
+
+        let skip = filter
+            .limit
+            .map_or(0, |limit| matching.len().saturating_sub(limit));
+
+        ConsoleLogQuery {
+            total_count: matching.len(),
+            logs: matching.into_iter().skip(skip).collect(),
+            dropped_count,
+        }
+    }
+
+    /// Store the batches emitted by the initialization script and forward them to subscribers
+    pub fn listen_to_guest<R: Runtime>(app: &AppHandle<R>) {
+        let handle = app.clone();
+        app.listen_any(CONSOLE_LOG_EVENT, move |event| {
+            let Some(state) = handle.try_state::<crate::TauriMcp<R>>() else {
+                return;
+            };
+            let batch = match serde_json::from_str::<ConsoleLogBatch>(event.payload()) {
+                Ok(batch) => batch,
+                Err(e) => {
+                    debug!("[TAURI_MCP] Ignoring malformed console batch: {}", e);
+                    return;
+                }
+            };
+            let Some(window_label) = batch.window_label else {
+                return;
+            };
+
+            for entry in &batch.entries {
+                if let Ok(data) = serde_json::to_value(entry) {
+                    state
+                        .notifications()
+                        .publish(EventStream::Console, Some(window_label.clone()), data);
+                }
+            }
+            state.console_logs().push(&window_label, batch.entries, batch.dropped);
+        });
+    }
+}
@@ -1,26 +1,34 @@
+use crate::auth::AuthToken;
+use crate::bridge::WebviewBridge;
 use crate::error::Error;
 use crate::models::*;
+use crate::console_capture::ConsoleLogStore;
+use crate::notifications::NotificationHub;
 use crate::shared::{
     McpInterface, MouseMovementParams, MouseMovementResult, ScreenshotParams,
     ScreenshotResult as SharedScreenshotResult, TextInputParams, TextInputResult,
     WindowManagerParams, WindowManagerResult,
 };
 use crate::socket_server::SocketServer;
-use crate::tools::mouse_movement;
+use crate::tools::windows::{resolve_webview, resolve_window};
+use crate::tools::events::EventRecorder;
+use crate::tools::invoke_command::AppCommands;
+use crate::tools::{ToolRegistry, mouse_movement, take_screenshot};
 use crate::{PluginConfig, Result};
 use enigo::{Enigo, Keyboard, Settings};
 use serde::de::DeserializeOwned;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
-use tauri::{AppHandle, Manager, Runtime, plugin::PluginApi};
+use tauri::{AppHandle, Runtime, plugin::PluginApi};
 use log::info;
 
 // ----- Screenshot Utilities -----
 
-/// Helper structure to hold window for screenshot functions
+/// Helper structure to hold the captured webview and its window for screenshot functions
 pub struct ScreenshotContext<R: Runtime> {
-    pub window: tauri::WebviewWindow<R>,
+    pub window: tauri::Window<R>,
+    pub webview: tauri::Webview<R>,
 }
 
 /// Create a success response with data
@@ -29,6 +37,8 @@ pub fn create_success_response(data_url: String) -> ScreenshotResponse {
         data: Some(data_url),
         success: true,
         error: None,
+        region: None,
+        mime_type: None,
     }
 }
 
@@ -38,6 +48,8 @@ pub fn create_error_response(error_msg: String) -> ScreenshotResponse {
         data: None,
         success: false,
         error: Some(error_msg),
+        region: None,
+        mime_type: None,
     }
 }
 
@@ -46,31 +58,116 @@ pub fn create_error_response(error_msg: String) -> ScreenshotResponse {
 pub fn init<R: Runtime, C: DeserializeOwned>(
     app: &AppHandle<R>,
     _api: PluginApi<R, C>,
-    config: &PluginConfig,
+    config: &PluginConfig<R>,
 ) -> crate::Result<TauriMcp<R>> {
-    let socket_server = if config.start_socket_server {
-        let mut server = SocketServer::new(app.clone(), config.socket_type.clone());
-        server.start()?;
-        Some(Arc::new(Mutex::new(server)))
-    } else {
-        None
-    };
+    let mut tools = ToolRegistry::with_builtins();
+    for tool in &config.tools {
+        info!("[TAURI_MCP] Registering host tool: {}", tool.name());
+        tools.register(tool.clone());
+    }
+
+    let auth = AuthToken::from_config(&config.auth, config.auth_token_file.as_deref())?.map(Arc::new);
+
+    // Started from the plugin setup once this state is managed, so early commands can reach it
+    let socket_server = SocketServer::new(app.clone(), config.socket_type.clone(), auth);
+
+    NotificationHub::listen_to_guest(app);
+    ConsoleLogStore::listen_to_guest(app);
 
     Ok(TauriMcp {
         app: app.clone(),
-        socket_server,
+        socket_server: Mutex::new(socket_server),
         application_name: config.application_name.clone(),
+        bridge: Arc::new(WebviewBridge::default()),
+        notifications: NotificationHub::default(),
+        console_logs: ConsoleLogStore::new(config.console_log_capacity),
+        app_commands: AppCommands::from_config(config)?,
+        events: EventRecorder::new(config.event_capacity, &config.app_events),
+        baseline_dir: config
+            .baseline_dir
+            .clone()
+            .unwrap_or_else(|| std::env::temp_dir().join("tauri-mcp-baselines")),
+        tools: Arc::new(tools),
     })
 }
 
 /// Access to the tauri-mcp APIs.
 pub struct TauriMcp<R: Runtime> {
     app: AppHandle<R>,
-    socket_server: Option<Arc<Mutex<SocketServer<R>>>>,
+    socket_server: Mutex<SocketServer<R>>,
     application_name: String,
+    bridge: Arc<WebviewBridge>,
+    notifications: NotificationHub,
+    console_logs: ConsoleLogStore,
+    app_commands: AppCommands,
+    events: EventRecorder,
+    baseline_dir: std::path::PathBuf,
+    tools: Arc<ToolRegistry<R>>,
 }
 
 impl<R: Runtime> TauriMcp<R> {
+    /// Correlates webview requests with their responses
+    pub(crate) fn bridge(&self) -> &Arc<WebviewBridge> {
+        &self.bridge
+    }
+
+    /// Broadcasts events to connections that subscribed to them
+    pub(crate) fn notifications(&self) -> &NotificationHub {
+        &self.notifications
+    }
+
+    /// Console output captured from every webview
+    pub(crate) fn console_logs(&self) -> &ConsoleLogStore {
+        &self.console_logs
+    }
+
+    /// Host application commands and ACL, as declared in the plugin configuration
+    pub(crate) fn app_commands(&self) -> &AppCommands {
+        &self.app_commands
+    }
+
+    /// App events recorded for `listen_events`
+    pub(crate) fn events(&self) -> &EventRecorder {
+        &self.events
+    }
+
+    /// Where `visual_compare` keeps its baseline images
+    pub(crate) fn baseline_dir(&self) -> &std::path::Path {
+        &self.baseline_dir
+    }
+
+    /// Tools served over the socket, built-in and host-provided
+    pub fn tools(&self) -> &ToolRegistry<R> {
+        &self.tools
+    }
+
+    /// Start the socket server. Does nothing if it is already running.
+    pub fn start_socket_server(&self) -> crate::Result<()> {
+        self.socket_server()?.start()
+    }
+
+    /// Stop the socket server, waiting for in-flight commands to finish.
+    /// It can be started again afterwards on the same address.
+    pub async fn stop_socket_server(&self) -> crate::Result<()> {
+        // Release the lock before waiting, the drain can take a while
+        let stopping = self.socket_server()?.request_stop();
+        match stopping {
+            Some(stopping) => stopping.wait().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the socket server is accepting connections
+    pub fn is_socket_server_running(&self) -> bool {
+        self.socket_server().is_ok_and(|server| server.is_running())
+    }
+
+    fn socket_server(&self) -> crate::Result<std::sync::MutexGuard<'_, SocketServer<R>>> {
+        self.socket_server
+            .lock()
+            .map_err(|_| Error::Anyhow { message: "Socket server lock poisoned".to_string() })
+    }
+
     pub fn ping(&self, payload: PingRequest) -> crate::Result<PingResponse> {
         Ok(PingResponse {
             value: payload.value,
@@ -82,31 +179,53 @@ impl<R: Runtime> TauriMcp<R> {
         &self,
         payload: ScreenshotRequest,
     ) -> crate::Result<ScreenshotResponse> {
-        let window_label = payload.window_label.clone();
+        self.capture_screenshot(payload, false).await
+    }
 
-        let window = self
-            .app
-            .get_webview_window(&window_label)
-            .ok_or_else(|| Error::window_not_found(window_label.clone()))?;
+    /// Take a screenshot that fails rather than being downscaled to fit `max_width` or `max_size_mb`
+    pub(crate) async fn take_native_screenshot_async(
+        &self,
+        payload: ScreenshotRequest,
+    ) -> crate::Result<ScreenshotResponse> {
+        self.capture_screenshot(payload, true).await
+    }
+
+    async fn capture_screenshot(
+        &self,
+        payload: ScreenshotRequest,
+        strict_size: bool,
+    ) -> crate::Result<ScreenshotResponse> {
+        let window_label = payload.window_label.clone();
+        let webview = resolve_webview(&self.app, Some(&window_label), payload.webview_label.as_deref())?;
+        let region = take_screenshot::resolve_region(&self.app, &webview, &payload).await?;
 
         // Create shared parameters struct from the request
         let params = ScreenshotParams {
-            window_label: Some(window_label),
-            quality: None,
-            max_width: None,
-            max_size_mb: None,
             application_name: Some(self.application_name.clone()),
+            crop: region.map(|region| region.physical),
+            strict_size,
+            ..payload.into()
         };
+        let format = params.format.unwrap_or_default();
 
         // Create a context with the window for platform implementation
         let window_context = ScreenshotContext {
-            window: window.clone(),
+            window: webview.window(),
+            webview,
         };
 
-        info!("[TAURI_MCP] Taking screenshot with default parameters");
+        match &region {
+            Some(region) => info!("[TAURI_MCP] Taking screenshot of region {:?}", region.physical),
+            None => info!("[TAURI_MCP] Taking {:?} screenshot of the whole webview", format),
+        }
 
         // Use platform-specific implementation to capture the window
-        crate::platform::current::take_screenshot(params, window_context).await
+        let mut response = crate::platform::current::take_screenshot(params, window_context).await?;
+        if response.success {
+            response.region = region;
+            response.mime_type = Some(format.mime_type().to_string());
+        }
+        Ok(response)
     }
 
     // Add async method to perform window operations
@@ -114,12 +233,9 @@ impl<R: Runtime> TauriMcp<R> {
         &self,
         params: WindowManagerRequest,
     ) -> Result<WindowManagerResponse> {
-        let window_label = params.window_label.unwrap_or_else(|| "main".to_string());
-
         // Get the window by label
-        let window = self.app.get_webview_window(&window_label).ok_or_else(|| {
-            Error::window_operation_failed("get_window", format!("Window not found: {}", window_label))
-        })?;
+        let window = resolve_window(&self.app, params.window_label.as_deref(), params.webview_label.as_deref())
+            .map_err(|e| Error::window_operation_failed("get_window", e.to_string()))?;
 
         // Execute the requested operation
         match params.operation.as_str() {
@@ -238,6 +354,11 @@ impl<R: Runtime> TauriMcp<R> {
         let delay_ms = params.delay_ms.unwrap_or(20);
         let initial_delay_ms = params.initial_delay_ms.unwrap_or(500);
 
+        // Keystrokes go to the focused window, so bring the requested one forward first
+        if let Some(window_label) = params.window_label.as_deref() {
+            resolve_window(&self.app, Some(window_label), None)?.set_focus()?;
+        }
+
         // Create Enigo instance with the latest API
         let mut enigo = Enigo::new(&Settings::default())
             .map_err(|e| Error::communication_error(format!("Failed to initialize Enigo: {}", e)))?;
@@ -281,16 +402,6 @@ impl<R: Runtime> TauriMcp<R> {
     }
 }
 
-impl<R: Runtime> Drop for TauriMcp<R> {
-    fn drop(&mut self) {
-        if let Some(server) = &self.socket_server {
-            if let Ok(server) = server.lock() {
-                let _ = server.stop();
-            }
-        }
-    }
-}
-
 // Let's implement the interface properly
 impl<R: Runtime> McpInterface for TauriMcp<R> {
     fn take_screenshot_shared(
@@ -300,7 +411,15 @@ impl<R: Runtime> McpInterface for TauriMcp<R> {
         // Create a ScreenshotRequest from our interface params
         let window_label = params.window_label.unwrap_or_else(|| "main".to_string());
 
-        let request = ScreenshotRequest { window_label };
+        let request = ScreenshotRequest {
+            window_label,
+            format: params.format,
+            quality: params.quality,
+            max_width: params.max_width,
+            max_size_mb: params.max_size_mb,
+            size_fallback: params.size_fallback,
+            ..Default::default()
+        };
         match futures::executor::block_on(self.take_screenshot_async(request)) {
             Ok(response) => {
                 // Convert to the shared result type
@@ -308,7 +427,7 @@ impl<R: Runtime> McpInterface for TauriMcp<R> {
                     success: response.success,
                     error: response.error,
                     data: response.data,
-                    mime_type: Some("image/jpeg".to_string()),
+                    mime_type: response.mime_type,
                 })
             }
             Err(err) => {
@@ -325,6 +444,7 @@ impl<R: Runtime> McpInterface for TauriMcp<R> {
         // Convert from shared types to internal types
         let request = WindowManagerRequest {
             window_label: params.window_label,
+            webview_label: None,
             operation: params.operation,
             x: params.x,
             y: params.y,
@@ -354,6 +474,7 @@ impl<R: Runtime> McpInterface for TauriMcp<R> {
             text: params.text,
             delay_ms: params.delay_ms,
             initial_delay_ms: params.initial_delay_ms,
+            window_label: None,
         };
 
         // Run async method
@@ -2,7 +2,8 @@ use tauri::{
     Manager, Runtime,
     plugin::{Builder, TauriPlugin},
 };
-use log::info;
+use log::{info, warn};
+use std::sync::Arc;
 
 pub use models::*;
 
@@ -11,9 +12,15 @@ mod desktop;
 #[cfg(mobile)]
 mod mobile;
 
+mod auth;
+mod bridge;
 mod commands;
+mod console_capture;
 mod error;
+mod log_capture;
+mod mcp;
 mod models;
+mod notifications;
 pub mod shared;
 mod socket_server;
 mod tools;
@@ -21,6 +28,8 @@ mod tools;
 mod platform;
 
 pub use error::{Error, Result};
+pub use log_capture::McpLogTee;
+pub use tools::{McpTool, ToolFuture, ToolRegistry};
 pub use shared::{
     McpInterface, ScreenshotParams, ScreenshotResult, WindowManagerParams, WindowManagerResult,
 };
@@ -64,24 +73,74 @@ impl Default for SocketType {
     }
 }
 
+/// How clients authenticate with the socket server
+#[derive(Clone, Debug, Default)]
+pub enum SocketAuth {
+    /// Accept every connection
+    #[default]
+    None,
+    /// Require the given shared secret
+    Token(String),
+    /// Require a token generated randomly at every launch
+    RandomToken,
+}
+
 /// Plugin configuration options.
-#[derive(Default)]
-pub struct PluginConfig {
+pub struct PluginConfig<R: Runtime = tauri::Wry> {
     /// Application name (used for default socket naming)
     pub application_name: String,
     /// Socket configuration
     pub socket_type: SocketType,
     /// Whether to start the socket server automatically. Default is true.
     pub start_socket_server: bool,
+    /// Additional tools provided by the host application, served next to the built-in ones
+    pub tools: Vec<Arc<dyn McpTool<R>>>,
+    /// Token clients must present before sending commands. Default is no authentication.
+    pub auth: SocketAuth,
+    /// File the token is written to (mode 0600). Defaults to `tauri-mcp.token` in `$XDG_RUNTIME_DIR`, or the temp directory where that is unset.
+    /// On Windows the file gets no ACL of its own and is only as private as its directory.
+    pub auth_token_file: Option<std::path::PathBuf>,
+    /// Console entries kept per window. Default is 1000.
+    pub console_log_capacity: usize,
+    /// Names of the commands in the app's invoke handler, reported by `list_commands`
+    pub app_commands: Vec<String>,
+    /// Contents of the `acl-manifests.json` and `capabilities.json` generated by `tauri-build`,
+    /// used by `list_commands` to tell which commands a webview may call
+    pub acl: Option<(String, String)>,
+    /// Names of events the app emits, so `listen_events` wildcard patterns can match them
+    pub app_events: Vec<String>,
+    /// Events kept by `listen_events`. Default is 1000.
+    pub event_capacity: usize,
+    /// Directory `visual_compare` keeps its baseline images in. Defaults to `tauri-mcp-baselines` in the temp directory.
+    pub baseline_dir: Option<std::path::PathBuf>,
+}
+
+impl<R: Runtime> Default for PluginConfig<R> {
+    fn default() -> Self {
+        Self {
+            application_name: String::new(),
+            socket_type: SocketType::default(),
+            start_socket_server: false,
+            tools: Vec::new(),
+            auth: SocketAuth::default(),
+            auth_token_file: None,
+            console_log_capacity: console_capture::DEFAULT_CONSOLE_LOG_CAPACITY,
+            app_commands: Vec::new(),
+            acl: None,
+            app_events: Vec::new(),
+            event_capacity: tools::events::DEFAULT_EVENT_CAPACITY,
+            baseline_dir: None,
+        }
+    }
 }
 
-impl PluginConfig {
+impl<R: Runtime> PluginConfig<R> {
     /// Create a new plugin configuration with default values.
     pub fn new(application_name: String) -> Self {
         Self {
             application_name,
-            socket_type: SocketType::default(),
             start_socket_server: true,
+            ..Self::default()
         }
     }
 
@@ -102,6 +161,84 @@ impl PluginConfig {
         self.start_socket_server = start;
         self
     }
+
+    /// Require clients to authenticate with the given shared secret.
+    pub fn auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth = SocketAuth::Token(token.into());
+        self
+    }
+
+    /// Require clients to authenticate with a token generated at every launch.
+    pub fn random_auth_token(mut self) -> Self {
+        self.auth = SocketAuth::RandomToken;
+        self
+    }
+
+    /// Set the file the auth token is written to.
+    pub fn auth_token_file(mut self, path: std::path::PathBuf) -> Self {
+        self.auth_token_file = Some(path);
+        self
+    }
+
+    /// Set how many console entries are kept per window.
+    pub fn console_log_capacity(mut self, capacity: usize) -> Self {
+        self.console_log_capacity = capacity;
+        self
+    }
+
+    /// Declare the commands registered with the app's invoke handler, so `list_commands` can report them.
+    pub fn app_commands<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.app_commands.extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Provide the app's ACL so `list_commands` can tell which commands each webview may call.
+    /// `tauri-build` writes both files to `OUT_DIR`:
+    ///
+    /// ```ignore
+    /// .acl(
+    ///     include_str!(concat!(env!("OUT_DIR"), "/acl-manifests.json")),
+    ///     include_str!(concat!(env!("OUT_DIR"), "/capabilities.json")),
+    /// )
+    /// ```
+    pub fn acl(mut self, acl_manifests: impl Into<String>, capabilities: impl Into<String>) -> Self {
+        self.acl = Some((acl_manifests.into(), capabilities.into()));
+        self
+    }
+
+    /// Declare event names the app emits, so `listen_events` wildcard patterns can match them.
+    /// Exact names passed to `listen_events` work without being declared.
+    pub fn app_events<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.app_events.extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set how many events `listen_events` keeps.
+    pub fn event_capacity(mut self, capacity: usize) -> Self {
+        self.event_capacity = capacity;
+        self
+    }
+
+    /// Set the directory `visual_compare` reads and writes baseline images in,
+    /// e.g. a folder checked into the app's repository.
+    pub fn baseline_dir(mut self, path: std::path::PathBuf) -> Self {
+        self.baseline_dir = Some(path);
+        self
+    }
+
+    /// Register a host-provided tool. A tool named like a built-in one replaces it.
+    pub fn tool(mut self, tool: impl McpTool<R> + 'static) -> Self {
+        self.tools.push(Arc::new(tool));
+        self
+    }
 }
 
 /// Initializes the plugin.
@@ -110,7 +247,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
 }
 
 /// Initializes the plugin with the given configuration.
-pub fn init_with_config<R: Runtime>(config: PluginConfig) -> TauriPlugin<R> {
+pub fn init_with_config<R: Runtime>(config: PluginConfig<R>) -> TauriPlugin<R> {
     // Log socket configuration
     match &config.socket_type {
         SocketType::Ipc { path } => {
@@ -135,6 +272,16 @@ pub fn init_with_config<R: Runtime>(config: PluginConfig) -> TauriPlugin<R> {
         }
     }
 
+    match &config.auth {
+        SocketAuth::None => {
+            if matches!(&config.socket_type, SocketType::Tcp { host, .. } if host != "127.0.0.1" && host != "localhost") {
+                warn!("[TAURI_MCP] TCP socket is reachable from the network without authentication");
+            }
+        }
+        SocketAuth::Token(_) => info!("[TAURI_MCP] Socket clients must authenticate with the configured token"),
+        SocketAuth::RandomToken => info!("[TAURI_MCP] Socket clients must authenticate with a per-launch token"),
+    }
+
     if config.start_socket_server {
         info!("[TAURI_MCP] Socket server will start automatically");
     } else {
@@ -142,6 +289,8 @@ pub fn init_with_config<R: Runtime>(config: PluginConfig) -> TauriPlugin<R> {
     }
 
     Builder::new("tauri-mcp")
+        // Console capture starts with the page, before the app or an agent does anything
+        .js_init_script(console_capture::CONSOLE_CAPTURE_SCRIPT.to_string())
         .invoke_handler(tauri::generate_handler![
         // Server Commands
         ])
@@ -152,8 +301,19 @@ pub fn init_with_config<R: Runtime>(config: PluginConfig) -> TauriPlugin<R> {
             #[cfg(desktop)]
             let tauri_mcp = desktop::init(app, api, &config)?;
             app.manage(tauri_mcp);
+            if config.start_socket_server {
+                app.tauri_mcp().start_socket_server()?;
+            }
             info!("[TAURI_MCP] Plugin setup complete");
             Ok(())
         })
+        .on_event(|app, event| {
+            if let tauri::RunEvent::WindowEvent { label, event, .. } = event {
+                notifications::publish_window_event(app, label, event);
+            }
+        })
+        .on_page_load(|webview, payload| {
+            notifications::publish_page_load(webview, payload);
+        })
         .build()
 }
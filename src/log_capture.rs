@@ -0,0 +1,143 @@
+// Capture of the host app's Rust `log` records.
+//
+// `McpLogTee` wraps the app's logger: every record still goes to that logger, and a copy is
+// kept in a ring buffer that `get_console_logs` reads next to webview console output
+// (`source: "rust"`). The logger is global and installed before the plugin exists, so the
+// buffer is a process-wide static rather than part of the plugin state.
+
+use log::{LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::console_capture::{ConsoleLogEntry, DEFAULT_CONSOLE_LOG_CAPACITY, LogSource};
+
+/// The plugin's own records are left out, like its guest-js chatter is
+const OWN_TARGET: &str = "tauri_plugin_mcp";
+
+struct RustLogs {
+    capacity: usize,
+    entries: VecDeque<ConsoleLogEntry>,
+    dropped: u64,
+}
+
+static RUST_LOGS: LazyLock<Mutex<RustLogs>> = LazyLock::new(|| {
+    Mutex::new(RustLogs {
+        capacity: DEFAULT_CONSOLE_LOG_CAPACITY,
+        entries: VecDeque::new(),
+        dropped: 0,
+    })
+});
+
+/// Captured Rust records, oldest first, and how many were evicted.
+/// Empty when no tee is installed.
+pub(crate) fn rust_log_entries() -> (Vec<ConsoleLogEntry>, u64) {
+    let logs = RUST_LOGS.lock().unwrap();
+    (logs.entries.iter().cloned().collect(), logs.dropped)
+}
+
+/// A `log::Log` that forwards to the app's logger and keeps a copy of each record for
+/// `get_console_logs`.
+///
+/// ```ignore
+/// // With tauri-plugin-log, split the plugin to get its logger
+/// let (log_plugin, max_level, logger) = tauri_plugin_log::Builder::new().split(app.handle())?;
+/// log::set_max_level(max_level);
+/// tauri_plugin_mcp::McpLogTee::new(logger).install()?;
+/// app.handle().plugin(log_plugin)?;
+/// ```
+pub struct McpLogTee {
+    inner: Option<Box<dyn Log>>,
+    level: LevelFilter,
+}
+
+impl McpLogTee {
+    /// Chain to the given logger
+    pub fn new(inner: Box<dyn Log>) -> Self {
+        Self {
+            inner: Some(inner),
+            level: LevelFilter::Info,
+        }
+    }
+
+    /// Only capture records, for apps that have no logger of their own
+    pub fn standalone() -> Self {
+        Self {
+            inner: None,
+            level: LevelFilter::Info,
+        }
+    }
+
+    /// Most verbose level captured for agents. Default is `Info`.
+    /// The chained logger still applies its own filtering.
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Records kept in the buffer. Default is 1000.
+    pub fn capacity(self, capacity: usize) -> Self {
+        RUST_LOGS.lock().unwrap().capacity = capacity.max(1);
+        self
+    }
+
+    /// Install as the global logger, raising the max level to the capture level if needed.
+    /// Fails if a logger is already installed.
+    pub fn install(self) -> crate::Result<()> {
+        let max_level = log::max_level().max(self.level);
+        log::set_boxed_logger(Box::new(self)).map_err(|e| crate::Error::Anyhow {
+            message: format!("Failed to install log tee: {}", e),
+        })?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+
+    fn captures(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level && !metadata.target().starts_with(OWN_TARGET)
+    }
+}
+
+impl Log for McpLogTee {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.captures(metadata) || self.inner.as_ref().is_some_and(|inner| inner.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        // install() may have raised the max level past what the wrapped logger wants
+        if let Some(inner) = &self.inner
+            && inner.enabled(record.metadata())
+        {
+            inner.log(record);
+        }
+        if !self.captures(record.metadata()) {
+            return;
+        }
+
+        let message = record.args().to_string();
+        let entry = ConsoleLogEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            level: record.level().as_str().to_lowercase(),
+            args: vec![message.clone()],
+            message,
+            source: LogSource::Rust,
+            target: Some(record.target().to_string()),
+            module_path: record.module_path().map(str::to_string),
+        };
+
+        let mut logs = RUST_LOGS.lock().unwrap();
+        while logs.entries.len() >= logs.capacity {
+            logs.entries.pop_front();
+            logs.dropped += 1;
+        }
+        logs.entries.push_back(entry);
+    }
+
+    fn flush(&self) {
+        if let Some(inner) = &self.inner {
+            inner.flush();
+        }
+    }
+}
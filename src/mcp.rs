@@ -0,0 +1,299 @@
+// Model Context Protocol (JSON-RPC 2.0) front end for the socket server.
+//
+// The socket server accepts two line-delimited protocols on the same connection:
+// the plugin's own `{command, payload}` requests and MCP JSON-RPC messages. Lines
+// carrying a `"jsonrpc"` member are routed here, so agents can talk to the app
+// directly (or over stdio through the `tauri-mcp-stdio` companion binary) without
+// going through the TypeScript server. Tools are listed from the plugin's registry.
+
+use log::info;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tauri::{AppHandle, Runtime};
+
+use crate::TauriMcpExt;
+use crate::socket_server::SocketResponse;
+use crate::tools;
+
+/// Newest protocol revision this server implements
+pub const LATEST_PROTOCOL_VERSION: &str = "2025-03-26";
+
+/// Protocol revisions a client may negotiate
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26"];
+
+/// URI prefix for the window resources exposed through `resources/list`
+const WINDOW_RESOURCE_PREFIX: &str = "tauri://window/";
+
+// Standard JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcRequest {
+    jsonrpc: String,
+    /// Absent for notifications
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+// Error raised while handling a single request, mapped to a JSON-RPC error object
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Returns true if a parsed socket line is a JSON-RPC message rather than a plugin command
+pub fn is_jsonrpc_message(message: &Value) -> bool {
+    match message {
+        Value::Object(map) => map.contains_key("jsonrpc"),
+        Value::Array(items) => items.first().is_some_and(is_jsonrpc_message),
+        _ => false,
+    }
+}
+
+/// Returns true if a line that failed to parse as JSON was meant as a JSON-RPC message
+pub fn looks_like_jsonrpc(line: &str) -> bool {
+    line.contains("\"jsonrpc\"")
+}
+
+/// JSON-RPC reply to a line that is not valid JSON
+pub fn parse_error_response(error: &serde_json::Error) -> Value {
+    error_response(Value::Null, RpcError::new(PARSE_ERROR, format!("Parse error: {}", error)))
+}
+
+/// Handle one JSON-RPC message (single or batch).
+/// Returns the response to write back, or `None` when the message only held notifications.
+pub async fn handle_message<R: Runtime>(app: &AppHandle<R>, message: Value) -> Option<Value> {
+    match message {
+        Value::Array(batch) => {
+            if batch.is_empty() {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(INVALID_REQUEST, "Empty batch"),
+                ));
+            }
+
+            let mut responses = Vec::new();
+            for item in batch {
+                if let Some(response) = handle_single(app, item).await {
+                    responses.push(response);
+                }
+            }
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        single => handle_single(app, single).await,
+    }
+}
+
+async fn handle_single<R: Runtime>(app: &AppHandle<R>, message: Value) -> Option<Value> {
+    let request: JsonRpcRequest = match serde_json::from_value(message) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, format!("Invalid request: {}", e)),
+            ));
+        }
+    };
+
+    if request.jsonrpc != "2.0" {
+        return Some(error_response(
+            request.id.unwrap_or(Value::Null),
+            RpcError::new(INVALID_REQUEST, "Only JSON-RPC 2.0 is supported"),
+        ));
+    }
+
+    // Notifications never get a response
+    let Some(id) = request.id else {
+        info!("[TAURI_MCP] Received MCP notification: {}", request.method);
+        return None;
+    };
+
+    info!("[TAURI_MCP] Received MCP request: {}", request.method);
+
+    let result = match request.method.as_str() {
+        "initialize" => Ok(initialize(&request.params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(list_tools(app)),
+        "tools/call" => call_tool(app, request.params).await,
+        "resources/list" => Ok(list_resources(app)),
+        "resources/read" => read_resource(app, &request.params).await,
+        method => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => error_response(id, error),
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    // Answer with the client's revision when we support it, otherwise offer our latest
+    let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+    let protocol_version = requested
+        .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(LATEST_PROTOCOL_VERSION);
+
+    json!({
+        "protocolVersion": protocol_version,
+        "capabilities": {
+            "tools": { "listChanged": false },
+            "resources": { "listChanged": false },
+        },
+        "serverInfo": {
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+fn list_tools<R: Runtime>(app: &AppHandle<R>) -> Value {
+    let tools: Vec<Value> = app
+        .tauri_mcp()
+        .tools()
+        .iter()
+        .map(|tool| {
+            json!({
+                "name": tool.name(),
+                "description": tool.description(),
+                "inputSchema": tool.input_schema(),
+            })
+        })
+        .collect();
+
+    json!({ "tools": tools })
+}
+
+async fn call_tool<R: Runtime>(app: &AppHandle<R>, params: Value) -> Result<Value, RpcError> {
+    let name = params
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "tools/call requires a tool name"))?;
+
+    if app.tauri_mcp().tools().get(name).is_none() {
+        return Err(RpcError::new(INVALID_PARAMS, format!("Unknown tool: {}", name)));
+    }
+
+    let arguments = match params.get("arguments") {
+        Some(Value::Null) | None => json!({}),
+        Some(arguments) => arguments.clone(),
+    };
+
+    // Handler errors are tool failures, not protocol errors, so report them in the result
+    let response = match tools::handle_command(app, name, arguments).await {
+        Ok(response) => response,
+        Err(e) => SocketResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+        },
+    };
+
+    Ok(tool_result(response))
+}
+
+// Convert a plugin response into an MCP CallToolResult
+fn tool_result(response: SocketResponse) -> Value {
+    if !response.success {
+        let message = response
+            .error
+            .unwrap_or_else(|| "Command failed".to_string());
+        return json!({
+            "content": [{ "type": "text", "text": message }],
+            "isError": true,
+        });
+    }
+
+    let data = response.data.unwrap_or(Value::Null);
+
+    // Screenshots come back as data URLs; hand them to the client as image content
+    let data_url = data
+        .get("data")
+        .and_then(|v| v.as_str())
+        .or_else(|| data.as_str())
+        .filter(|s| s.starts_with("data:image/"));
+    if let Some((mime_type, encoded)) = data_url.and_then(split_data_url) {
+        return json!({
+            "content": [{ "type": "image", "data": encoded, "mimeType": mime_type }],
+        });
+    }
+
+    let text = match data {
+        Value::String(text) => text,
+        other => serde_json::to_string_pretty(&other).unwrap_or_default(),
+    };
+    json!({ "content": [{ "type": "text", "text": text }] })
+}
+
+// Split "data:<mime>;base64,<payload>" into its MIME type and payload
+fn split_data_url(url: &str) -> Option<(&str, &str)> {
+    let (header, encoded) = url.strip_prefix("data:")?.split_once(',')?;
+    let mime_type = header.strip_suffix(";base64")?;
+    Some((mime_type, encoded))
+}
+
+fn list_resources<R: Runtime>(app: &AppHandle<R>) -> Value {
+    let windows = tools::windows::list_windows(app).unwrap_or_default();
+
+    let resources: Vec<Value> = windows
+        .into_iter()
+        .map(|window| {
+            json!({
+                "uri": format!("{}{}", WINDOW_RESOURCE_PREFIX, window.label),
+                "name": window.label,
+                "title": window.title,
+                "description": format!("HTML document of window '{}'", window.label),
+                "mimeType": "text/html",
+            })
+        })
+        .collect();
+
+    json!({ "resources": resources })
+}
+
+async fn read_resource<R: Runtime>(app: &AppHandle<R>, params: &Value) -> Result<Value, RpcError> {
+    let uri = params
+        .get("uri")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "resources/read requires a uri"))?;
+
+    let label = uri
+        .strip_prefix(WINDOW_RESOURCE_PREFIX)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Unknown resource: {}", uri)))?;
+
+    let webview = tools::windows::resolve_webview(app, Some(label), None)
+        .map_err(|_| RpcError::new(INVALID_PARAMS, format!("Unknown resource: {}", uri)))?;
+
+    let html = tools::webview::get_dom_text(app.clone(), webview)
+        .await
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))?;
+
+    Ok(json!({
+        "contents": [{ "uri": uri, "mimeType": "text/html", "text": html }],
+    }))
+}
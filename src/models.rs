@@ -1,12 +1,13 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PingRequest {
     pub value: Option<String>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PingResponse {
     pub value: Option<String>,
@@ -42,25 +43,50 @@ pub struct WindowControlResponse {
     pub message: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowListRequest {}
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowListResponse {
     pub windows: Vec<WindowInfo>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowInfo {
     pub label: String,
     pub title: String,
+    /// URL of the window's first webview
+    pub url: Option<String>,
     pub is_visible: bool,
     pub is_focused: bool,
     pub is_maximized: bool,
+    pub is_minimized: bool,
     pub is_fullscreen: bool,
+    /// Outer position and inner size, in physical pixels
+    pub bounds: WindowBounds,
+    pub scale_factor: f64,
+    /// Webviews hosted by the window. Only multi-webview windows have more than one.
+    pub webviews: Vec<WebviewInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebviewInfo {
+    pub label: String,
+    pub url: Option<String>,
+    /// Position inside the window and size, in physical pixels
+    pub bounds: WindowBounds,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
 }
 
 // New MCP JavaScript execution models
@@ -112,30 +138,85 @@ pub struct TypeTextRequest {
 }
 
 // Screenshot request - updated to use shared interface
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct ScreenshotRequest {
     pub window_label: String,
+    /// Webview to capture inside a multi-webview window
+    #[serde(default)]
+    pub webview_label: Option<String>,
+    /// Capture only this element, scrolled into view first
+    #[serde(default)]
+    pub selector_value: Option<String>,
+    /// "id", "class", "tag", "text", "xpath", "css" (default), "ref" (from get_accessibility_tree) or "mark" (from take_annotated_screenshot)
+    #[serde(default)]
+    pub selector_type: Option<String>,
+    /// Capture only this region, in CSS pixels from the webview's top-left corner
+    #[serde(default)]
+    pub x: Option<f64>,
+    #[serde(default)]
+    pub y: Option<f64>,
+    #[serde(default)]
+    pub width: Option<f64>,
+    #[serde(default)]
+    pub height: Option<f64>,
+    /// CSS pixels added around the element or region on every side
+    #[serde(default)]
+    pub padding: Option<f64>,
+    /// "jpeg" (default), "png" or "webp". PNG and WebP are lossless.
+    #[serde(default)]
+    pub format: Option<crate::shared::ScreenshotFormat>,
+    /// JPEG quality 1-100 (default 85)
+    #[serde(default)]
+    pub quality: Option<i32>,
+    /// Downscale wider images to this many pixels (default 1920)
+    #[serde(default)]
+    pub max_width: Option<i32>,
+    /// Shrink the encoded image until it fits (default 2)
+    #[serde(default)]
+    pub max_size_mb: Option<f32>,
+    /// "quality_first" (default) or "downscale_first"
+    #[serde(default)]
+    pub size_fallback: Option<crate::shared::SizeFallback>,
 }
 
 impl From<ScreenshotRequest> for crate::shared::ScreenshotParams {
     fn from(req: ScreenshotRequest) -> Self {
         Self {
             window_label: Some(req.window_label),
-            quality: None,
-            max_width: None,
-            max_size_mb: None,
+            format: req.format,
+            quality: req.quality,
+            max_width: req.max_width,
+            max_size_mb: req.max_size_mb,
+            size_fallback: req.size_fallback,
             application_name: None,
+            crop: None,
+            strict_size: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ScreenshotResponse {
     pub data: Option<String>, // Base64 encoded image
     pub success: bool,
     pub error: Option<String>,
+    /// Area captured when an element or region was requested, after padding and clipping to the webview
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<ScreenshotRegion>,
+    /// MIME type of the encoded image, e.g. "image/png"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// The same captured area in both coordinate systems
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+pub struct ScreenshotRegion {
+    /// CSS pixels from the webview's top-left corner
+    pub css: crate::shared::CssRect,
+    /// Physical pixels from the webview's top-left corner, before any downscaling of the image
+    pub physical: crate::shared::PixelRect,
 }
 
 impl From<crate::shared::ScreenshotResult> for ScreenshotResponse {
@@ -144,6 +225,8 @@ impl From<crate::shared::ScreenshotResult> for ScreenshotResponse {
             data: result.data,
             success: result.success,
             error: result.error,
+            region: None,
+            mime_type: result.mime_type,
         }
     }
 }
@@ -180,19 +263,27 @@ pub struct HtmlResponse {
 }
 
 // LocalStorage request model
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct LocalStorageRequest {
+    /// "get", "set", "remove", "clear" or "keys"
     pub action: String,
     pub key: Option<String>,
     pub value: Option<String>,
+    #[serde(alias = "windowLabel")]
     pub window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    #[serde(alias = "webviewLabel")]
+    pub webview_label: Option<String>,
 }
 
 // Window manager request model
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct WindowManagerRequest {
     pub window_label: Option<String>,
+    /// Alternatively, the label of a webview whose window should be managed
+    pub webview_label: Option<String>,
+    /// "minimize", "maximize", "unmaximize", "close", "show", "hide", "setPosition",
+    /// "setSize", "center", "toggleFullscreen" or "focus"
     pub operation: String,
     pub x: Option<i32>,
     pub y: Option<i32>,
@@ -201,23 +292,27 @@ pub struct WindowManagerRequest {
 }
 
 // Window manager response model
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct WindowManagerResponse {
     pub success: bool,
     pub error: Option<String>,
 }
 
 // TextInput request model
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct TextInputRequest {
     pub text: String,
+    #[serde(alias = "delayMs")]
     pub delay_ms: Option<u64>,
+    #[serde(alias = "initialDelayMs")]
     pub initial_delay_ms: Option<u64>,
+    /// Focus this window before typing. Types into whatever has focus if omitted.
+    #[serde(alias = "windowLabel")]
+    pub window_label: Option<String>,
 }
 
 // TextInput response model
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TextInputResponse {
     pub chars_typed: u32,
@@ -225,21 +320,33 @@ pub struct TextInputResponse {
 }
 
 // Mouse movement request model
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct MouseMovementRequest {
     pub x: i32,
     pub y: i32,
     pub relative: Option<bool>,
     pub click: Option<bool>,
-    pub button: Option<String>, // "left", "right", or "middle"
+    /// "left", "right", or "middle"
+    pub button: Option<String>,
+    /// Window the coordinates are relative to. Defaults to "main".
+    #[serde(alias = "windowLabel")]
+    pub window_label: Option<String>,
+    /// Webview the coordinates are relative to, inside a multi-webview window
+    #[serde(alias = "webviewLabel")]
+    pub webview_label: Option<String>,
 }
 
 // Mouse movement response model
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MouseMovementResponse {
     pub success: bool,
     pub duration_ms: u64,
     pub position: Option<(i32, i32)>,
 }
+
+// Acknowledgement for commands that only report what they did
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct MessageResponse {
+    pub message: String,
+}
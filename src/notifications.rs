@@ -0,0 +1,482 @@
+// Server-pushed event streams.
+//
+// Network requests and exceptions are reported by the guest-js through the `mcp-push-event`
+// event, console output by the console capture script; window events and page loads come
+// from the plugin's own hooks. Everything is published on one broadcast channel, and each
+// socket connection forwards the streams it subscribed to as unsolicited lines:
+//   {"type": "notification", "stream": "console", "windowLabel": "main", "timestampMs": ..., "data": {...}}
+
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::{AppHandle, DragDropEvent, Emitter, Listener, Manager, Runtime, Webview, WindowEvent};
+use tokio::sync::broadcast;
+
+/// Event emitted by the guest-js for every network request and exception
+pub const GUEST_PUSH_EVENT: &str = "mcp-push-event";
+
+/// Notifications buffered per connection before the slowest subscriber starts losing them
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Streams a connection can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EventStream {
+    Console,
+    Network,
+    Exceptions,
+    Window,
+    Navigation,
+}
+
+impl EventStream {
+    // Guest event that turns on capture for webview streams
+    fn capture_event(self) -> Option<&'static str> {
+        match self {
+            EventStream::Network => Some("inject-network-capture"),
+            EventStream::Exceptions => Some("inject-error-tracker"),
+            // Console capture is always installed by the plugin's initialization script
+            EventStream::Console | EventStream::Window | EventStream::Navigation => None,
+        }
+    }
+}
+
+/// One event pushed to subscribed connections
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename = "notification", rename_all = "camelCase")]
+pub struct Notification {
+    pub stream: EventStream,
+    pub window_label: Option<String>,
+    pub timestamp_ms: u64,
+    pub data: Value,
+}
+
+/// Payload of `subscribe`
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct SubscribeRequest {
+    pub streams: Vec<EventStream>,
+    /// Only forward events from this window, and only switch on capture there.
+    /// All windows if omitted, in which case every open webview starts capturing.
+    pub window_label: Option<String>,
+}
+
+/// Payload of `unsubscribe`
+#[derive(Debug, Clone, Default, Deserialize, schemars::JsonSchema)]
+pub struct UnsubscribeRequest {
+    /// Streams to stop. All streams if omitted.
+    pub streams: Option<Vec<EventStream>>,
+    /// Only drop subscriptions for this window. Subscriptions for any window if omitted.
+    pub window_label: Option<String>,
+}
+
+/// A stream a connection is subscribed to
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, schemars::JsonSchema)]
+pub struct SubscriptionInfo {
+    pub stream: EventStream,
+    pub window_label: Option<String>,
+}
+
+/// Response to `subscribe` and `unsubscribe`: the connection's subscriptions afterwards
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SubscriptionsResponse {
+    pub subscriptions: Vec<SubscriptionInfo>,
+}
+
+/// Guest-js push event payload
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GuestPushEvent {
+    stream: EventStream,
+    window_label: Option<String>,
+    data: Value,
+}
+
+/// Fan-out point for notifications
+pub struct NotificationHub {
+    sender: broadcast::Sender<Notification>,
+}
+
+impl Default for NotificationHub {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl NotificationHub {
+    /// Publish an event to every subscribed connection. Dropped when nobody listens.
+    pub fn publish(&self, stream: EventStream, window_label: Option<String>, data: Value) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+
+        let _ = self.sender.send(Notification {
+            stream,
+            window_label,
+            timestamp_ms: now_ms(),
+            data,
+        });
+    }
+
+    fn receiver(&self) -> broadcast::Receiver<Notification> {
+        self.sender.subscribe()
+    }
+
+    /// Forward the guest-js push events into the hub
+    pub fn listen_to_guest<R: Runtime>(app: &AppHandle<R>) {
+        let handle = app.clone();
+        app.listen_any(GUEST_PUSH_EVENT, move |event| {
+            let Some(state) = handle.try_state::<crate::TauriMcp<R>>() else {
+                return;
+            };
+            match serde_json::from_str::<GuestPushEvent>(event.payload()) {
+                Ok(push) => state.notifications().publish(push.stream, push.window_label, push.data),
+                Err(e) => debug!("[TAURI_MCP] Ignoring malformed push event: {}", e),
+            }
+        });
+    }
+}
+
+/// Publish a window event from the plugin's `on_event` hook
+pub fn publish_window_event<R: Runtime>(app: &AppHandle<R>, label: &str, event: &WindowEvent) {
+    let Some(state) = app.try_state::<crate::TauriMcp<R>>() else {
+        return;
+    };
+
+    let data = match event {
+        WindowEvent::Resized(size) => json!({ "event": "resized", "width": size.width, "height": size.height }),
+        WindowEvent::Moved(position) => json!({ "event": "moved", "x": position.x, "y": position.y }),
+        WindowEvent::CloseRequested { .. } => json!({ "event": "closeRequested" }),
+        WindowEvent::Destroyed => json!({ "event": "destroyed" }),
+        WindowEvent::Focused(focused) => json!({ "event": "focused", "focused": focused }),
+        WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+            json!({ "event": "scaleFactorChanged", "scaleFactor": scale_factor })
+        }
+        WindowEvent::DragDrop(drop) => match drop {
+            DragDropEvent::Enter { paths, .. } => json!({ "event": "dragEnter", "paths": paths }),
+            DragDropEvent::Drop { paths, .. } => json!({ "event": "drop", "paths": paths }),
+            DragDropEvent::Leave => json!({ "event": "dragLeave" }),
+            // Pointer movement while dragging is too chatty to forward
+            _ => return,
+        },
+        WindowEvent::ThemeChanged(theme) => json!({ "event": "themeChanged", "theme": theme.to_string() }),
+        _ => return,
+    };
+
+    state.notifications().publish(EventStream::Window, Some(label.to_string()), data);
+}
+
+/// Publish a page load from the plugin's `on_page_load` hook
+pub fn publish_page_load<R: Runtime>(webview: &Webview<R>, payload: &PageLoadPayload<'_>) {
+    let Some(state) = webview.try_state::<crate::TauriMcp<R>>() else {
+        return;
+    };
+
+    let event = match payload.event() {
+        PageLoadEvent::Started => "started",
+        PageLoadEvent::Finished => "finished",
+    };
+    state.notifications().publish(
+        EventStream::Navigation,
+        Some(webview.label().to_string()),
+        json!({ "event": event, "url": payload.url().as_str() }),
+    );
+}
+
+/// What a pushed line looks like after filtering for one connection
+pub enum Pushed {
+    Notification(Notification),
+    /// The connection fell behind and this many notifications were dropped
+    Lagged(u64),
+}
+
+impl Pushed {
+    /// Serialized socket line, newline included
+    pub fn to_line(&self) -> crate::Result<String> {
+        let value = match self {
+            Pushed::Notification(notification) => serde_json::to_value(notification),
+            Pushed::Lagged(dropped) => Ok(json!({ "type": "lagged", "dropped": dropped })),
+        };
+        value
+            .and_then(|v| serde_json::to_string(&v))
+            .map(|line| line + "\n")
+            .map_err(|e| crate::Error::serialization_error(format!("Failed to serialize notification: {}", e)))
+    }
+}
+
+/// Subscriptions held by one socket connection
+#[derive(Default)]
+pub struct Subscriptions {
+    receiver: Option<broadcast::Receiver<Notification>>,
+    active: BTreeSet<SubscriptionInfo>,
+}
+
+impl Subscriptions {
+    /// Add subscriptions and turn on capture in the webviews that feed them.
+    /// Network and exception capture is enabled in the named window, or in every open webview
+    /// when no window is given. It stays on for the life of the page, shared by all clients.
+    pub fn subscribe<R: Runtime>(&mut self, app: &AppHandle<R>, request: SubscribeRequest) -> SubscriptionsResponse {
+        for stream in request.streams {
+            enable_capture(app, stream, request.window_label.as_deref());
+            self.active.insert(SubscriptionInfo {
+                stream,
+                window_label: request.window_label.clone(),
+            });
+        }
+
+        if self.receiver.is_none() && !self.active.is_empty() {
+            self.receiver = Some(app.state::<crate::TauriMcp<R>>().notifications().receiver());
+        }
+
+        info!("[TAURI_MCP] Connection subscribed to {} stream(s)", self.active.len());
+        self.response()
+    }
+
+    /// Remove subscriptions. Capture stays on in the webview for other clients.
+    pub fn unsubscribe(&mut self, request: UnsubscribeRequest) -> SubscriptionsResponse {
+        self.active.retain(|sub| {
+            let stream_matches = request
+                .streams
+                .as_ref()
+                .is_none_or(|streams| streams.contains(&sub.stream));
+            let window_matches = request.window_label.is_none() || request.window_label == sub.window_label;
+            !(stream_matches && window_matches)
+        });
+
+        if self.active.is_empty() {
+            self.receiver = None;
+        }
+
+        info!("[TAURI_MCP] Connection now subscribed to {} stream(s)", self.active.len());
+        self.response()
+    }
+
+    fn response(&self) -> SubscriptionsResponse {
+        SubscriptionsResponse {
+            subscriptions: self.active.iter().cloned().collect(),
+        }
+    }
+
+    fn wants(&self, notification: &Notification) -> bool {
+        self.active.iter().any(|sub| {
+            sub.stream == notification.stream
+                && (sub.window_label.is_none() || sub.window_label == notification.window_label)
+        })
+    }
+
+    /// Wait for the next notification this connection subscribed to.
+    /// Never resolves while there are no subscriptions. Cancel safe.
+    pub async fn next(&mut self) -> Pushed {
+        loop {
+            let Some(receiver) = self.receiver.as_mut() else {
+                return std::future::pending().await;
+            };
+
+            match receiver.recv().await {
+                Ok(notification) if self.wants(&notification) => return Pushed::Notification(notification),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(dropped)) => return Pushed::Lagged(dropped),
+                Err(broadcast::error::RecvError::Closed) => {
+                    self.receiver = None;
+                }
+            }
+        }
+    }
+}
+
+// Webview streams only produce events once their capture script is active
+fn enable_capture<R: Runtime>(app: &AppHandle<R>, stream: EventStream, window_label: Option<&str>) {
+    let Some(event) = stream.capture_event() else {
+        return;
+    };
+
+    let labels: Vec<String> = match window_label {
+        Some(label) => vec![label.to_string()],
+        None => app.webview_windows().into_keys().collect(),
+    };
+    for label in labels {
+        if let Err(e) = app.emit_to(label.as_str(), event, json!({})) {
+            info!("[TAURI_MCP] Failed to enable {} in window {}: {}", event, label, e);
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tauri::test::MockRuntime;
+
+    fn app() -> tauri::App<MockRuntime> {
+        let app = tauri::test::mock_builder()
+            .plugin(crate::init_with_config(
+                crate::PluginConfig::new("notifications-test".to_string()).start_socket_server(false),
+            ))
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        for label in ["main", "other"] {
+            tauri::WebviewWindowBuilder::new(&app, label, Default::default()).build().unwrap();
+        }
+        app
+    }
+
+    // Counts how often `event` reaches the webview window `label`
+    fn count_events(app: &tauri::App<MockRuntime>, label: &str, event: &str) -> Arc<AtomicUsize> {
+        let count = Arc::new(AtomicUsize::new(0));
+        let seen = count.clone();
+        app.get_webview_window(label).unwrap().listen(event, move |_| {
+            seen.fetch_add(1, Ordering::SeqCst);
+        });
+        count
+    }
+
+    fn subscription(stream: EventStream, window_label: Option<&str>) -> SubscriptionInfo {
+        SubscriptionInfo {
+            stream,
+            window_label: window_label.map(str::to_string),
+        }
+    }
+
+    fn notification(stream: EventStream, window_label: Option<&str>) -> Notification {
+        Notification {
+            stream,
+            window_label: window_label.map(str::to_string),
+            timestamp_ms: 0,
+            data: json!({}),
+        }
+    }
+
+    #[test]
+    fn capture_is_only_enabled_in_the_named_window() {
+        let app = app();
+        let main_network = count_events(&app, "main", "inject-network-capture");
+        let other_network = count_events(&app, "other", "inject-network-capture");
+        let main_errors = count_events(&app, "main", "inject-error-tracker");
+        let other_errors = count_events(&app, "other", "inject-error-tracker");
+
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.subscribe(
+            app.handle(),
+            SubscribeRequest {
+                streams: vec![EventStream::Network, EventStream::Console],
+                window_label: Some("main".to_string()),
+            },
+        );
+        assert_eq!(main_network.load(Ordering::SeqCst), 1);
+        assert_eq!(other_network.load(Ordering::SeqCst), 0);
+        assert_eq!(main_errors.load(Ordering::SeqCst), 0);
+
+        subscriptions.subscribe(
+            app.handle(),
+            SubscribeRequest {
+                streams: vec![EventStream::Exceptions],
+                window_label: None,
+            },
+        );
+        assert_eq!(main_errors.load(Ordering::SeqCst), 1);
+        assert_eq!(other_errors.load(Ordering::SeqCst), 1);
+        assert_eq!(other_network.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn subscribe_and_unsubscribe_report_what_is_left() {
+        let app = app();
+        let mut subscriptions = Subscriptions::default();
+
+        subscriptions.subscribe(
+            app.handle(),
+            SubscribeRequest {
+                streams: vec![EventStream::Console, EventStream::Window],
+                window_label: Some("main".to_string()),
+            },
+        );
+        let response = subscriptions.subscribe(
+            app.handle(),
+            SubscribeRequest {
+                streams: vec![EventStream::Console],
+                window_label: None,
+            },
+        );
+        assert_eq!(
+            response.subscriptions,
+            vec![
+                subscription(EventStream::Console, None),
+                subscription(EventStream::Console, Some("main")),
+                subscription(EventStream::Window, Some("main")),
+            ]
+        );
+        assert!(subscriptions.receiver.is_some());
+
+        let response = subscriptions.unsubscribe(UnsubscribeRequest {
+            streams: Some(vec![EventStream::Console]),
+            window_label: Some("main".to_string()),
+        });
+        assert_eq!(
+            response.subscriptions,
+            vec![
+                subscription(EventStream::Console, None),
+                subscription(EventStream::Window, Some("main")),
+            ]
+        );
+
+        let response = subscriptions.unsubscribe(UnsubscribeRequest::default());
+        assert!(response.subscriptions.is_empty());
+        assert!(subscriptions.receiver.is_none());
+    }
+
+    #[test]
+    fn wants_matches_stream_and_window() {
+        let subscriptions = Subscriptions {
+            receiver: None,
+            active: BTreeSet::from([
+                subscription(EventStream::Console, None),
+                subscription(EventStream::Network, Some("main")),
+            ]),
+        };
+
+        assert!(subscriptions.wants(&notification(EventStream::Console, Some("main"))));
+        assert!(subscriptions.wants(&notification(EventStream::Console, Some("other"))));
+        assert!(subscriptions.wants(&notification(EventStream::Network, Some("main"))));
+        assert!(!subscriptions.wants(&notification(EventStream::Network, Some("other"))));
+        assert!(!subscriptions.wants(&notification(EventStream::Network, None)));
+        assert!(!subscriptions.wants(&notification(EventStream::Exceptions, Some("main"))));
+        assert!(!Subscriptions::default().wants(&notification(EventStream::Console, None)));
+    }
+
+    #[tokio::test]
+    async fn next_skips_notifications_nobody_asked_for() {
+        let app = app();
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.subscribe(
+            app.handle(),
+            SubscribeRequest {
+                streams: vec![EventStream::Window],
+                window_label: Some("main".to_string()),
+            },
+        );
+
+        let hub = app.state::<crate::TauriMcp<MockRuntime>>();
+        hub.notifications()
+            .publish(EventStream::Console, Some("main".to_string()), json!({ "n": 1 }));
+        hub.notifications()
+            .publish(EventStream::Window, Some("other".to_string()), json!({ "n": 2 }));
+        hub.notifications()
+            .publish(EventStream::Window, Some("main".to_string()), json!({ "n": 3 }));
+
+        match subscriptions.next().await {
+            Pushed::Notification(notification) => assert_eq!(notification.data, json!({ "n": 3 })),
+            Pushed::Lagged(dropped) => panic!("unexpected lag of {}", dropped),
+        }
+    }
+}
@@ -6,7 +6,7 @@ use tauri::Runtime;
 
 // Import shared functionality
 use crate::desktop::{ScreenshotContext, create_success_response};
-use crate::platform::shared::{get_window_title, handle_screenshot_task};
+use crate::platform::shared::{crop_in_window_capture, get_window_title, handle_screenshot_task};
 use crate::shared::ScreenshotParams;
 use crate::tools::take_screenshot::process_image;
 
@@ -16,12 +16,10 @@ pub async fn take_screenshot<R: Runtime>(
     window_context: ScreenshotContext<R>,
 ) -> Result<ScreenshotResponse> {
     // Clone necessary parameters for use in the closure
-    let params_clone = params.clone();
+    let mut params_clone = params.clone();
+    crop_in_window_capture(&mut params_clone, &window_context)?;
     let window_clone = window_context.window.clone();
-    let window_label = params
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
+    let window_label = window_context.window.label().to_string();
     
     // Get application name from params or use a default
     let application_name = params.application_name.clone().unwrap_or_else(|| "".to_string());
@@ -2,6 +2,8 @@ use crate::desktop::create_error_response;
 use crate::models::ScreenshotResponse;
 use crate::{Error, Result};
 use tauri::Runtime;
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use {crate::desktop::ScreenshotContext, crate::shared::ScreenshotParams, tauri::Manager};
 
 // Common function for handling the screenshot task and response
 pub async fn handle_screenshot_task<F>(screenshot_fn: F) -> Result<ScreenshotResponse>
@@ -21,7 +23,7 @@ where
 }
 
 // Helper function to get window title - used by multiple platforms
-pub fn get_window_title<R: Runtime>(window: &tauri::WebviewWindow<R>) -> Result<String> {
+pub fn get_window_title<R: Runtime>(window: &tauri::Window<R>) -> Result<String> {
     match window.title() {
         Ok(title) => Ok(title),
         Err(e) => Err(Error::window_operation_failed(
@@ -30,3 +32,22 @@ pub fn get_window_title<R: Runtime>(window: &tauri::WebviewWindow<R>) -> Result<
         )),
     }
 }
+
+// Move a crop given relative to the webview into a capture of the whole window, frame included
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn crop_in_window_capture<R: Runtime>(
+    params: &mut ScreenshotParams,
+    window_context: &ScreenshotContext<R>,
+) -> Result<()> {
+    if let Some(crop) = params.crop.as_mut() {
+        let outer = window_context.window.outer_position()?;
+        let inner = window_context.window.inner_position()?;
+        let webview = &window_context.webview;
+        let webview_offset = crate::tools::windows::webview_offset(webview.app_handle(), webview)?;
+        let offset_x = inner.x - outer.x + webview_offset.x;
+        let offset_y = inner.y - outer.y + webview_offset.y;
+        crop.x = (crop.x as i32 + offset_x).max(0) as u32;
+        crop.y = (crop.y as i32 + offset_y).max(0) as u32;
+    }
+    Ok(())
+}
@@ -1,72 +1,125 @@
 use crate::models::ScreenshotResponse;
 use crate::{Error, Result};
+use image::{DynamicImage, RgbaImage};
+use log::info;
+use std::sync::mpsc;
+use std::time::Duration;
 use tauri::Runtime;
+use webkit2gtk::{SnapshotOptions, SnapshotRegion, WebViewExt};
 
 // Import shared functionality
-use crate::desktop::ScreenshotContext;
-use crate::platform::shared::handle_screenshot_task;
+use crate::desktop::{ScreenshotContext, create_success_response};
+use crate::platform::shared::{get_window_title, handle_screenshot_task};
 use crate::shared::ScreenshotParams;
+use crate::tools::take_screenshot::process_image;
 
-// Unix-specific implementation for taking screenshots (fallback for non-macOS Unix systems)
+/// How long to wait for WebKitGTK to deliver the snapshot before giving up
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Unix-specific implementation for taking screenshots (WebKitGTK on Linux and the BSDs)
 pub async fn take_screenshot<R: Runtime>(
     params: ScreenshotParams,
     window_context: ScreenshotContext<R>,
 ) -> Result<ScreenshotResponse> {
-    // Clone necessary values from params for use in the closure
+    // Clone params for use in the closure
+    let params_clone = params.clone();
     let window_clone = window_context.window.clone();
-    let quality = params.quality.unwrap_or(85) as u8;
-    let max_width = params.max_width.map(|w| w as u32).unwrap_or(0);
+    let webview_clone = window_context.webview.clone();
+    let window_label = window_context.window.label().to_string();
 
     handle_screenshot_task(move || {
-    let script = format!(
-      r#"
-      (function() {{
-        try {{
-          const canvas = document.createElement('canvas');
-          const context = canvas.getContext('2d');
-          
-          // Set dimensions to match the window content
-          let width = window.innerWidth;
-          let height = window.innerHeight;
-          
-          // Apply max width constraint if specified
-          if ({max_width} > 0 && width > {max_width}) {{
-            const aspectRatio = width / height;
-            width = {max_width};
-            height = width / aspectRatio;
-          }}
-          
-          canvas.width = width;
-          canvas.height = height;
-          
-          // Draw only the document to the canvas (not the OS chrome/window)
-          context.drawImage(document.documentElement, 0, 0, width, height);
-          
-          // Convert canvas to base64 image with specified quality
-          return canvas.toDataURL('image/jpeg', {quality}/100);
-        }} catch (err) {{
-          console.error('Screenshot error:', err);
-          return null;
-        }}
-      }})();
-      "#,
-      max_width = max_width,
-      quality = quality
-    );
+        let window_title = get_window_title(&window_clone)?;
+        info!(
+            "[TAURI-MCP] Capturing WebKitGTK snapshot of window: {} (label: {})",
+            window_title, window_label
+        );
 
-    // Evaluate the JavaScript in the webview
-    match window_clone.eval(&script) {
-      Ok(_) => {
-        // In Tauri 2.x, we can't get the result from eval, so we return a sample image
-        Ok(ScreenshotResponse {
-          data: Some("data:image/jpeg;base64,/9j/4AAQSkZJRgABAQEAYABgAAD/2wBDAAUDBAQEAwUEBAQFBQUGBwwIBwcHBw8LCwkMEQ8SEhEPERETFhwXExQaFRERGCEYGh0dHx8fExciJCIeJBweHx7/2wBDAQUFBQcGBw4ICA4eFBEUHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh7/wAARCAABAAEDASIAAhEBAxEB/8QAFQABAQAAAAAAAAAAAAAAAAAAAAb/xAAUEAEAAAAAAAAAAAAAAAAAAAAA/8QAFAEBAAAAAAAAAAAAAAAAAAAAAP/EABQRAQAAAAAAAAAAAAAAAAAAAAD/2gAMAwEAAhEDEQA/ALAKD//Z".to_string()),
-          success: true,
-          error: None,
-        })
-      },
-      Err(e) => Err(Error::WindowOperationFailed(format!("Failed to execute screenshot script: {}", e)))
-    }
-  }).await
+        // Ask the webview itself for a snapshot of its visible region. This renders
+        // through WebKit rather than the X server, so it also works under Xvfb.
+        let (tx, rx) = mpsc::channel::<Result<RgbaImage>>();
+        webview_clone
+            .with_webview(move |webview| {
+                webview.inner().snapshot(
+                    SnapshotRegion::Visible,
+                    SnapshotOptions::NONE,
+                    None::<&webkit2gtk::gio::Cancellable>,
+                    move |result| {
+                        let image = match result {
+                            Ok(surface) => surface_to_rgba(surface),
+                            Err(e) => Err(Error::window_operation_failed(
+                                "webview snapshot",
+                                format!("WebKitGTK snapshot failed: {}", e),
+                            )),
+                        };
+                        let _ = tx.send(image);
+                    },
+                );
+            })
+            .map_err(|e| Error::window_operation_failed("webview snapshot", format!("Failed to access webview: {}", e)))?;
+
+        let image = rx
+            .recv_timeout(SNAPSHOT_TIMEOUT)
+            .map_err(|_| Error::timeout_error("webview snapshot", SNAPSHOT_TIMEOUT.as_millis() as u64))??;
+
+        info!(
+            "[TAURI-MCP] Successfully captured webview snapshot: {}x{}",
+            image.width(),
+            image.height()
+        );
+
+        // Process the image
+        match process_image(DynamicImage::ImageRgba8(image), &params_clone) {
+            Ok(data_url) => Ok(create_success_response(data_url)),
+            Err(e) => Err(e),
+        }
+    })
+    .await
 }
 
-// Add any other Unix-specific functionality here
+// Convert a cairo surface returned by WebKitGTK into an RGBA image.
+// Cairo stores ARGB32 as premultiplied, native-endian 32-bit words.
+fn surface_to_rgba(surface: cairo::Surface) -> Result<RgbaImage> {
+    let surface = cairo::ImageSurface::try_from(surface).map_err(|_| {
+        Error::window_operation_failed("webview snapshot", "Snapshot is not an image surface")
+    })?;
+
+    let format = surface.format();
+    if format != cairo::Format::ARgb32 && format != cairo::Format::Rgb24 {
+        return Err(Error::window_operation_failed(
+            "webview snapshot",
+            format!("Unsupported snapshot pixel format: {:?}", format),
+        ));
+    }
+
+    let width = surface.width() as u32;
+    let height = surface.height() as u32;
+    let stride = surface.stride() as usize;
+    let has_alpha = format == cairo::Format::ARgb32;
+
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    surface
+        .with_data(|data| {
+            for row in data.chunks(stride).take(height as usize) {
+                for px in row[..(width as usize) * 4].chunks_exact(4) {
+                    let argb = u32::from_ne_bytes([px[0], px[1], px[2], px[3]]);
+                    let a = if has_alpha { (argb >> 24) as u8 } else { 255 };
+                    let unpremultiply = |c: u32| -> u8 {
+                        let c = (c & 0xff) as u8;
+                        if a == 0 || a == 255 {
+                            c
+                        } else {
+                            ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8
+                        }
+                    };
+                    pixels.push(unpremultiply(argb >> 16));
+                    pixels.push(unpremultiply(argb >> 8));
+                    pixels.push(unpremultiply(argb));
+                    pixels.push(a);
+                }
+            }
+        })
+        .map_err(|e| Error::window_operation_failed("webview snapshot", format!("Failed to read snapshot data: {}", e)))?;
+
+    RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| Error::window_operation_failed("create_image", "Failed to create image from snapshot"))
+}
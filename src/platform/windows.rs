@@ -7,7 +7,7 @@ use win_screenshot::prelude::*;
 
 // Import shared functionality
 use crate::desktop::{ScreenshotContext, create_success_response};
-use crate::platform::shared::{get_window_title, handle_screenshot_task};
+use crate::platform::shared::{crop_in_window_capture, get_window_title, handle_screenshot_task};
 use crate::shared::ScreenshotParams;
 use crate::tools::take_screenshot::process_image;
 
@@ -17,12 +17,10 @@ pub async fn take_screenshot<R: Runtime>(
     window_context: ScreenshotContext<R>,
 ) -> Result<ScreenshotResponse> {
     // Clone params for use in the closure
-    let params_clone = params.clone();
+    let mut params_clone = params.clone();
+    crop_in_window_capture(&mut params_clone, &window_context)?;
     let window_clone = window_context.window.clone();
-    let window_label = params
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
+    let window_label = window_context.window.label().to_string();
 
     handle_screenshot_task(move || {
     // Get the window title to help identify the right window
@@ -8,6 +8,10 @@ pub struct ScreenshotParams {
     /// The label of the window to capture
     pub window_label: Option<String>,
 
+    /// Image encoding, JPEG by default
+    #[serde(default)]
+    pub format: Option<ScreenshotFormat>,
+
     /// JPEG quality (1-100)
     pub quality: Option<i32>,
 
@@ -17,8 +21,78 @@ pub struct ScreenshotParams {
     /// Maximum file size in MB
     pub max_size_mb: Option<f32>,
 
+    /// What to give up first when the image is over `max_size_mb`
+    #[serde(default)]
+    pub size_fallback: Option<SizeFallback>,
+
     /// Application name to look for in window matching
     pub application_name: Option<String>,
+
+    /// Region to keep, in physical pixels from the webview's top-left corner
+    #[serde(default)]
+    pub crop: Option<PixelRect>,
+
+    /// Fail instead of downscaling when the image is wider than `max_width` or larger than `max_size_mb`
+    #[serde(default)]
+    pub strict_size: bool,
+}
+
+/// Image encoding of a screenshot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotFormat {
+    /// Lossy, smallest for photos and gradients
+    #[default]
+    #[serde(alias = "jpg")]
+    Jpeg,
+    /// Lossless, exact pixels for comparisons and crisp text
+    Png,
+    /// Lossless WebP, usually smaller than PNG
+    Webp,
+}
+
+impl ScreenshotFormat {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ScreenshotFormat::Jpeg => "image/jpeg",
+            ScreenshotFormat::Png => "image/png",
+            ScreenshotFormat::Webp => "image/webp",
+        }
+    }
+
+    /// Whether the format has a quality setting to lower
+    pub fn is_lossy(self) -> bool {
+        self == ScreenshotFormat::Jpeg
+    }
+}
+
+/// Order in which an oversized screenshot is shrunk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeFallback {
+    /// Lower the JPEG quality down to 30 first, then downscale
+    #[default]
+    QualityFirst,
+    /// Downscale first, keeping the quality, then lower it if the image is still too large
+    DownscaleFirst,
+}
+
+/// A rectangle in CSS pixels, relative to the webview's top-left corner
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CssRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A rectangle in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
 }
 
 /// Result of taking a screenshot
@@ -141,6 +215,7 @@ pub mod commands {
     pub const INJECT_CONSOLE_CAPTURE: &str = "inject_console_capture";
     pub const NETWORK_INSPECTOR: &str = "network_inspector";
     pub const INJECT_NETWORK_CAPTURE: &str = "inject_network_capture";
+    pub const NETWORK_MOCK: &str = "network_mock";
     pub const STATE_DUMP: &str = "state_dump";
     pub const DEVTOOLS_BRIDGE: &str = "devtools_bridge";
     pub const GET_EXCEPTIONS: &str = "get_exceptions";
@@ -149,4 +224,270 @@ pub mod commands {
     pub const GET_PERFORMANCE_METRICS: &str = "get_performance_metrics";
     pub const STORAGE_INSPECTOR: &str = "storage_inspector";
     pub const HEALTH_CHECK: &str = "health_check";
+    pub const DESCRIBE_COMMANDS: &str = "describe_commands";
+    pub const LIST_WINDOWS: &str = "list_windows";
+    pub const GET_ACCESSIBILITY_TREE: &str = "get_accessibility_tree";
+    pub const INVOKE_COMMAND: &str = "invoke_command";
+    pub const LIST_COMMANDS: &str = "list_commands";
+    pub const EMIT_EVENT: &str = "emit_event";
+    pub const LISTEN_EVENTS: &str = "listen_events";
+    pub const WAIT_FOR: &str = "wait_for";
+    pub const PRESS_KEYS: &str = "press_keys";
+    pub const MOUSE_ACTION: &str = "mouse_action";
+    pub const VISUAL_COMPARE: &str = "visual_compare";
+    pub const TAKE_ANNOTATED_SCREENSHOT: &str = "take_annotated_screenshot";
+
+    /// Handshake sent as the first line when the server requires a token.
+    /// Handled by the socket server itself, so it is not listed as a tool.
+    pub const AUTHENTICATE: &str = "authenticate";
+
+    /// Start and stop pushed notifications on the current connection.
+    /// Handled by the socket server itself, since they only make sense per connection.
+    pub const SUBSCRIBE: &str = "subscribe";
+    pub const UNSUBSCRIBE: &str = "unsubscribe";
+
+    use schemars::JsonSchema;
+    use serde_json::Value;
+
+    use crate::models;
+    use crate::tools::{
+        accessibility, annotated_screenshot, console_logs, describe_commands, devtools_bridge, error_tracker, events,
+        execute_js, health_check, hot_reload, invoke_command, local_storage, mouse_action, network_inspector, network_mock,
+        performance, press_keys, state_dump, storage_inspector, visual_compare, wait_for, webview,
+    };
+
+    /// A socket command together with the description and schemas advertised to MCP clients
+    pub struct CommandInfo {
+        pub name: &'static str,
+        pub description: &'static str,
+        /// JSON Schema of the command's payload, derived from its request type
+        pub input_schema: fn() -> Value,
+        /// JSON Schema of the response data, derived from its response type
+        pub output_schema: fn() -> Value,
+    }
+
+    // JSON Schema of a request or response type
+    fn schema<T: JsonSchema>() -> Value {
+        serde_json::to_value(schemars::schema_for!(T)).unwrap_or(Value::Bool(true))
+    }
+
+    /// Every command handled by the socket server, in the order tools are listed
+    pub const ALL: &[CommandInfo] = &[
+        CommandInfo {
+            name: PING,
+            description: "Echoes the given value back to check that the plugin is responding.",
+            input_schema: schema::<models::PingRequest>,
+            output_schema: schema::<models::PingResponse>,
+        },
+        CommandInfo {
+            name: TAKE_SCREENSHOT,
+            description: "Captures a screenshot of an application window, or of one element or CSS pixel region of it, and returns it as an image.",
+            input_schema: schema::<models::ScreenshotRequest>,
+            output_schema: schema::<models::ScreenshotResponse>,
+        },
+        CommandInfo {
+            name: GET_DOM,
+            description: "Returns the HTML of a window's document as a string.",
+            input_schema: schema::<webview::GetDomRequest>,
+            output_schema: schema::<String>,
+        },
+        CommandInfo {
+            name: GET_ACCESSIBILITY_TREE,
+            description: "Returns a compact accessibility tree (roles, names, states, bounds) with ref IDs usable as \"ref\" selectors.",
+            input_schema: schema::<accessibility::AccessibilityTreeRequest>,
+            output_schema: schema::<accessibility::AccessibilityTreeResponse>,
+        },
+        CommandInfo {
+            name: MANAGE_LOCAL_STORAGE,
+            description: "Reads or modifies localStorage of a window (get, set, remove, clear, keys).",
+            input_schema: schema::<models::LocalStorageRequest>,
+            output_schema: schema::<local_storage::LocalStorageResponse>,
+        },
+        CommandInfo {
+            name: EXECUTE_JS,
+            description: "Executes JavaScript in a window's webview and returns the (awaited) result.",
+            input_schema: schema::<execute_js::ExecuteJsRequest>,
+            output_schema: schema::<execute_js::ExecuteJsResponse>,
+        },
+        CommandInfo {
+            name: MANAGE_WINDOW,
+            description: "Focuses, minimizes, maximizes, closes, shows, hides, moves or resizes a window.",
+            input_schema: schema::<models::WindowManagerRequest>,
+            output_schema: schema::<models::WindowManagerResponse>,
+        },
+        CommandInfo {
+            name: SIMULATE_TEXT_INPUT,
+            description: "Types text into the focused element using native keyboard events.",
+            input_schema: schema::<models::TextInputRequest>,
+            output_schema: schema::<models::TextInputResponse>,
+        },
+        CommandInfo {
+            name: SIMULATE_MOUSE_MOVEMENT,
+            description: "Moves the mouse to absolute or relative coordinates and optionally clicks.",
+            input_schema: schema::<models::MouseMovementRequest>,
+            output_schema: schema::<models::MouseMovementResponse>,
+        },
+        CommandInfo {
+            name: GET_ELEMENT_POSITION,
+            description: "Finds an element by id, class, tag, text, CSS, XPath or accessibility ref and returns its position, optionally clicking it.",
+            input_schema: schema::<webview::GetElementPositionPayload>,
+            output_schema: schema::<webview::ElementPosition>,
+        },
+        CommandInfo {
+            name: SEND_TEXT_TO_ELEMENT,
+            description: "Finds an element by selector and types text into it.",
+            input_schema: schema::<webview::SendTextToElementPayload>,
+            output_schema: schema::<()>,
+        },
+        CommandInfo {
+            name: HOT_RELOAD,
+            description: "Reloads the webview of a window.",
+            input_schema: schema::<hot_reload::HotReloadPayload>,
+            output_schema: schema::<hot_reload::HotReloadResult>,
+        },
+        CommandInfo {
+            name: GET_CONSOLE_LOGS,
+            description: "Returns console messages captured from the webview since page start, merged with the app's Rust log records when a log tee is installed, filtered by level, source and time range.",
+            input_schema: schema::<console_logs::ConsoleLogsRequest>,
+            output_schema: schema::<console_logs::ConsoleLogsResponse>,
+        },
+        CommandInfo {
+            name: INJECT_CONSOLE_CAPTURE,
+            description: "Kept for compatibility: console output is captured automatically in every webview.",
+            input_schema: schema::<console_logs::InjectionRequest>,
+            output_schema: schema::<models::MessageResponse>,
+        },
+        CommandInfo {
+            name: NETWORK_INSPECTOR,
+            description: "Lists, clears or toggles capture of fetch/XHR requests and Tauri invoke calls made by the webview (only those over the IPC protocol; postMessage fallback calls and `invoke_command` are not recorded), reports the capture status, exports them as HAR 1.2 (returns `{ har | path, entry_count }`) or imports a HAR file as replay mocks (returns `{ imported_count, skipped_count, rules }`).",
+            input_schema: schema::<network_inspector::NetworkInspectorRequest>,
+            output_schema: schema::<network_inspector::NetworkInspectorResponse>,
+        },
+        CommandInfo {
+            name: INJECT_NETWORK_CAPTURE,
+            description: "Starts intercepting fetch and XMLHttpRequest calls in the webview.",
+            input_schema: schema::<network_inspector::InjectionRequest>,
+            output_schema: schema::<models::MessageResponse>,
+        },
+        CommandInfo {
+            name: NETWORK_MOCK,
+            description: "Adds, lists or removes rules that answer, delay or fail matching fetch/XHR requests in the webview.",
+            input_schema: schema::<network_mock::NetworkMockRequest>,
+            output_schema: schema::<network_mock::NetworkMockResponse>,
+        },
+        CommandInfo {
+            name: STATE_DUMP,
+            description: "Dumps application state from supported state management libraries.",
+            input_schema: schema::<state_dump::StateDumpRequest>,
+            output_schema: schema::<state_dump::StateDumpResponse>,
+        },
+        CommandInfo {
+            name: DEVTOOLS_BRIDGE,
+            description: "Queries React/Vue component hierarchy, props, state and hooks.",
+            input_schema: schema::<devtools_bridge::DevToolsBridgeRequest>,
+            output_schema: schema::<devtools_bridge::DevToolsBridgeResponse>,
+        },
+        CommandInfo {
+            name: GET_EXCEPTIONS,
+            description: "Returns unhandled exceptions and promise rejections tracked in the webview.",
+            input_schema: schema::<error_tracker::ErrorTrackerRequest>,
+            output_schema: schema::<error_tracker::ErrorTrackerResponse>,
+        },
+        CommandInfo {
+            name: INJECT_ERROR_TRACKER,
+            description: "Starts tracking unhandled exceptions and promise rejections in the webview.",
+            input_schema: schema::<error_tracker::InjectErrorTrackerRequest>,
+            output_schema: schema::<error_tracker::InjectErrorTrackerResponse>,
+        },
+        CommandInfo {
+            name: CLEAR_EXCEPTIONS,
+            description: "Clears the tracked exceptions.",
+            input_schema: schema::<error_tracker::ClearExceptionsRequest>,
+            output_schema: schema::<models::MessageResponse>,
+        },
+        CommandInfo {
+            name: GET_PERFORMANCE_METRICS,
+            description: "Returns navigation, resource, user timing, memory and long task metrics.",
+            input_schema: schema::<performance::PerformanceMetricsRequest>,
+            output_schema: schema::<performance::PerformanceMetricsResponse>,
+        },
+        CommandInfo {
+            name: STORAGE_INSPECTOR,
+            description: "Inspects localStorage, sessionStorage and IndexedDB with filtering and pagination.",
+            input_schema: schema::<storage_inspector::StorageInspectorRequest>,
+            output_schema: schema::<storage_inspector::StorageInspectorResponse>,
+        },
+        CommandInfo {
+            name: HEALTH_CHECK,
+            description: "Reports plugin version, system details, capabilities and connection status.",
+            input_schema: schema::<health_check::HealthCheckRequest>,
+            output_schema: schema::<health_check::HealthCheckResponse>,
+        },
+        CommandInfo {
+            name: DESCRIBE_COMMANDS,
+            description: "Lists every command with the JSON Schemas of its payload and response.",
+            input_schema: schema::<describe_commands::DescribeCommandsRequest>,
+            output_schema: schema::<describe_commands::DescribeCommandsResponse>,
+        },
+        CommandInfo {
+            name: LIST_WINDOWS,
+            description: "Lists windows with their label, title, URL, visibility, focus, bounds and webviews.",
+            input_schema: schema::<models::WindowListRequest>,
+            output_schema: schema::<models::WindowListResponse>,
+        },
+        CommandInfo {
+            name: INVOKE_COMMAND,
+            description: "Runs one of the app's Tauri commands through its invoke handler from a webview, subject to the app's capabilities, and returns the result or rejection.",
+            input_schema: schema::<invoke_command::InvokeCommandRequest>,
+            output_schema: schema::<invoke_command::InvokeCommandResponse>,
+        },
+        CommandInfo {
+            name: LIST_COMMANDS,
+            description: "Lists the app and plugin commands known to the plugin and whether the webview is allowed to call them.",
+            input_schema: schema::<invoke_command::ListCommandsRequest>,
+            output_schema: schema::<invoke_command::ListCommandsResponse>,
+        },
+        CommandInfo {
+            name: EMIT_EVENT,
+            description: "Emits an app event with a JSON payload, to every listener or to one window or webview label.",
+            input_schema: schema::<events::EmitEventRequest>,
+            output_schema: schema::<events::EmitEventResponse>,
+        },
+        CommandInfo {
+            name: LISTEN_EVENTS,
+            description: "Starts, stops, reads or clears a recording of app events whose names match the given patterns.",
+            input_schema: schema::<events::ListenEventsRequest>,
+            output_schema: schema::<events::ListenEventsResponse>,
+        },
+        CommandInfo {
+            name: WAIT_FOR,
+            description: "Waits until an element is visible or hidden, text appears, the URL changes, the network is idle or a JS expression is truthy, and returns how long it waited.",
+            input_schema: schema::<wait_for::WaitForRequest>,
+            output_schema: schema::<wait_for::WaitForResponse>,
+        },
+        CommandInfo {
+            name: PRESS_KEYS,
+            description: "Presses key chords such as Control+Shift+P, with hold/release steps, repeats and delays, as OS key presses or as DOM keyboard events.",
+            input_schema: schema::<press_keys::PressKeysRequest>,
+            output_schema: schema::<press_keys::PressKeysResponse>,
+        },
+        CommandInfo {
+            name: MOUSE_ACTION,
+            description: "Clicks, double or triple clicks, holds, drags, scrolls or hovers with the real mouse, at CSS pixel coordinates or on an element found by selector.",
+            input_schema: schema::<mouse_action::MouseActionRequest>,
+            output_schema: schema::<mouse_action::MouseActionResponse>,
+        },
+        CommandInfo {
+            name: VISUAL_COMPARE,
+            description: "Captures a window, element or region and compares it with a named baseline PNG, returning the mismatch percentage, changed regions and a diff image. Creates the baseline when missing or when update_baseline is set.",
+            input_schema: schema::<visual_compare::VisualCompareRequest>,
+            output_schema: schema::<visual_compare::VisualCompareResponse>,
+        },
+        CommandInfo {
+            name: TAKE_ANNOTATED_SCREENSHOT,
+            description: "Captures the webview with numbered boxes drawn around every visible interactive element and returns the image with a legend of each number's selector, role, text and rect. The numbers work as \"mark\" selectors in the element tools until the next annotated screenshot.",
+            input_schema: schema::<annotated_screenshot::AnnotatedScreenshotRequest>,
+            output_schema: schema::<annotated_screenshot::AnnotatedScreenshotResponse>,
+        },
+    ];
 }
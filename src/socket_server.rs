@@ -1,54 +1,31 @@
-use interprocess::TryClone;
-use interprocess::local_socket::{
-    GenericFilePath, GenericNamespaced, Listener as IpcListener, ListenerOptions, Name, Stream as IpcStream, ToFsName,
-    ToNsName, prelude::*,
-};
+use interprocess::local_socket::tokio::{Listener as IpcListener, prelude::*};
+use interprocess::local_socket::{GenericFilePath, GenericNamespaced, ListenerOptions, Name, ToFsName, ToNsName};
 use serde_json::Value;
-use std::io::{BufRead, BufReader, Read, Write};
-use std::net::{TcpListener, TcpStream};
-use std::sync::{Arc, Mutex};
-use std::thread;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Runtime};
-use log::{info, error};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::{oneshot, watch};
+use tokio::task::JoinSet;
+use log::{info, error, warn};
 
 use serde::{Deserialize, Serialize};
 
+use crate::auth::{AuthToken, AuthenticateRequest};
 use crate::error::Error;
+use crate::mcp;
+use crate::notifications::{SubscribeRequest, Subscriptions, UnsubscribeRequest};
+use crate::shared::commands;
 use crate::tools;
 use crate::SocketType;
 
-/// A wrapper stream that logs all reads and writes for debugging
-struct LoggingStream<S: Write + Read> {
-    inner: S,
-}
-
-impl<S: Write + Read> LoggingStream<S> {
-    fn new(inner: S) -> Self {
-        Self { inner }
-    }
-}
-
-impl<S: Write + Read> Write for LoggingStream<S> {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        info!("[TAURI_MCP] Writing: {}", String::from_utf8_lossy(buf));
-        self.inner.write(buf)
-    }
-
-    fn flush(&mut self) -> std::io::Result<()> {
-        self.inner.flush()
-    }
-}
+/// How long in-flight commands may run after `stop()` before their connections are aborted
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
 
-impl<S: Write + Read> Read for LoggingStream<S> {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let n = self.inner.read(buf)?;
-        info!(
-            "[TAURI_MCP] Read: {}",
-            String::from_utf8_lossy(&buf[..n])
-        );
-        Ok(n)
-    }
-}
+/// Longest first line accepted from a client that still has to authenticate
+const MAX_HANDSHAKE_LEN: usize = 4096;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -65,75 +42,77 @@ pub struct SocketResponse {
     pub error: Option<String>,
 }
 
-/// Unified stream type that can handle both IPC and TCP
-enum UnifiedStream {
-    Ipc(IpcStream),
-    Tcp(TcpStream),
+type ClientReader = Box<dyn AsyncRead + Send + Unpin>;
+type ClientWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Unified listener type that can handle both IPC and TCP
+enum UnifiedListener {
+    Ipc(IpcListener),
+    Tcp(TcpListener),
 }
 
-impl Read for UnifiedStream {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+impl UnifiedListener {
+    // Wait for the next client and split its stream into read and write halves
+    async fn accept(&self) -> std::io::Result<(ClientReader, ClientWriter)> {
         match self {
-            UnifiedStream::Ipc(stream) => stream.read(buf),
-            UnifiedStream::Tcp(stream) => stream.read(buf),
+            UnifiedListener::Ipc(listener) => {
+                let stream = listener.accept().await?;
+                info!("[TAURI_MCP] Accepted new IPC connection");
+                let (reader, writer) = stream.split();
+                Ok((Box::new(reader), Box::new(writer)))
+            }
+            UnifiedListener::Tcp(listener) => {
+                let (stream, addr) = listener.accept().await?;
+                info!("[TAURI_MCP] Accepted new TCP connection from: {}", addr);
+                let (reader, writer) = stream.into_split();
+                Ok((Box::new(reader), Box::new(writer)))
+            }
         }
     }
 }
 
-impl Write for UnifiedStream {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        match self {
-            UnifiedStream::Ipc(stream) => stream.write(buf),
-            UnifiedStream::Tcp(stream) => stream.write(buf),
-        }
-    }
+// Handles to a started server, dropped once it has stopped
+struct RunningServer {
+    // Flipped to true to ask the accept loop and every connection to wind down
+    shutdown: watch::Sender<bool>,
+    // Signalled by the accept loop after connections are drained and files are cleaned up
+    finished: oneshot::Receiver<()>,
+}
 
-    fn flush(&mut self) -> std::io::Result<()> {
-        match self {
-            UnifiedStream::Ipc(stream) => stream.flush(),
-            UnifiedStream::Tcp(stream) => stream.flush(),
-        }
-    }
+/// A server that has been asked to stop and is draining its connections
+pub struct StoppingServer {
+    finished: oneshot::Receiver<()>,
 }
 
-impl UnifiedStream {
-    fn try_clone(&self) -> std::io::Result<Self> {
-        match self {
-            UnifiedStream::Ipc(stream) => Ok(UnifiedStream::Ipc(stream.try_clone()?)),
-            UnifiedStream::Tcp(stream) => Ok(UnifiedStream::Tcp(stream.try_clone()?)),
+impl StoppingServer {
+    /// Wait until in-flight commands have finished and the socket and token files are removed
+    pub async fn wait(self) -> crate::Result<()> {
+        // Allow a little longer than the drain itself for aborting and cleanup
+        let wait = DRAIN_TIMEOUT + Duration::from_secs(1);
+        match tokio::time::timeout(wait, self.finished).await {
+            Ok(_) => {
+                info!("[TAURI_MCP] Socket server stopped");
+                Ok(())
+            }
+            Err(_) => Err(Error::timeout_error("socket server shutdown", wait.as_millis() as u64)),
         }
     }
 }
 
-/// Unified listener type that can handle both IPC and TCP
-enum UnifiedListener {
-    Ipc(IpcListener),
-    Tcp(TcpListener),
-}
-
 pub struct SocketServer<R: Runtime> {
-    listener: Option<Arc<Mutex<UnifiedListener>>>,
     socket_type: SocketType,
     app: AppHandle<R>,
-    running: Arc<Mutex<bool>>,
+    auth: Option<Arc<AuthToken>>,
+    running: Option<RunningServer>,
 }
 
 impl<R: Runtime> SocketServer<R> {
-    pub fn new(app: AppHandle<R>, socket_type: SocketType) -> Self {
+    pub fn new(app: AppHandle<R>, socket_type: SocketType, auth: Option<Arc<AuthToken>>) -> Self {
         match &socket_type {
             SocketType::Ipc { path } => {
-                let socket_path = if let Some(path) = path {
-                    path.to_string_lossy().to_string()
-                } else {
-                    let temp_dir = std::env::temp_dir();
-                    temp_dir
-                        .join("tauri-mcp.sock")
-                        .to_string_lossy()
-                        .to_string()
-                };
                 info!(
                     "[TAURI_MCP] Initializing IPC socket server at: {}",
-                    socket_path
+                    socket_path(path).display()
                 );
             }
             SocketType::Tcp { host, port } => {
@@ -145,43 +124,55 @@ impl<R: Runtime> SocketServer<R> {
         }
 
         SocketServer {
-            listener: None,
             socket_type,
             app,
-            running: Arc::new(Mutex::new(false)),
+            auth,
+            running: None,
         }
     }
 
+    /// Whether the server is accepting connections
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Bind the listener and start serving connections on Tauri's async runtime
     pub fn start(&mut self) -> crate::Result<()> {
+        if self.running.is_some() {
+            info!("[TAURI_MCP] Socket server is already running");
+            return Ok(());
+        }
+
         info!("[TAURI_MCP] Starting socket server...");
 
+        // Tokio listeners register with the reactor of the runtime they are created in
+        let runtime = tauri::async_runtime::handle();
+        let _guard = runtime.inner().enter();
+
         let listener = match &self.socket_type {
             SocketType::Ipc { path } => {
-                // Get the socket path for cleanup (prefixed with _ for Windows where it's unused)
-                let _socket_path = if let Some(p) = path {
-                    p.clone()
-                } else {
-                    std::env::temp_dir().join("tauri-mcp.sock")
-                };
-
                 // Clean up any stale socket file on Unix platforms
                 #[cfg(not(target_os = "windows"))]
-                if _socket_path.exists() {
-                    info!("[TAURI_MCP] Removing stale socket file: {:?}", _socket_path);
-                    std::fs::remove_file(&_socket_path)
-                        .map_err(|e| Error::Io { message: format!("Failed to remove stale socket: {}", e) })?;
+                {
+                    let socket_path = socket_path(path);
+                    if socket_path.exists() {
+                        info!("[TAURI_MCP] Removing stale socket file: {:?}", socket_path);
+                        std::fs::remove_file(&socket_path)
+                            .map_err(|e| Error::Io { message: format!("Failed to remove stale socket: {}", e) })?;
+                    }
                 }
 
                 // Create a name for our socket based on the platform
-                let socket_name = self.get_socket_name(path)?;
+                let socket_name = socket_name(path)?;
 
                 // Configure and create the IPC listener
-                let opts = ListenerOptions::new().name(socket_name);
-                let ipc_listener = opts.create_sync()
+                let ipc_listener = ListenerOptions::new()
+                    .name(socket_name)
+                    .create_tokio()
                     .map_err(|e| {
                         info!("[TAURI_MCP] Error creating IPC socket listener: {}", e);
                         if e.kind() == std::io::ErrorKind::AddrInUse {
-                            Error::Io { message: format!("Socket address already in use. If the socket file exists, it may be a stale socket. Try removing it manually.") }
+                            Error::Io { message: "Socket address already in use. If the socket file exists, it may be a stale socket. Try removing it manually.".to_string() }
                         } else {
                             Error::Io { message: format!("Failed to create local socket: {}", e) }
                         }
@@ -189,9 +180,13 @@ impl<R: Runtime> SocketServer<R> {
                 UnifiedListener::Ipc(ipc_listener)
             }
             SocketType::Tcp { host, port } => {
-                // Create TCP listener
+                // Bind synchronously so the caller sees address errors, then hand over to tokio
                 let addr = format!("{}:{}", host, port);
-                let tcp_listener = TcpListener::bind(&addr)
+                let tcp_listener = std::net::TcpListener::bind(&addr)
+                    .and_then(|listener| {
+                        listener.set_nonblocking(true)?;
+                        TcpListener::from_std(listener)
+                    })
                     .map_err(|e| {
                         info!("[TAURI_MCP] Error creating TCP socket listener: {}", e);
                         Error::Io { message: format!("Failed to bind to {}: {}", addr, e) }
@@ -200,179 +195,48 @@ impl<R: Runtime> SocketServer<R> {
             }
         };
 
-        let listener = Arc::new(Mutex::new(listener));
-        self.listener = Some(listener.clone());
+        // Publish the token before accepting connections so clients can read it
+        if let Some(auth) = &self.auth {
+            auth.write_file()?;
+        }
 
-        *self.running.lock().unwrap() = true;
-        info!("[TAURI_MCP] Set running flag to true");
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let (finished_tx, finished) = oneshot::channel();
 
         let app = self.app.clone();
-        let running = self.running.clone();
+        let auth = self.auth.clone();
         let socket_type = self.socket_type.clone();
 
-        // Spawn a thread to handle socket connections
-        info!("[TAURI_MCP] Spawning listener thread");
-        thread::spawn(move || {
-            match &socket_type {
-                SocketType::Ipc { .. } => {
-                    info!("[TAURI_MCP] Listener thread started for IPC socket");
-                }
-                SocketType::Tcp { host, port } => {
-                    info!("[TAURI_MCP] Listener thread started for TCP socket at {}:{}", host, port);
+        info!("[TAURI_MCP] Spawning listener task");
+        tauri::async_runtime::spawn(async move {
+            serve(listener, app, auth.clone(), shutdown_rx).await;
+
+            // The listener is closed by now; remove what the server left on disk
+            if let SocketType::Ipc { path } = &socket_type
+                && cfg!(not(target_os = "windows"))
+            {
+                match std::fs::remove_file(socket_path(path)) {
+                    Ok(()) => info!("[TAURI_MCP] Removed socket file"),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => error!("[TAURI_MCP] Failed to remove socket file: {}", e),
                 }
             }
 
-            // Set panic handler to suppress specific Windows named pipe errors
-            let original_hook = std::panic::take_hook();
-            std::panic::set_hook(Box::new(move |panic_info| {
-                let panic_payload = panic_info.payload();
-                let is_pipe_error = if let Some(s) = panic_payload.downcast_ref::<String>() {
-                    s.contains("No process is on the other end of the pipe")
-                } else if let Some(s) = panic_payload.downcast_ref::<&str>() {
-                    s.contains("No process is on the other end of the pipe")
-                } else {
-                    false
-                };
-
-                // If it's not the Windows pipe disconnection error, pass to the original handler
-                if !is_pipe_error {
-                    original_hook(panic_info);
-                } else {
-                    // Just log the error instead of panicking
-                    info!(
-                        "[TAURI_MCP] Handled pipe disconnection (normal client disconnect)"
-                    );
-                }
-            }));
-
-            let listener_guard = listener.lock().unwrap();
-
-            loop {
-                if !*running.lock().unwrap() {
-                    break;
-                }
-
-                match &*listener_guard {
-                    UnifiedListener::Ipc(ipc_listener) => {
-                        // Handle IPC connections
-                        for conn in ipc_listener.incoming() {
-                            if !*running.lock().unwrap() {
-                                break;
-                            }
-
-                            match conn {
-                                Ok(stream) => {
-                                    info!("[TAURI_MCP] Accepted new IPC connection");
-                                    let app_clone = app.clone();
-                                    let unified_stream = UnifiedStream::Ipc(stream);
-
-                                    // Spawn a new thread with its own panic handler for client handling
-                                    thread::spawn(move || {
-                                        // Set a similar panic handler for the client handler thread
-                                        let client_hook = std::panic::take_hook();
-                                        std::panic::set_hook(Box::new(move |panic_info| {
-                                            let panic_payload = panic_info.payload();
-                                            let is_pipe_error = if let Some(s) =
-                                                panic_payload.downcast_ref::<String>()
-                                            {
-                                                s.contains("No process is on the other end of the pipe")
-                                            } else if let Some(s) = panic_payload.downcast_ref::<&str>() {
-                                                s.contains("No process is on the other end of the pipe")
-                                            } else {
-                                                false
-                                            };
-
-                                            if !is_pipe_error {
-                                                client_hook(panic_info);
-                                            } else {
-                                                info!(
-                                                    "[TAURI_MCP] Handled pipe disconnection in client thread"
-                                                );
-                                            }
-                                        }));
-
-                                        // Handle the client with error trapping
-                                        if let Err(e) = handle_client(unified_stream, app_clone) {
-                                            if e.to_string()
-                                                .contains("No process is on the other end of the pipe")
-                                            {
-                                                info!("[TAURI_MCP] Client disconnected normally");
-                                            } else {
-                                                error!("[TAURI_MCP] Error handling client: {}", e);
-                                            }
-                                        }
-                                    });
-                                }
-                                Err(e) => {
-                                    error!("[TAURI_MCP] Error accepting IPC connection: {}", e);
-                                    // Short sleep to avoid busy waiting in case of persistent errors
-                                    std::thread::sleep(std::time::Duration::from_millis(100));
-                                }
-                            }
-
-                            // Check the running flag after each connection
-                            if !*running.lock().unwrap() {
-                                break;
-                            }
-                        }
-                    }
-                    UnifiedListener::Tcp(tcp_listener) => {
-                        // Handle TCP connections
-                        // Set non-blocking mode to allow checking the running flag
-                        tcp_listener.set_nonblocking(true).ok();
-                        
-                        loop {
-                            if !*running.lock().unwrap() {
-                                break;
-                            }
-
-                            match tcp_listener.accept() {
-                                Ok((stream, addr)) => {
-                                    info!("[TAURI_MCP] Accepted new TCP connection from: {}", addr);
-                                    
-                                    // Set the stream back to blocking mode for normal I/O operations
-                                    if let Err(e) = stream.set_nonblocking(false) {
-                                        error!("[TAURI_MCP] Failed to set stream to blocking mode: {}", e);
-                                        continue;
-                                    }
-                                    
-                                    let app_clone = app.clone();
-                                    let unified_stream = UnifiedStream::Tcp(stream);
-
-                                    // Spawn a new thread for client handling
-                                    thread::spawn(move || {
-                                        // Handle the client with error trapping
-                                        if let Err(e) = handle_client(unified_stream, app_clone) {
-                                            error!("[TAURI_MCP] Error handling TCP client: {}", e);
-                                        }
-                                    });
-                                }
-                                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                                    // No connection available, sleep briefly
-                                    std::thread::sleep(std::time::Duration::from_millis(100));
-                                }
-                                Err(e) => {
-                                    error!("[TAURI_MCP] Error accepting TCP connection: {}", e);
-                                    std::thread::sleep(std::time::Duration::from_millis(100));
-                                }
-                            }
-                        }
-                    }
-                }
+            if let Some(auth) = auth {
+                auth.remove_file();
             }
-            info!("[TAURI_MCP] Listener thread ending");
+
+            info!("[TAURI_MCP] Listener task ending");
+            let _ = finished_tx.send(());
         });
 
+        self.running = Some(RunningServer { shutdown, finished });
+
         match &self.socket_type {
             SocketType::Ipc { path } => {
-                let display_path = if let Some(p) = path {
-                    p.to_string_lossy().to_string()
-                } else {
-                    std::env::temp_dir().join("tauri-mcp.sock").to_string_lossy().to_string()
-                };
                 info!(
                     "[TAURI_MCP] Socket server started successfully at {}",
-                    display_path
+                    socket_path(path).display()
                 );
             }
             SocketType::Tcp { host, port } => {
@@ -385,92 +249,236 @@ impl<R: Runtime> SocketServer<R> {
         Ok(())
     }
 
-    pub fn stop(&self) -> crate::Result<()> {
+    /// Stop accepting connections and let in-flight commands finish without waiting for them.
+    /// Wait on the returned handle before calling `start()` on the same address again.
+    /// Returns `None` if the server was not running.
+    pub fn request_stop(&mut self) -> Option<StoppingServer> {
+        let running = self.running.take()?;
+
         info!("[TAURI_MCP] Stopping socket server");
-        // Set running flag to false to stop the server thread
-        *self.running.lock().unwrap() = false;
+        let _ = running.shutdown.send(true);
+        Some(StoppingServer { finished: running.finished })
+    }
+}
 
-        // The interprocess crate automatically cleans up the socket file on drop for Unix platforms
-        info!("[TAURI_MCP] Socket server stopped");
-        Ok(())
+impl<R: Runtime> Drop for SocketServer<R> {
+    fn drop(&mut self) {
+        // Only signal; the server task drains and cleans up on the runtime by itself
+        self.request_stop();
     }
+}
 
-    #[cfg(desktop)]
-    fn get_socket_name(&self, path: &Option<std::path::PathBuf>) -> Result<Name<'_>, Error> {
-        let socket_path = if let Some(p) = path {
-            p.to_string_lossy().to_string()
-        } else {
-            let temp_dir = std::env::temp_dir();
-            temp_dir.join("tauri-mcp.sock").to_string_lossy().to_string()
-        };
+// Socket path, falling back to the default when none is configured
+fn socket_path(path: &Option<PathBuf>) -> PathBuf {
+    path.clone()
+        .unwrap_or_else(|| std::env::temp_dir().join("tauri-mcp.sock"))
+}
+
+fn socket_name(path: &Option<PathBuf>) -> Result<Name<'static>, Error> {
+    let socket_path = socket_path(path).to_string_lossy().to_string();
+
+    if cfg!(target_os = "windows") {
+        // Use named pipe on Windows
+        socket_path
+            .to_ns_name::<GenericNamespaced>()
+            .map_err(|e| Error::Io { message: format!("Failed to create pipe name: {}", e) })
+    } else {
+        // Use file-based socket on Unix platforms
+        socket_path
+            .to_fs_name::<GenericFilePath>()
+            .map_err(|e| Error::Io { message: format!("Failed to create file socket name: {}", e) })
+    }
+}
+
+// Resolves once shutdown has been requested, or the server handle is gone
+async fn stopped(shutdown: &mut watch::Receiver<bool>) {
+    let _ = shutdown.wait_for(|stopping| *stopping).await;
+}
 
-        if cfg!(target_os = "windows") {
-            // Use named pipe on Windows
-            socket_path
-                .to_ns_name::<GenericNamespaced>()
-                .map_err(|e| Error::Io { message: format!("Failed to create pipe name: {}", e) })
-        } else {
-            // Use file-based socket on Unix platforms
-            socket_path
-                .clone()
-                .to_fs_name::<GenericFilePath>()
-                .map_err(|e| Error::Io { message: format!("Failed to create file socket name: {}", e) })
+// Accept connections until shutdown is requested, then drain the open ones
+async fn serve<R: Runtime>(
+    listener: UnifiedListener,
+    app: AppHandle<R>,
+    auth: Option<Arc<AuthToken>>,
+    shutdown: watch::Receiver<bool>,
+) {
+    let mut clients = JoinSet::new();
+    // Separate receiver for the accept loop; `shutdown` is handed to each connection
+    let mut stopping = shutdown.clone();
+
+    loop {
+        tokio::select! {
+            _ = stopped(&mut stopping) => break,
+            accepted = listener.accept() => match accepted {
+                Ok((reader, writer)) => {
+                    let app = app.clone();
+                    let auth = auth.clone();
+                    let shutdown = shutdown.clone();
+                    clients.spawn(async move {
+                        if let Err(e) = handle_client(app, auth, reader, writer, shutdown).await {
+                            error!("[TAURI_MCP] Error handling client: {}", e);
+                        }
+                    });
+                }
+                Err(e) => {
+                    error!("[TAURI_MCP] Error accepting connection: {}", e);
+                    // Short pause to avoid spinning on persistent errors
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            },
+            // Reap finished connections as we go
+            Some(_) = clients.join_next(), if !clients.is_empty() => {}
         }
     }
+
+    // Close the listener first so no new clients get in while draining
+    drop(listener);
+
+    if !clients.is_empty() {
+        info!("[TAURI_MCP] Waiting for {} client connection(s) to finish", clients.len());
+    }
+    let drained = tokio::time::timeout(DRAIN_TIMEOUT, async {
+        while clients.join_next().await.is_some() {}
+    })
+    .await;
+    if drained.is_err() {
+        warn!("[TAURI_MCP] Aborting {} client connection(s) still busy after shutdown", clients.len());
+        clients.shutdown().await;
+    }
 }
 
-fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate::Result<()> {
+async fn handle_client<R: Runtime>(
+    app: AppHandle<R>,
+    auth: Option<Arc<AuthToken>>,
+    reader: ClientReader,
+    mut writer: ClientWriter,
+    mut shutdown: watch::Receiver<bool>,
+) -> crate::Result<()> {
     info!("[TAURI_MCP] Handling new client connection");
-    // Create a new runtime for this thread since handle_client runs in a separate thread
-    // spawned by the socket listener, not in Tauri's async context
-    let rt = tokio::runtime::Runtime::new()
-        .map_err(|e| Error::Io { message: format!("Failed to create runtime: {}", e) })?;
-
-    rt.block_on(async {
-        // Create a buffered reader and separate writer for the socket
-        let stream_clone = match stream.try_clone() {
-            Ok(clone) => clone,
-            Err(e) => {
-                // This might be a disconnection error on Windows
-                if e.to_string()
-                    .contains("No process is on the other end of the pipe")
-                {
-                    info!("[TAURI_MCP] Client already disconnected (pipe error)");
+    let mut reader = BufReader::new(reader);
+
+    // Connections without a token to check are trusted from the start
+    let mut authenticated = auth.is_none();
+
+    // Set once the client has sent a JSON-RPC message, so later parse errors are answered in kind
+    let mut mcp_mode = false;
+
+    // Streams this connection asked to have pushed
+    let mut subscriptions = Subscriptions::default();
+
+    // Keep handling requests until the client disconnects or the server stops
+    let mut buffer = Vec::new();
+    loop {
+        // Shutdown only interrupts the wait for the next request; a command that is
+        // already running below completes and gets its response first.
+        // `read_until` keeps partial input in the buffer when a notification wins the race.
+        let read = tokio::select! {
+            read = async {
+                if authenticated {
+                    reader.read_until(b'\n', &mut buffer).await
+                } else {
+                    // Unauthenticated clients may not make us buffer an unbounded line
+                    let limit = MAX_HANDSHAKE_LEN.saturating_sub(buffer.len()) as u64;
+                    (&mut reader).take(limit).read_until(b'\n', &mut buffer).await
+                }
+            } => read,
+            pushed = subscriptions.next() => {
+                if !write_response(&mut writer, &pushed.to_line()?).await? {
                     return Ok(());
                 }
-                return Err(Error::Io { message: format!("Failed to clone stream: {}", e) });
+                continue;
+            }
+            _ = stopped(&mut shutdown) => {
+                info!("[TAURI_MCP] Closing client connection for server shutdown");
+                return Ok(());
             }
         };
 
-        // Wrap the streams with our logging wrapper
-        let logging_reader = LoggingStream::new(stream_clone);
-        let mut reader = BufReader::new(logging_reader);
-        let mut writer = LoggingStream::new(stream);
-
-        // Keep handling requests until the client disconnects
-        loop {
-            let mut line = String::new();
-            match reader.read_line(&mut line) {
-                Ok(0) => {
-                    // End of stream, client disconnected
-                    info!("[TAURI_MCP] Client disconnected cleanly");
+        match read {
+            Ok(0) if buffer.is_empty() => {
+                // End of stream, client disconnected
+                info!("[TAURI_MCP] Client disconnected cleanly");
+                return Ok(());
+            }
+            Ok(_) => {}
+            Err(e) if is_disconnect(&e) => {
+                info!("[TAURI_MCP] Client disconnected during read (pipe error)");
+                return Ok(());
+            }
+            Err(e) => {
+                return Err(Error::Io { message: format!("Error reading from socket: {}", e) });
+            }
+        }
+
+        // Nothing but the handshake is accepted until the client has authenticated.
+        // The handshake line carries the token, so it is never logged.
+        if let Some(auth) = auth.as_deref().filter(|_| !authenticated) {
+            if buffer.len() >= MAX_HANDSHAKE_LEN && !buffer.ends_with(b"\n") {
+                warn!("[TAURI_MCP] Rejected connection: auth handshake longer than {} bytes", MAX_HANDSHAKE_LEN);
+                write_response(&mut writer, &auth_response(false)?).await?;
+                return Ok(());
+            }
+
+            let line = String::from_utf8_lossy(&buffer).into_owned();
+            buffer.clear();
+            info!("[TAURI_MCP] Received command: <auth handshake>");
+            if !is_valid_handshake(auth, &line) {
+                warn!("[TAURI_MCP] Rejected connection: missing or invalid auth token");
+                write_response(&mut writer, &auth_response(false)?).await?;
+                return Ok(());
+            }
+
+            info!("[TAURI_MCP] Client authenticated");
+            authenticated = true;
+            if !write_response(&mut writer, &auth_response(true)?).await? {
+                return Ok(());
+            }
+            continue;
+        }
+
+        let line = String::from_utf8_lossy(&buffer).into_owned();
+        buffer.clear();
+        info!("[TAURI_MCP] Received command: {}", line.trim());
+
+        // MCP clients speak JSON-RPC 2.0 over the same connection
+        let message = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => Some(message),
+            // MCP clients cannot read plugin-style errors, so they get a JSON-RPC parse error
+            Err(e) if mcp_mode || mcp::looks_like_jsonrpc(&line) => {
+                info!("[TAURI_MCP] Invalid JSON from MCP client: {}", e);
+                let reply_json = serde_json::to_string(&mcp::parse_error_response(&e))
+                    .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?
+                    + "\n";
+                if !write_response(&mut writer, &reply_json).await? {
                     return Ok(());
                 }
-                Ok(_) => {
-                    info!("[TAURI_MCP] Received command: {}", line.trim());
-                }
-                Err(e) => {
-                    // Check if this is a pipe disconnection error
-                    if e.to_string()
-                        .contains("No process is on the other end of the pipe")
-                        || e.kind() == std::io::ErrorKind::BrokenPipe
-                    {
-                        info!("[TAURI_MCP] Client disconnected during read (pipe error)");
-                        return Ok(());
-                    }
-                    return Err(Error::Io { message: format!("Error reading from socket: {}", e) });
-                }
+                continue;
+            }
+            Err(_) => None,
+        };
+        if let Some(message) = message
+            && mcp::is_jsonrpc_message(&message)
+        {
+            mcp_mode = true;
+            let Some(reply) = forwarding_notifications(
+                mcp::handle_message(&app, message),
+                &mut subscriptions,
+                &mut writer,
+            )
+            .await?
+            else {
+                return Ok(());
             };
+            if let Some(reply) = reply {
+                let reply_json = serde_json::to_string(&reply)
+                    .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?
+                    + "\n";
+                if !write_response(&mut writer, &reply_json).await? {
+                    return Ok(());
+                }
+            }
+            continue;
+        }
 
         // Parse and process the request
         let request: SocketRequest = match serde_json::from_str(&line) {
@@ -486,36 +494,48 @@ fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate:
                     error: Some(error_msg),
                 };
 
-                let error_json = match serde_json::to_string(&error_response) {
-                    Ok(json) => json + "\n",
-                    Err(e) => {
-                        return Err(Error::serialization_error(
-                            format!("Failed to serialize error response: {}", e),
-                        ));
-                    }
-                };
-
-                match writer.write_all(error_json.as_bytes()) {
-                    Ok(_) => {
-                        if let Err(e) = writer.flush() {
-                            return Err(Error::Io { message: format!("Error flushing error response: {}", e) });
-                        }
-                    }
-                    Err(e) => {
-                        return Err(Error::Io { message: format!("Error writing error response: {}", e) });
-                    }
+                let error_json = serde_json::to_string(&error_response)
+                    .map_err(|e| Error::serialization_error(format!("Failed to serialize error response: {}", e)))?
+                    + "\n";
+                if !write_response(&mut writer, &error_json).await? {
+                    return Ok(());
                 }
-
-                // Clear the line and continue to the next iteration
-                line.clear();
                 continue;
             }
         };
 
         info!("[TAURI_MCP] Processing command: {}", request.command);
 
+        // Clients configured with a token may still handshake with a server that needs none
+        if request.command == commands::AUTHENTICATE {
+            if !write_response(&mut writer, &auth_response(true)?).await? {
+                return Ok(());
+            }
+            continue;
+        }
+
+        if request.command == commands::SUBSCRIBE || request.command == commands::UNSUBSCRIBE {
+            let response = subscription_command(&app, &mut subscriptions, request);
+            let response_json = serde_json::to_string(&response)
+                .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?
+                + "\n";
+            if !write_response(&mut writer, &response_json).await? {
+                return Ok(());
+            }
+            continue;
+        }
+
         // Use the centralized command handler from tools module
-        let response = match tools::handle_command(&app, &request.command, request.payload).await {
+        let Some(result) = forwarding_notifications(
+            tools::handle_command(&app, &request.command, request.payload),
+            &mut subscriptions,
+            &mut writer,
+        )
+        .await?
+        else {
+            return Ok(());
+        };
+        let response = match result {
             Ok(resp) => resp,
             Err(e) => {
                 // Convert the error into a response structure
@@ -528,7 +548,6 @@ fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate:
             }
         };
 
-        // When writing the response, handle pipe errors gracefully
         let response_json = serde_json::to_string(&response)
             .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?
             + "\n";
@@ -537,44 +556,302 @@ fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate:
             response_json.len()
         );
 
-        // Write the response directly without chunking
-        match writer.write_all(response_json.as_bytes()) {
-            Ok(_) => {
-                match writer.flush() {
-                    Ok(_) => {
-                        info!("[TAURI_MCP] Response sent successfully");
-                        // Continue to the next iteration of the loop
-                    }
-                    Err(e) => {
-                        if e.to_string()
-                            .contains("No process is on the other end of the pipe")
-                            || e.kind() == std::io::ErrorKind::BrokenPipe
-                        {
-                            info!(
-                                "[TAURI_MCP] Client disconnected during flush (pipe error)"
-                            );
-                            return Ok(()); // Return success for expected client disconnect
-                        } else {
-                            return Err(Error::Io { message: format!("Error flushing response: {}", e) });
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                if e.to_string()
-                    .contains("No process is on the other end of the pipe")
-                    || e.kind() == std::io::ErrorKind::BrokenPipe
-                {
-                    info!("[TAURI_MCP] Client disconnected during write (pipe error)");
-                    return Ok(()); // Return success for expected client disconnect
-                } else {
-                    return Err(Error::Io { message: format!("Error writing response: {}", e) });
+        if !write_response(&mut writer, &response_json).await? {
+            return Ok(()); // Return success for expected client disconnect
+        }
+    }
+}
+
+// Await a command while still pushing notifications, so a slow command does not hold
+// them back. Returns None if the client went away while a notification was written.
+async fn forwarding_notifications<T>(
+    command: impl Future<Output = T>,
+    subscriptions: &mut Subscriptions,
+    writer: &mut ClientWriter,
+) -> crate::Result<Option<T>> {
+    tokio::pin!(command);
+    loop {
+        tokio::select! {
+            biased;
+            output = &mut command => return Ok(Some(output)),
+            pushed = subscriptions.next() => {
+                if !write_response(writer, &pushed.to_line()?).await? {
+                    return Ok(None);
                 }
             }
         }
-        
-        // Clear the line for the next command
-        line.clear();
-        } // End of loop
-    })
+    }
+}
+
+// Handle `subscribe` and `unsubscribe`, which change this connection's subscriptions
+fn subscription_command<R: Runtime>(
+    app: &AppHandle<R>,
+    subscriptions: &mut Subscriptions,
+    request: SocketRequest,
+) -> SocketResponse {
+    let result = if request.command == commands::SUBSCRIBE {
+        serde_json::from_value::<SubscribeRequest>(request.payload)
+            .map(|payload| subscriptions.subscribe(app, payload))
+    } else {
+        serde_json::from_value::<Option<UnsubscribeRequest>>(request.payload)
+            .map(|payload| subscriptions.unsubscribe(payload.unwrap_or_default()))
+    };
+
+    match result.map(serde_json::to_value) {
+        Ok(Ok(data)) => SocketResponse {
+            success: true,
+            data: Some(data),
+            error: None,
+        },
+        Ok(Err(e)) | Err(e) => SocketResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Invalid payload for {}: {}", request.command, e)),
+        },
+    }
+}
+
+// Check that a line is an `authenticate` request carrying the expected token
+fn is_valid_handshake(auth: &AuthToken, line: &str) -> bool {
+    serde_json::from_str::<SocketRequest>(line)
+        .ok()
+        .filter(|request| request.command == commands::AUTHENTICATE)
+        .and_then(|request| serde_json::from_value::<AuthenticateRequest>(request.payload).ok())
+        .is_some_and(|request| auth.verify(&request.token))
+}
+
+// Serialized reply to a handshake
+fn auth_response(authenticated: bool) -> crate::Result<String> {
+    let response = if authenticated {
+        SocketResponse {
+            success: true,
+            data: Some(serde_json::json!({ "authenticated": true })),
+            error: None,
+        }
+    } else {
+        SocketResponse {
+            success: false,
+            data: None,
+            error: Some("Authentication failed".to_string()),
+        }
+    };
+
+    serde_json::to_string(&response)
+        .map(|json| json + "\n")
+        .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))
+}
+
+// Errors that just mean the client has gone away
+fn is_disconnect(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::ConnectionReset
+    ) || e
+        .to_string()
+        .contains("No process is on the other end of the pipe")
+}
+
+// Write one response line. Returns Ok(false) if the client has gone away.
+async fn write_response<W: AsyncWrite + Unpin>(writer: &mut W, response_json: &str) -> crate::Result<bool> {
+    if let Err(e) = writer.write_all(response_json.as_bytes()).await {
+        if is_disconnect(&e) {
+            info!("[TAURI_MCP] Client disconnected during write (pipe error)");
+            return Ok(false);
+        }
+        return Err(Error::Io { message: format!("Error writing response: {}", e) });
+    }
+
+    if let Err(e) = writer.flush().await {
+        if is_disconnect(&e) {
+            info!("[TAURI_MCP] Client disconnected during flush (pipe error)");
+            return Ok(false);
+        }
+        return Err(Error::Io { message: format!("Error flushing response: {}", e) });
+    }
+
+    info!("[TAURI_MCP] Response sent successfully");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SocketAuth;
+    use tokio::io::DuplexStream;
+
+    const TOKEN: &str = "s3cret-token";
+
+    fn auth() -> Arc<AuthToken> {
+        Arc::new(
+            AuthToken::from_config(&SocketAuth::Token(TOKEN.to_string()), None)
+                .unwrap()
+                .unwrap(),
+        )
+    }
+
+    fn handshake(token: &str) -> String {
+        serde_json::json!({ "command": commands::AUTHENTICATE, "payload": { "token": token } }).to_string()
+    }
+
+    // Run handle_client on one end of an in-memory stream and return the other end
+    fn connect(auth: Arc<AuthToken>) -> (BufReader<DuplexStream>, tokio::task::JoinHandle<crate::Result<()>>) {
+        let app = tauri::test::mock_app();
+        let handle = app.handle().clone();
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let (reader, writer) = tokio::io::split(server);
+        let (shutdown, stopped) = watch::channel(false);
+        let task = tokio::spawn(async move {
+            // Dropping the sender would look like a shutdown
+            let _shutdown = shutdown;
+            handle_client(handle, Some(auth), Box::new(reader), Box::new(writer), stopped).await
+        });
+        (BufReader::new(client), task)
+    }
+
+    async fn read_response(client: &mut BufReader<DuplexStream>) -> Value {
+        let mut line = String::new();
+        client.read_line(&mut line).await.unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    // Host tool that reports when it starts, then takes a while to finish
+    struct SlowTool {
+        started: Arc<tokio::sync::Notify>,
+    }
+
+    impl<R: Runtime> crate::McpTool<R> for SlowTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn description(&self) -> &str {
+            "Answers after half a second"
+        }
+
+        fn call(&self, _app: AppHandle<R>, _arguments: Value) -> crate::ToolFuture {
+            let started = self.started.clone();
+            Box::pin(async move {
+                started.notify_one();
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                Ok(serde_json::json!({ "finished": true }))
+            })
+        }
+    }
+
+    fn app_on(socket: &std::path::Path, started: Arc<tokio::sync::Notify>) -> tauri::App<tauri::test::MockRuntime> {
+        tauri::test::mock_builder()
+            .plugin(crate::init_with_config(
+                crate::PluginConfig::new("socket-test".to_string())
+                    .socket_path(socket.to_path_buf())
+                    .start_socket_server(false)
+                    .tool(SlowTool { started }),
+            ))
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap()
+    }
+
+    fn temp_socket(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("tauri-mcp-{}-{}.sock", name, std::process::id()))
+    }
+
+    // Send one command over a fresh connection to the socket
+    async fn send_command(socket: &std::path::Path, command: &str) -> Value {
+        let stream = interprocess::local_socket::tokio::Stream::connect(socket_name(&Some(socket.to_path_buf())).unwrap())
+            .await
+            .unwrap();
+        let (reader, mut writer) = stream.split();
+        let request = serde_json::json!({ "command": command, "payload": { "value": "hi" } });
+        writer.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await.unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[tokio::test]
+    async fn restarts_on_the_same_socket_path() {
+        use crate::TauriMcpExt;
+
+        let socket = temp_socket("restart");
+        let app = app_on(&socket, Default::default());
+        let mcp = app.tauri_mcp();
+
+        mcp.start_socket_server().unwrap();
+        assert_eq!(send_command(&socket, "ping").await["success"], true);
+
+        mcp.stop_socket_server().await.unwrap();
+        assert!(!mcp.is_socket_server_running());
+        assert!(!socket.exists());
+
+        mcp.start_socket_server().unwrap();
+        assert_eq!(send_command(&socket, "ping").await["success"], true);
+        mcp.stop_socket_server().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn in_flight_command_finishes_during_drain() {
+        use crate::TauriMcpExt;
+
+        let socket = temp_socket("drain");
+        let started = Arc::new(tokio::sync::Notify::new());
+        let app = app_on(&socket, started.clone());
+        let mcp = app.tauri_mcp();
+        mcp.start_socket_server().unwrap();
+
+        let path = socket.clone();
+        let in_flight = tokio::spawn(async move { send_command(&path, "slow").await });
+        started.notified().await;
+
+        mcp.stop_socket_server().await.unwrap();
+        let response = in_flight.await.unwrap();
+        assert_eq!(response["success"], true);
+        assert_eq!(response["data"]["finished"], true);
+    }
+
+    #[test]
+    fn handshake_needs_the_authenticate_command_and_token() {
+        let auth = auth();
+        assert!(is_valid_handshake(&auth, &handshake(TOKEN)));
+        assert!(!is_valid_handshake(&auth, &handshake("wrong")));
+        assert!(!is_valid_handshake(&auth, r#"{"command":"ping","payload":{"token":"s3cret-token"}}"#));
+        assert!(!is_valid_handshake(&auth, r#"{"command":"authenticate","payload":{}}"#));
+        assert!(!is_valid_handshake(&auth, "not json"));
+    }
+
+    #[tokio::test]
+    async fn good_token_authenticates_the_connection() {
+        let (mut client, task) = connect(auth());
+        client.write_all(format!("{}\n", handshake(TOKEN)).as_bytes()).await.unwrap();
+
+        let response = read_response(&mut client).await;
+        assert_eq!(response["success"], true);
+        assert_eq!(response["data"]["authenticated"], true);
+
+        drop(client);
+        task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn bad_token_closes_the_connection() {
+        let (mut client, task) = connect(auth());
+        client.write_all(format!("{}\n", handshake("wrong")).as_bytes()).await.unwrap();
+
+        let response = read_response(&mut client).await;
+        assert_eq!(response["success"], false);
+        assert_eq!(response["error"], "Authentication failed");
+
+        task.await.unwrap().unwrap();
+        let mut rest = String::new();
+        assert_eq!(client.read_line(&mut rest).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn oversized_handshake_is_rejected() {
+        let (mut client, task) = connect(auth());
+        client.write_all(&vec![b'a'; MAX_HANDSHAKE_LEN * 2]).await.unwrap();
+
+        let response = read_response(&mut client).await;
+        assert_eq!(response["success"], false);
+        task.await.unwrap().unwrap();
+    }
 }
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::bridge;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
+
+/// Nodes returned when the request does not set `max_nodes`
+const DEFAULT_MAX_NODES: usize = 1000;
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct AccessibilityTreeRequest {
+    window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    webview_label: Option<String>,
+    /// Levels of accessibility nodes to return below the root. Unlimited if omitted.
+    max_depth: Option<usize>,
+    /// Stop after this many nodes (default 1000); `truncated` is set when nodes were left out
+    max_nodes: Option<usize>,
+    /// Only return nodes that intersect the viewport
+    #[serde(default)]
+    viewport_only: bool,
+    /// Only return interactive nodes (buttons, links, form fields, focusable elements)
+    #[serde(default)]
+    interactive_only: bool,
+    /// CSS selector of the element to start from. The document body if omitted.
+    root_selector: Option<String>,
+    timeout_ms: Option<u64>,
+}
+
+/// Element bounds in CSS pixels, relative to the viewport
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NodeBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Checked and pressed states can be `true`, `false` or `"mixed"`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum NodeState {
+    Flag(bool),
+    Mixed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AccessibilityNode {
+    /// Stable ID of the element, accepted as a "ref" selector by the element tools
+    #[serde(rename = "ref")]
+    pub ref_id: String,
+    pub role: String,
+    /// Accessible name (aria-label, label, alt, text content, title or placeholder)
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Heading level
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<u8>,
+    /// checked, disabled, expanded, selected, pressed, required, readonly, focused, current
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub states: Option<BTreeMap<String, NodeState>>,
+    pub bounds: NodeBounds,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<AccessibilityNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ViewportSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityTreeResponse {
+    pub url: String,
+    pub title: String,
+    pub viewport: ViewportSize,
+    pub nodes: Vec<AccessibilityNode>,
+    pub node_count: usize,
+    /// Whether the depth or node limit left parts of the page out
+    pub truncated: bool,
+}
+
+pub async fn handle_get_accessibility_tree<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: AccessibilityTreeRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for get_accessibility_tree: {}", e)))?;
+
+    if request.max_depth == Some(0) {
+        return Err(Error::invalid_parameter("max_depth", "at least 1", "0"));
+    }
+    if request.max_nodes == Some(0) {
+        return Err(Error::invalid_parameter("max_nodes", "at least 1", "0"));
+    }
+
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+
+    let js_payload = json!({
+        "maxDepth": request.max_depth,
+        "maxNodes": request.max_nodes.unwrap_or(DEFAULT_MAX_NODES),
+        "viewportOnly": request.viewport_only,
+        "interactiveOnly": request.interactive_only,
+        "rootSelector": request.root_selector,
+    });
+
+    let response = bridge::request(
+        app,
+        webview.label(),
+        "get-accessibility-tree",
+        js_payload,
+        Duration::from_millis(request.timeout_ms.unwrap_or(10000)),
+    )
+    .await?;
+
+    let success = response.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !success {
+        let error = response
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error occurred");
+        return Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(error.to_string()),
+        });
+    }
+
+    let tree: AccessibilityTreeResponse = serde_json::from_value(response.get("data").cloned().unwrap_or(Value::Null))
+        .map_err(|e| Error::serialization_error(format!("Invalid accessibility tree result: {}", e)))?;
+    info!(
+        "[TAURI_MCP] Accessibility snapshot of {} with {} node(s){}",
+        webview.label(),
+        tree.node_count,
+        if tree.truncated { " (truncated)" } else { "" }
+    );
+
+    let data = serde_json::to_value(tree)
+        .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
@@ -0,0 +1,266 @@
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::TauriMcpExt;
+use crate::bridge;
+use crate::error::Error;
+use crate::models::ScreenshotRequest;
+use crate::shared::{CssRect, ScreenshotFormat, ScreenshotParams, SizeFallback};
+use crate::socket_server::SocketResponse;
+use crate::tools::accessibility::ViewportSize;
+use crate::tools::take_screenshot::process_image;
+use crate::tools::windows::resolve_webview;
+
+/// Elements numbered when the request does not set `max_elements`
+const DEFAULT_MAX_ELEMENTS: usize = 200;
+
+// Box colors, cycled through by mark number. Dark enough for white numbers on top.
+const PALETTE: [[u8; 3]; 8] = [
+    [220, 38, 38],
+    [37, 99, 235],
+    [22, 163, 74],
+    [192, 38, 211],
+    [234, 88, 12],
+    [13, 148, 136],
+    [124, 58, 237],
+    [161, 98, 7],
+];
+
+// 5x7 bitmaps of the digits 0-9, one row per byte, leftmost pixel in bit 4
+const DIGITS: [[u8; 7]; 10] = [
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+];
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct AnnotatedScreenshotRequest {
+    window_label: Option<String>,
+    /// Webview to capture inside a multi-webview window
+    webview_label: Option<String>,
+    /// Number at most this many elements, in document order (default 200)
+    max_elements: Option<usize>,
+    /// Image encoding, JPEG by default
+    format: Option<ScreenshotFormat>,
+    /// JPEG quality (1-100)
+    quality: Option<i32>,
+    /// Maximum image width in pixels
+    max_width: Option<i32>,
+    /// Maximum file size in MB
+    max_size_mb: Option<f32>,
+    /// What to give up first when the image is over `max_size_mb`
+    size_fallback: Option<SizeFallback>,
+    timeout_ms: Option<u64>,
+}
+
+/// A numbered element, as listed in the legend
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MarkedElement {
+    /// Number drawn on the image, accepted as a "mark" selector by the element tools
+    pub mark: u32,
+    /// Ref ID of the element, as in get_accessibility_tree
+    #[serde(rename = "ref")]
+    pub ref_id: String,
+    /// CSS selector matching only this element
+    pub selector: String,
+    pub role: String,
+    /// Accessible name or value
+    pub text: String,
+    /// Visible part of the element in CSS pixels, relative to the viewport
+    pub rect: CssRect,
+}
+
+#[derive(Debug, Deserialize)]
+struct InteractiveElements {
+    url: String,
+    viewport: ViewportSize,
+    elements: Vec<MarkedElement>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AnnotatedScreenshotResponse {
+    /// Data URL of the annotated image
+    pub data: String,
+    /// MIME type of the encoded image, e.g. "image/jpeg"
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub url: String,
+    pub viewport: ViewportSize,
+    /// Legend of the numbers on the image
+    pub elements: Vec<MarkedElement>,
+}
+
+fn fill_rect(image: &mut RgbaImage, x: i64, y: i64, width: i64, height: i64, color: Rgba<u8>) {
+    let x0 = x.clamp(0, image.width() as i64) as u32;
+    let y0 = y.clamp(0, image.height() as i64) as u32;
+    let x1 = (x + width).clamp(0, image.width() as i64) as u32;
+    let y1 = (y + height).clamp(0, image.height() as i64) as u32;
+    for py in y0..y1 {
+        for px in x0..x1 {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
+
+fn stroke_rect(image: &mut RgbaImage, x: i64, y: i64, width: i64, height: i64, thickness: i64, color: Rgba<u8>) {
+    fill_rect(image, x, y, width, thickness, color);
+    fill_rect(image, x, y + height - thickness, width, thickness, color);
+    fill_rect(image, x, y, thickness, height, color);
+    fill_rect(image, x + width - thickness, y, thickness, height, color);
+}
+
+/// Draw a numbered box around every element. `scale` converts CSS pixels to image pixels.
+fn draw_marks(image: &mut RgbaImage, elements: &[MarkedElement], origin: (f64, f64), scale: f64) {
+    // One CSS pixel per font pixel on a standard display, and never thinner than an image pixel
+    let dot = scale.round().max(1.0) as i64;
+    let thickness = (2.0 * scale).round().max(1.0) as i64;
+    let white = Rgba([255, 255, 255, 255]);
+
+    for element in elements {
+        let [r, g, b] = PALETTE[(element.mark as usize - 1) % PALETTE.len()];
+        let color = Rgba([r, g, b, 255]);
+        let x = ((element.rect.x - origin.0) * scale).round() as i64;
+        let y = ((element.rect.y - origin.1) * scale).round() as i64;
+        let width = ((element.rect.width * scale).round() as i64).max(thickness * 2);
+        let height = ((element.rect.height * scale).round() as i64).max(thickness * 2);
+        stroke_rect(image, x, y, width, height, thickness, color);
+
+        // Label at the top-left corner, above the box when there is room
+        let digits: Vec<usize> = element.mark.to_string().bytes().map(|b| (b - b'0') as usize).collect();
+        let label_width = (digits.len() as i64 * 6 + 1) * dot + 2 * dot;
+        let label_height = 9 * dot;
+        let label_y = if y >= label_height { y - label_height } else { y };
+        fill_rect(image, x, label_y, label_width, label_height, color);
+        for (i, digit) in digits.iter().enumerate() {
+            let glyph_x = x + 2 * dot + i as i64 * 6 * dot;
+            for (row, bits) in DIGITS[*digit].iter().enumerate() {
+                for column in 0..5 {
+                    if bits & (0x10 >> column) != 0 {
+                        fill_rect(image, glyph_x + column * dot, label_y + (row as i64 + 1) * dot, dot, dot, white);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Screenshot with numbered boxes around the visible interactive elements, and their legend
+pub async fn handle_take_annotated_screenshot<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: AnnotatedScreenshotRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for take_annotated_screenshot: {}", e)))?;
+
+    if request.max_elements == Some(0) {
+        return Err(Error::invalid_parameter("max_elements", "at least 1", "0"));
+    }
+
+    let window_label = request.window_label.clone().unwrap_or_else(|| "main".to_string());
+    let webview = resolve_webview(app, Some(&window_label), request.webview_label.as_deref())?;
+
+    // Numbering the elements also makes the numbers usable as selectors
+    let response = bridge::request(
+        app,
+        webview.label(),
+        "get-interactive-elements",
+        json!({ "maxElements": request.max_elements.unwrap_or(DEFAULT_MAX_ELEMENTS) }),
+        Duration::from_millis(request.timeout_ms.unwrap_or(10000)),
+    )
+    .await?;
+
+    let success = response.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !success {
+        let error = response
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error occurred");
+        return Err(Error::window_operation_failed("take_annotated_screenshot", error));
+    }
+    let page: InteractiveElements = serde_json::from_value(response.get("data").cloned().unwrap_or(Value::Null))
+        .map_err(|e| Error::serialization_error(format!("Invalid interactive elements result: {}", e)))?;
+
+    // The viewport as a region, so the image holds exactly the page the rects refer to.
+    // Lossless, since it is encoded again once the marks are drawn.
+    let capture = app
+        .tauri_mcp()
+        .take_screenshot_async(ScreenshotRequest {
+            window_label,
+            webview_label: request.webview_label.clone(),
+            x: Some(0.0),
+            y: Some(0.0),
+            width: Some(page.viewport.width as f64),
+            height: Some(page.viewport.height as f64),
+            format: Some(ScreenshotFormat::Png),
+            max_width: request.max_width,
+            max_size_mb: Some(3.5),
+            ..Default::default()
+        })
+        .await?;
+    let (data_url, region) = match (capture.success, capture.data, capture.region) {
+        (true, Some(data_url), Some(region)) => (data_url, region),
+        (_, _, _) => {
+            return Err(Error::window_operation_failed(
+                "take_annotated_screenshot",
+                capture.error.unwrap_or_else(|| "Screenshot failed".to_string()),
+            ));
+        }
+    };
+    let encoded = data_url.split_once(',').map_or(data_url.as_str(), |(_, data)| data);
+    let bytes = base64::decode(encoded)
+        .map_err(|e| Error::serialization_error(format!("Invalid screenshot data: {}", e)))?;
+    let mut image = image::load_from_memory_with_format(&bytes, ImageFormat::Png)
+        .map_err(|e| Error::serialization_error(format!("Invalid screenshot image: {}", e)))?
+        .to_rgba8();
+
+    let scale = image.width() as f64 / region.css.width;
+    draw_marks(&mut image, &page.elements, (region.css.x, region.css.y), scale);
+
+    let format = request.format.unwrap_or_default();
+    let params = ScreenshotParams {
+        window_label: None,
+        format: Some(format),
+        quality: request.quality,
+        max_width: request.max_width,
+        max_size_mb: request.max_size_mb,
+        size_fallback: request.size_fallback,
+        application_name: None,
+        crop: None,
+        strict_size: false,
+    };
+    let data = tokio::task::spawn_blocking(move || process_image(DynamicImage::ImageRgba8(image), &params))
+        .await
+        .map_err(|e| Error::Anyhow { message: format!("Image encoding task failed: {}", e) })??;
+
+    info!(
+        "[TAURI_MCP] Annotated screenshot of {} with {} marked element(s)",
+        webview.label(),
+        page.elements.len()
+    );
+
+    let data = serde_json::to_value(AnnotatedScreenshotResponse {
+        data,
+        mime_type: format.mime_type().to_string(),
+        url: page.url,
+        viewport: page.viewport,
+        elements: page.elements,
+    })
+    .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
@@ -0,0 +1,67 @@
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use crate::TauriMcpExt;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+
+#[derive(Debug, Clone, Default, serde::Deserialize, schemars::JsonSchema)]
+pub struct DescribeCommandsRequest {
+    /// Only describe these commands. All registered commands are described when omitted.
+    commands: Option<Vec<String>>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct DescribeCommandsResponse {
+    pub commands: Vec<CommandDescription>,
+}
+
+// Name, description and JSON Schemas of a single command
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct CommandDescription {
+    pub name: String,
+    pub description: String,
+    /// JSON Schema of the command's payload
+    pub input_schema: Value,
+    /// JSON Schema of the `data` member of a successful response
+    pub output_schema: Value,
+}
+
+/// Handler function for describe_commands
+/// Lists every registered command with the JSON Schemas of its payload and response
+pub fn handle_describe_commands<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    // A missing payload describes everything
+    let request: DescribeCommandsRequest = serde_json::from_value::<Option<_>>(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for describe_commands: {}", e)))?
+        .unwrap_or_default();
+
+    let commands = app
+        .tauri_mcp()
+        .tools()
+        .iter()
+        .filter(|tool| {
+            request
+                .commands
+                .as_ref()
+                .is_none_or(|names| names.iter().any(|name| name == tool.name()))
+        })
+        .map(|tool| CommandDescription {
+            name: tool.name().to_string(),
+            description: tool.description().to_string(),
+            input_schema: tool.input_schema(),
+            output_schema: tool.output_schema(),
+        })
+        .collect();
+
+    let data = serde_json::to_value(DescribeCommandsResponse { commands })
+        .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
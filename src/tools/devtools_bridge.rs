@@ -1,21 +1,25 @@
 use serde_json::{json, Value};
-use tauri::{AppHandle, Runtime, Manager, Emitter, Listener};
+use tauri::{AppHandle, Runtime};
 use log::info;
 
+use crate::bridge;
 use crate::error::Error;
 use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
 pub struct DevToolsBridgeRequest {
     window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    webview_label: Option<String>,
     max_depth: Option<usize>,
     component_filter: Option<String>,
     timeout_ms: Option<u64>,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct DevToolsBridgeResponse {
     framework: FrameworkInfo,
     components: Vec<ComponentInfo>,
@@ -23,15 +27,16 @@ pub struct DevToolsBridgeResponse {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct FrameworkInfo {
-    framework_type: String,  // "react", "vue", "both", "none"
+    /// "react", "vue", "both" or "none"
+    framework_type: String,
     react_version: Option<String>,
     vue_version: Option<String>,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ComponentInfo {
     name: String,
     id: String,
@@ -47,14 +52,14 @@ pub struct ComponentInfo {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct HookInfo {
     hook_name: String,
     hook_value: Value,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct DevToolsMetadata {
     max_depth_reached: bool,
     total_components: usize,
@@ -69,16 +74,7 @@ pub async fn handle_devtools_bridge<R: Runtime>(
     let request: DevToolsBridgeRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for devtools_bridge: {}", e)))?;
 
-    // Get the window label or use "main" as default
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
-
-    // Verify the window exists
-    let _window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
 
     // Generate the introspection JavaScript code
     let js_code = generate_devtools_bridge_code(
@@ -86,30 +82,16 @@ pub async fn handle_devtools_bridge<R: Runtime>(
         request.component_filter.clone(),
     );
 
-    // Execute the JavaScript in the window
-    let (tx, rx) = std::sync::mpsc::channel();
-
-    app.emit_to(&window_label, "execute-js", &js_code)
-        .map_err(|e| Error::communication_error_with_context(
-            "Failed to emit execute-js event",
-            format!("window: {}, error: {}", window_label, e),
-        ))?;
-
-    // Listen for response
-    app.once("execute-js-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
-
-    // Wait for the response with timeout
+    // Execute the JavaScript in the window and wait for its response
     let timeout = std::time::Duration::from_millis(request.timeout_ms.unwrap_or(5000));
-    let result_string = rx
-        .recv_timeout(timeout)
-        .map_err(|_| Error::timeout_error("devtools bridge execution", request.timeout_ms.unwrap_or(5000)))?;
-
-    // Parse the response
-    let response_value: Value = serde_json::from_str(&result_string)
-        .map_err(|e| Error::serialization_error(format!("Failed to parse devtools response: {}", e)))?;
+    let response_value = bridge::request(
+        app,
+        webview.label(),
+        "execute-js",
+        json!({ "code": js_code }),
+        timeout,
+    )
+    .await?;
 
     // Check if result contains an error
     if let Some(error) = response_value.get("error") {
@@ -1,12 +1,13 @@
 use serde::{Serialize, Serializer};
 use serde_json::Value;
 use std::fmt;
-use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+use tauri::{AppHandle, Emitter, Runtime};
 
+use crate::bridge;
 use crate::error::Error;
 use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
 
 // Error type enumeration
 #[allow(dead_code)]
@@ -35,7 +36,6 @@ impl ErrorType {
 pub enum ErrorTrackerError {
     WebviewOperation(String),
     TimeoutError(String),
-    ParseError(String),
 }
 
 // Implement Display for the error
@@ -44,7 +44,6 @@ impl fmt::Display for ErrorTrackerError {
         match self {
             ErrorTrackerError::WebviewOperation(s) => write!(f, "Error tracking operation error: {}", s),
             ErrorTrackerError::TimeoutError(s) => write!(f, "Operation timed out: {}", s),
-            ErrorTrackerError::ParseError(s) => write!(f, "Parse error: {}", s),
         }
     }
 }
@@ -59,18 +58,21 @@ impl Serialize for ErrorTrackerError {
     }
 }
 
-// Support conversion from timeout error
-impl From<mpsc::RecvTimeoutError> for ErrorTrackerError {
-    fn from(err: mpsc::RecvTimeoutError) -> Self {
-        ErrorTrackerError::TimeoutError(format!(
-            "Timeout waiting for error tracker response: {}",
-            err
-        ))
+// Support conversion from webview bridge errors
+impl From<Error> for ErrorTrackerError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::TimeoutError { .. } => ErrorTrackerError::TimeoutError(format!(
+                "Timeout waiting for error tracker response: {}",
+                err
+            )),
+            other => ErrorTrackerError::WebviewOperation(other.to_string()),
+        }
     }
 }
 
 // Stack frame representing a single line in a stack trace
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct StackFrame {
     pub function_name: Option<String>,
     pub file_name: Option<String>,
@@ -82,10 +84,11 @@ pub struct StackFrame {
 }
 
 // Single exception/error entry
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ExceptionEntry {
     pub id: String,
-    pub error_type: String, // "uncaught", "unhandledrejection", "reactboundary"
+    /// "uncaught", "unhandledrejection" or "reactboundary"
+    pub error_type: String,
     pub message: String,
     pub stack_trace: Vec<StackFrame>,
     pub first_occurrence_ms: u64,
@@ -95,10 +98,13 @@ pub struct ExceptionEntry {
 }
 
 // Request for retrieving exceptions
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
 pub struct ErrorTrackerRequest {
     pub window_label: Option<String>,
-    pub error_type: Option<String>, // "uncaught", "unhandledrejection", "reactboundary", or "all"
+    /// Webview to use inside a multi-webview window
+    pub webview_label: Option<String>,
+    /// "uncaught", "unhandledrejection", "reactboundary", or "all"
+    pub error_type: Option<String>,
     pub message_pattern: Option<String>,
     pub start_time_ms: Option<u64>,
     pub end_time_ms: Option<u64>,
@@ -106,7 +112,7 @@ pub struct ErrorTrackerRequest {
 }
 
 // Response model for exceptions
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
 pub struct ErrorTrackerResponse {
     pub exceptions: Vec<ExceptionEntry>,
     pub total_count: usize,
@@ -114,14 +120,16 @@ pub struct ErrorTrackerResponse {
 }
 
 // Request to inject error tracking script
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
 pub struct InjectErrorTrackerRequest {
     pub window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    pub webview_label: Option<String>,
     pub circular_buffer_size: Option<usize>,
 }
 
 // Response to inject error tracking script
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
 pub struct InjectErrorTrackerResponse {
     pub message: String,
     pub circular_buffer_size: usize,
@@ -132,19 +140,12 @@ pub async fn handle_get_exceptions<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
-    let request: ErrorTrackerRequest = serde_json::from_value(payload)
+    let mut request: ErrorTrackerRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for error tracker: {}", e)))?;
 
-    // Get the window label or use "main" as default
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
-
-    // Verify the window exists
-    let _window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
+    // Resolve the target webview, which the helpers then address by label
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+    request.window_label = Some(webview.label().to_string());
 
     // Get exceptions from the window
     let result = retrieve_exceptions(app.clone(), request).await;
@@ -177,26 +178,17 @@ pub async fn handle_inject_error_tracker<R: Runtime>(
     let request: InjectErrorTrackerRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for error tracker injection: {}", e)))?;
 
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
-
-    // Verify the window exists
-    let window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
 
     let circular_buffer_size = request.circular_buffer_size.unwrap_or(1000);
 
-    // Send injection event to the window
-    window
-        .emit("inject-error-tracker", serde_json::json!({
+    // Send injection event to the webview
+    app.emit_to(webview.label(), "inject-error-tracker", serde_json::json!({
             "circular_buffer_size": circular_buffer_size
         }))
         .map_err(|e| Error::communication_error_with_context(
             "Failed to emit injection event",
-            format!("window: {}, error: {}", window_label, e),
+            format!("window: {}, error: {}", webview.label(), e),
         ))?;
 
     Ok(SocketResponse {
@@ -209,34 +201,29 @@ pub async fn handle_inject_error_tracker<R: Runtime>(
     })
 }
 
+// Request to clear tracked exceptions
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct ClearExceptionsRequest {
+    window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    webview_label: Option<String>,
+}
+
 /// Handler function to clear exceptions
 pub async fn handle_clear_exceptions<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
-    #[derive(serde::Deserialize)]
-    pub struct ClearRequest {
-        window_label: Option<String>,
-    }
-
-    let request: ClearRequest = serde_json::from_value(payload)
+    let request: ClearExceptionsRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for clear exceptions: {}", e)))?;
 
-    let window_label = request
-        .window_label
-        .unwrap_or_else(|| "main".to_string());
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
 
-    // Verify the window exists
-    let window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
-
-    // Send clear event to the window
-    window
-        .emit("clear-exceptions", ())
+    // Send clear event to the webview
+    app.emit_to(webview.label(), "clear-exceptions", ())
         .map_err(|e| Error::communication_error_with_context(
             "Failed to emit clear event",
-            format!("window: {}, error: {}", window_label, e),
+            format!("window: {}, error: {}", webview.label(), e),
         ))?;
 
     Ok(SocketResponse {
@@ -265,53 +252,40 @@ async fn retrieve_exceptions<R: Runtime>(
         "limit": request.limit.unwrap_or(1000),
     });
 
-    // Emit event to retrieve exceptions from webview
-    app.emit_to(&window_label, "get-exceptions", filter_payload)
-        .map_err(|e| ErrorTrackerError::WebviewOperation(format!("Failed to emit event: {}", e)))?;
+    // Emit event to the window and wait for its response
+    let response = bridge::request(
+        &app,
+        &window_label,
+        "get-exceptions",
+        filter_payload,
+        Duration::from_secs(10),
+    )
+    .await?;
+
+    // Check if result contains an error
+    if let Some(error) = response.get("error")
+        && let Some(error_str) = error.as_str()
+    {
+        return Err(ErrorTrackerError::WebviewOperation(error_str.to_string()));
+    }
 
-    // Set up channel for response
-    let (tx, rx) = mpsc::channel();
+    // Extract exceptions array from response
+    let exceptions: Vec<ExceptionEntry> = response
+        .get("exceptions")
+        .and_then(|e| serde_json::from_value(e.clone()).ok())
+        .unwrap_or_default();
 
-    // Listen for response
-    app.once("get-exceptions-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
+    let total_count = response
+        .get("total_count")
+        .and_then(|c| c.as_u64())
+        .map(|c| c as usize)
+        .unwrap_or(exceptions.len());
 
-    // Wait for response with timeout (10 seconds)
-    match rx.recv_timeout(Duration::from_secs(10)) {
-        Ok(result_string) => {
-            // Parse the response
-            let response: Value = serde_json::from_str(&result_string)
-                .map_err(|e| ErrorTrackerError::ParseError(format!("Failed to parse response: {}", e)))?;
-
-            // Check if result contains an error
-            if let Some(error) = response.get("error") {
-                if let Some(error_str) = error.as_str() {
-                    return Err(ErrorTrackerError::WebviewOperation(error_str.to_string()));
-                }
-            }
-
-            // Extract exceptions array from response
-            let exceptions: Vec<ExceptionEntry> = response
-                .get("exceptions")
-                .and_then(|e| serde_json::from_value(e.clone()).ok())
-                .unwrap_or_default();
-
-            let total_count = response
-                .get("total_count")
-                .and_then(|c| c.as_u64())
-                .map(|c| c as usize)
-                .unwrap_or(exceptions.len());
-
-            let returned_count = exceptions.len();
-
-            Ok(ErrorTrackerResponse {
-                exceptions,
-                total_count,
-                returned_count,
-            })
-        }
-        Err(e) => Err(e.into()),
-    }
+    let returned_count = exceptions.len();
+
+    Ok(ErrorTrackerResponse {
+        exceptions,
+        total_count,
+        returned_count,
+    })
 }
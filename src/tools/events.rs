@@ -0,0 +1,361 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, EventId, Listener, Manager, Runtime};
+use log::info;
+
+use crate::TauriMcpExt;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
+
+/// Events recorded when the plugin configuration does not say otherwise
+pub const DEFAULT_EVENT_CAPACITY: usize = 1000;
+
+/// Events Tauri emits itself, so wildcard patterns can match them without being declared
+const TAURI_EVENTS: &[&str] = &[
+    "tauri://resize",
+    "tauri://move",
+    "tauri://close-requested",
+    "tauri://destroyed",
+    "tauri://focus",
+    "tauri://blur",
+    "tauri://scale-change",
+    "tauri://theme-changed",
+    "tauri://window-created",
+    "tauri://webview-created",
+    "tauri://drag-enter",
+    "tauri://drag-over",
+    "tauri://drag-drop",
+    "tauri://drag-leave",
+];
+
+/// An event received while recording
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RecordedEvent {
+    /// Increases with every recorded event; pass the last one seen as `since` to get only newer events
+    pub sequence: u64,
+    pub event: String,
+    /// Payload as JSON, or as a string if it is not valid JSON
+    pub payload: Value,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+#[derive(Default)]
+struct Recording {
+    patterns: Vec<String>,
+    listeners: HashMap<String, EventId>,
+    entries: VecDeque<RecordedEvent>,
+    next_sequence: u64,
+    dropped: u64,
+}
+
+/// Records app events matching name patterns.
+/// Tauri has no catch-all listener, so patterns are resolved against the event names the plugin
+/// knows: exact patterns, names declared by the app, Tauri's own events and names sent with `emit_event`.
+pub struct EventRecorder {
+    capacity: usize,
+    known: Mutex<BTreeSet<String>>,
+    recording: Mutex<Recording>,
+}
+
+impl EventRecorder {
+    pub fn new(capacity: usize, app_events: &[String]) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            known: Mutex::new(
+                TAURI_EVENTS
+                    .iter()
+                    .map(|name| name.to_string())
+                    .chain(app_events.iter().cloned())
+                    .collect(),
+            ),
+            recording: Mutex::new(Recording::default()),
+        }
+    }
+
+    /// Remember an event name and start listening to it if an active pattern matches
+    pub fn learn<R: Runtime>(&self, app: &AppHandle<R>, name: &str) {
+        self.known.lock().unwrap().insert(name.to_string());
+        let mut recording = self.recording.lock().unwrap();
+        if recording.patterns.iter().any(|pattern| pattern_matches(pattern, name)) {
+            listen(app, &mut recording, name);
+        }
+    }
+
+    /// Add patterns and listen to every known event they match
+    fn start<R: Runtime>(&self, app: &AppHandle<R>, patterns: Vec<String>) {
+        let mut known = self.known.lock().unwrap();
+        let mut recording = self.recording.lock().unwrap();
+        for pattern in patterns {
+            if !pattern.contains('*') {
+                known.insert(pattern.clone());
+            }
+            if !recording.patterns.contains(&pattern) {
+                recording.patterns.push(pattern);
+            }
+        }
+        let matching: Vec<String> = known
+            .iter()
+            .filter(|name| recording.patterns.iter().any(|pattern| pattern_matches(pattern, name)))
+            .cloned()
+            .collect();
+        for name in matching {
+            listen(app, &mut recording, &name);
+        }
+    }
+
+    /// Stop listening to everything. Recorded events are kept until cleared.
+    fn stop<R: Runtime>(&self, app: &AppHandle<R>) {
+        let mut recording = self.recording.lock().unwrap();
+        recording.patterns.clear();
+        for (_, id) in recording.listeners.drain() {
+            app.unlisten(id);
+        }
+    }
+
+    fn clear(&self) {
+        let mut recording = self.recording.lock().unwrap();
+        recording.entries.clear();
+        recording.dropped = 0;
+    }
+
+    fn push(&self, event: &str, payload: &str) {
+        let mut recording = self.recording.lock().unwrap();
+        // Late deliveries after stop are not wanted
+        if !recording.listeners.contains_key(event) {
+            return;
+        }
+        if recording.entries.len() == self.capacity {
+            recording.entries.pop_front();
+            recording.dropped += 1;
+        }
+        recording.next_sequence += 1;
+        let entry = RecordedEvent {
+            sequence: recording.next_sequence,
+            event: event.to_string(),
+            payload: serde_json::from_str(payload).unwrap_or_else(|_| Value::String(payload.to_string())),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+        };
+        recording.entries.push_back(entry);
+    }
+
+    fn snapshot(&self, since: Option<u64>, event: Option<&str>, limit: Option<usize>) -> ListenEventsResponse {
+        let recording = self.recording.lock().unwrap();
+        let matching: Vec<RecordedEvent> = recording
+            .entries
+            .iter()
+            .filter(|entry| since.is_none_or(|since| entry.sequence > since))
+            .filter(|entry| event.is_none_or(|pattern| pattern_matches(pattern, &entry.event)))
+            .cloned()
+            .collect();
+        let skip = limit.map_or(0, |limit| matching.len().saturating_sub(limit));
+
+        ListenEventsResponse {
+            patterns: recording.patterns.clone(),
+            listening: recording.listeners.keys().cloned().collect::<BTreeSet<_>>().into_iter().collect(),
+            total_count: matching.len(),
+            events: matching.into_iter().skip(skip).collect(),
+            dropped_count: recording.dropped,
+        }
+    }
+}
+
+fn listen<R: Runtime>(app: &AppHandle<R>, recording: &mut Recording, name: &str) {
+    if recording.listeners.contains_key(name) {
+        return;
+    }
+    let handle = app.clone();
+    let event_name = name.to_string();
+    let id = app.listen_any(name, move |event| {
+        if let Some(state) = handle.try_state::<crate::TauriMcp<R>>() {
+            state.events().push(&event_name, event.payload());
+        }
+    });
+    recording.listeners.insert(name.to_string(), id);
+}
+
+/// Match an event name against a pattern where `*` stands for any run of characters
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard at all
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Tauri only accepts alphanumeric characters, `-`, `/`, `:` and `_` in event names
+fn is_valid_event_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '/' || c == ':' || c == '_')
+}
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct EmitEventRequest {
+    /// Event name
+    event: String,
+    /// JSON payload (default null)
+    #[serde(default)]
+    payload: Value,
+    /// Only deliver to listeners of this window or webview label. Default is every listener.
+    target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct EmitEventResponse {
+    pub event: String,
+    /// Label the event was sent to, or null for a global emit
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct ListenEventsRequest {
+    /// "start", "get", "stop" or "clear"
+    action: String,
+    /// Event names to record for "start"; `*` matches any run of characters, e.g. "download-*"
+    patterns: Option<Vec<String>>,
+    /// For "get": only events recorded after this sequence number
+    since: Option<u64>,
+    /// For "get": only events whose name matches this pattern
+    event: Option<String>,
+    /// For "get": at most this many of the most recent events
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ListenEventsResponse {
+    /// Active patterns
+    pub patterns: Vec<String>,
+    /// Event names currently listened to
+    pub listening: Vec<String>,
+    /// Recorded events matching the request, oldest first
+    pub events: Vec<RecordedEvent>,
+    /// Matching events before `limit` was applied
+    pub total_count: usize,
+    /// Events evicted from the full buffer since it was last cleared
+    pub dropped_count: u64,
+}
+
+/// Fire an app event, globally or to one window or webview, as if the app had emitted it
+pub fn handle_emit_event<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: EmitEventRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for emit_event: {}", e)))?;
+
+    if !is_valid_event_name(&request.event) {
+        return Err(Error::invalid_parameter(
+            "event",
+            "a name made of alphanumeric characters, '-', '/', ':' and '_'",
+            &request.event,
+        ));
+    }
+
+    // Listen before emitting, so an active pattern catches this very event
+    app.tauri_mcp().events().learn(app, &request.event);
+
+    match &request.target {
+        Some(target) => {
+            // Tauri silently drops events for unknown labels
+            resolve_webview(app, Some(target), None).or_else(|_| resolve_webview(app, None, Some(target)))?;
+            app.emit_to(target.as_str(), &request.event, &request.payload)
+        }
+        None => app.emit(&request.event, &request.payload),
+    }
+    .map_err(|e| Error::window_operation_failed("emit_event", e.to_string()))?;
+
+    info!(
+        "[TAURI_MCP] Emitted event {} to {}",
+        request.event,
+        request.target.as_deref().unwrap_or("all listeners")
+    );
+
+    let data = serde_json::to_value(EmitEventResponse {
+        event: request.event,
+        target: request.target,
+    })
+    .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
+
+/// Start, stop, read or clear the recording of app events
+pub fn handle_listen_events<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: ListenEventsRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for listen_events: {}", e)))?;
+
+    let recorder = app.tauri_mcp().events();
+    match request.action.as_str() {
+        "start" => {
+            let patterns = request
+                .patterns
+                .filter(|patterns| !patterns.is_empty())
+                .ok_or_else(|| Error::invalid_parameter("patterns", "event name patterns for \"start\"", "nothing"))?;
+            if let Some(invalid) = patterns
+                .iter()
+                .find(|pattern| pattern.as_str() != "*" && !is_valid_event_name(&pattern.replace('*', "")))
+            {
+                return Err(Error::invalid_parameter(
+                    "patterns",
+                    "event names, optionally with '*' wildcards",
+                    invalid,
+                ));
+            }
+            recorder.start(app, patterns);
+        }
+        "stop" => recorder.stop(app),
+        "clear" => recorder.clear(),
+        "get" => {}
+        other => {
+            return Err(Error::invalid_parameter(
+                "action",
+                "\"start\", \"get\", \"stop\" or \"clear\"",
+                other,
+            ));
+        }
+    }
+
+    let response = recorder.snapshot(request.since, request.event.as_deref(), request.limit);
+    info!(
+        "[TAURI_MCP] Event recording {}: {} pattern(s), {} event(s) listened to",
+        request.action,
+        response.patterns.len(),
+        response.listening.len()
+    );
+
+    let data = serde_json::to_value(response)
+        .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
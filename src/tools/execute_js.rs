@@ -1,12 +1,13 @@
 use serde::{Serialize, Serializer};
 use serde_json::Value;
 use std::fmt;
-use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+use tauri::{AppHandle, Runtime};
 
+use crate::bridge;
 use crate::error::Error;
 use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
 
 // Define a custom error type for JavaScript execution operations
 #[derive(Debug)]
@@ -38,24 +39,29 @@ impl Serialize for ExecuteJsError {
     }
 }
 
-// Support conversion from timeout error
-impl From<mpsc::RecvTimeoutError> for ExecuteJsError {
-    fn from(err: mpsc::RecvTimeoutError) -> Self {
-        ExecuteJsError::Timeout(format!(
-            "Timeout waiting for JavaScript execution response: {}",
-            err
-        ))
+// Support conversion from webview bridge errors
+impl From<Error> for ExecuteJsError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::TimeoutError { .. } => ExecuteJsError::Timeout(format!(
+                "Timeout waiting for JavaScript execution response: {}",
+                err
+            )),
+            other => ExecuteJsError::WebviewOperation(other.to_string()),
+        }
     }
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
 pub struct ExecuteJsRequest {
     window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    webview_label: Option<String>,
     code: String,
     timeout_ms: Option<u64>,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ExecuteJsResponse {
     result: String,
     #[serde(rename = "type")]
@@ -66,19 +72,12 @@ pub async fn handle_execute_js<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
-    let request: ExecuteJsRequest = serde_json::from_value(payload)
+    let mut request: ExecuteJsRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for executeJs: {}", e)))?;
 
-    // Get the window label or use "main" as default
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
-
-    // Verify the window exists
-    let _window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
+    // Resolve the target webview, which the helpers then address by label
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+    request.window_label = Some(webview.label().to_string());
 
     // Execute JavaScript and get the result
     let result = execute_js_in_window(app.clone(), request).await;
@@ -118,58 +117,42 @@ async fn execute_js_in_window<R: Runtime>(
     // Get timeout or use default (5 seconds)
     let timeout = Duration::from_millis(params.timeout_ms.unwrap_or(5000));
 
-    // Emit event to execute the JavaScript in the specified window
-    app.emit_to(&window_label, "execute-js", &params.code)
-        .map_err(|e| {
-            ExecuteJsError::WebviewOperation(format!("Failed to emit execute-js event to {}: {}", window_label, e))
-        })?;
-
-    // Set up a channel to receive the response
-    let (tx, rx) = mpsc::channel();
-
-    // Listen for response
-    app.once("execute-js-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
-
-    // Wait for the response with timeout
-    match rx.recv_timeout(timeout) {
-        Ok(result_string) => {
-            // Parse the response JSON
-            let response: Value = serde_json::from_str(&result_string).map_err(|e| {
-                ExecuteJsError::JavaScriptError(format!("Failed to parse response: {}", e))
-            })?;
-
-            // Check if result contains an error
-            if let Some(error) = response.get("error") {
-                if let Some(error_str) = error.as_str() {
-                    return Err(ExecuteJsError::JavaScriptError(error_str.to_string()));
-                } else {
-                    return Err(ExecuteJsError::JavaScriptError(
-                        "Unknown JavaScript execution error".to_string(),
-                    ));
-                }
-            }
-
-            // Build the ExecuteJsResponse
-            let result = response
-                .get("result")
-                .and_then(|r| r.as_str())
-                .unwrap_or("[Result could not be stringified]")
-                .to_string();
-
-            let result_type = response
-                .get("type")
-                .and_then(|t| t.as_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            Ok(ExecuteJsResponse {
-                result,
-                result_type,
-            })
+    // Execute the JavaScript in the specified window and wait for its response
+    let response = bridge::request(
+        &app,
+        &window_label,
+        "execute-js",
+        serde_json::json!({ "code": params.code }),
+        timeout,
+    )
+    .await?;
+
+    // Check if result contains an error
+    if let Some(error) = response.get("error") {
+        if let Some(error_str) = error.as_str() {
+            return Err(ExecuteJsError::JavaScriptError(error_str.to_string()));
+        } else {
+            return Err(ExecuteJsError::JavaScriptError(
+                "Unknown JavaScript execution error".to_string(),
+            ));
         }
-        Err(e) => Err(e.into()),
     }
+
+    // Build the ExecuteJsResponse
+    let result = response
+        .get("result")
+        .and_then(|r| r.as_str())
+        .unwrap_or("[Result could not be stringified]")
+        .to_string();
+
+    let result_type = response
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    Ok(ExecuteJsResponse {
+        result,
+        result_type,
+    })
 }
@@ -0,0 +1,598 @@
+// HAR 1.2 export of captured network requests, and import of HAR files as network mocks.
+//
+// Export converts the `NetworkRequest` log of a webview into a document that browser devtools
+// and HAR viewers understand. Import turns each recorded entry into a `network_mock` rule that
+// answers the same method and URL with the recorded response, so a session can be replayed
+// without the backend.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::network_inspector::{NetworkInspectorRequest, NetworkRequest, retrieve_network_requests};
+use crate::tools::network_mock::{MockResponse, NetworkMockRule, apply_mock_action, prepare_rule};
+
+/// Requests the guest keeps per webview, exported when the filter sets no limit
+const MAX_EXPORTED_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    pub started_date_time: String,
+    /// Total time in milliseconds
+    #[serde(default)]
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    #[serde(default)]
+    pub cache: Value,
+    #[serde(default)]
+    pub timings: HarTimings,
+    /// "fetch", "xhr" or "ipc", named like Chrome's custom field
+    #[serde(rename = "_resourceType", default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    /// Network error of a failed request
+    #[serde(rename = "_error", default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Answered, delayed or failed by a `network_mock` rule
+    #[serde(rename = "_mocked", default, skip_serializing_if = "std::ops::Not::not")]
+    pub mocked: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<Value>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    #[serde(default)]
+    pub query_string: Vec<HarNameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    /// 0 when the request failed before a response arrived
+    pub status: u16,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<Value>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    #[serde(default)]
+    pub content: HarContent,
+    #[serde(rename = "redirectURL", default)]
+    pub redirect_url: String,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// "base64" for binary bodies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+/// Only the wait phase is known; the capture hooks do not see connection setup
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarTimings {
+    #[serde(default)]
+    pub send: f64,
+    #[serde(default)]
+    pub wait: f64,
+    #[serde(default)]
+    pub receive: f64,
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+/// Header map as HAR name/value pairs, sorted so exports are stable
+fn name_values(headers: &HashMap<String, String>) -> Vec<HarNameValue> {
+    let mut pairs: Vec<HarNameValue> = headers
+        .iter()
+        .map(|(name, value)| HarNameValue {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    pairs.sort_by(|a, b| a.name.cmp(&b.name));
+    pairs
+}
+
+fn query_string(url: &str) -> Vec<HarNameValue> {
+    let Some((_, query)) = url.split_once('?') else {
+        return Vec::new();
+    };
+    let query = query.split('#').next().unwrap_or_default();
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            HarNameValue {
+                name: name.to_string(),
+                value: value.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Milliseconds since the Unix epoch as an ISO 8601 UTC date, e.g. "2024-06-10T08:15:30.123Z"
+fn iso8601(timestamp_ms: u64) -> String {
+    let days = (timestamp_ms / 86_400_000) as i64;
+    let ms_of_day = timestamp_ms % 86_400_000;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        ms_of_day / 3_600_000,
+        ms_of_day / 60_000 % 60,
+        ms_of_day / 1000 % 60,
+        ms_of_day % 1000
+    )
+}
+
+fn header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Convert captured requests to a HAR document, oldest first
+pub fn to_har(requests: &[NetworkRequest]) -> Har {
+    let mut entries: Vec<HarEntry> = requests
+        .iter()
+        .map(|request| {
+            let duration = request.duration_ms.unwrap_or(0) as f64;
+            HarEntry {
+                started_date_time: iso8601(request.start_time_ms),
+                time: duration,
+                request: HarRequest {
+                    method: request.method.clone(),
+                    url: request.url.clone(),
+                    http_version: String::new(),
+                    cookies: Vec::new(),
+                    headers: name_values(&request.request_headers),
+                    query_string: query_string(&request.url),
+                    post_data: request.request_body.as_ref().map(|text| HarPostData {
+                        mime_type: header(&request.request_headers, "content-type")
+                            .unwrap_or_default()
+                            .to_string(),
+                        text: text.clone(),
+                    }),
+                    headers_size: -1,
+                    body_size: request.request_body.as_ref().map_or(0, |body| body.len() as i64),
+                },
+                response: HarResponse {
+                    status: request.status_code.unwrap_or(0),
+                    status_text: String::new(),
+                    http_version: String::new(),
+                    cookies: Vec::new(),
+                    headers: name_values(&request.response_headers),
+                    content: HarContent {
+                        size: request.response_body.as_ref().map_or(0, |body| body.len() as i64),
+                        mime_type: header(&request.response_headers, "content-type")
+                            .unwrap_or_default()
+                            .to_string(),
+                        text: request.response_body.clone(),
+                        encoding: None,
+                    },
+                    redirect_url: header(&request.response_headers, "location")
+                        .unwrap_or_default()
+                        .to_string(),
+                    headers_size: -1,
+                    body_size: request.response_body.as_ref().map_or(-1, |body| body.len() as i64),
+                },
+                cache: json!({}),
+                timings: HarTimings {
+                    send: 0.0,
+                    wait: duration,
+                    receive: 0.0,
+                },
+                resource_type: Some(request.request_type.clone()),
+                error: request.error.clone(),
+                mocked: request.mocked,
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| a.started_date_time.cmp(&b.started_date_time));
+
+    Har {
+        log: HarLog {
+            version: "1.2".to_string(),
+            creator: HarCreator {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            entries,
+        },
+    }
+}
+
+/// Regex matching exactly the given text
+fn exact_pattern(text: &str) -> String {
+    let mut pattern = String::with_capacity(text.len() + 2);
+    pattern.push('^');
+    for c in text.chars() {
+        if "\\^$.|?*+()[]{}/".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('$');
+    pattern
+}
+
+/// Turn HAR entries into mock rules answering the same method and URL. Entries that cannot be
+/// replayed (redirects and informational responses) are counted as skipped.
+pub fn to_mock_rules(har: &Har) -> (Vec<NetworkMockRule>, usize) {
+    let mut rules = Vec::new();
+    let mut skipped = 0;
+
+    for entry in &har.log.entries {
+        let mut rule = NetworkMockRule {
+            id: None,
+            url_pattern: exact_pattern(&entry.request.url),
+            method: Some(entry.request.method.to_uppercase()),
+            body_pattern: None,
+            response: None,
+            latency_ms: None,
+            fail: None,
+            hit_count: 0,
+        };
+
+        let status = entry.response.status;
+        if status == 0 || entry.error.is_some() {
+            rule.fail = Some(entry.error.clone().unwrap_or_else(|| "Failed to fetch".to_string()));
+        } else if (200..=599).contains(&status) && !(300..400).contains(&status) {
+            // Binary bodies stay base64 encoded; the page decodes them into the response bytes
+            let content = &entry.response.content;
+            let encoding = content.encoding.clone().filter(|encoding| encoding == "base64");
+            rule.response = Some(MockResponse {
+                status,
+                headers: entry
+                    .response
+                    .headers
+                    .iter()
+                    // The body is replayed decoded and in full
+                    .filter(|h| !h.name.eq_ignore_ascii_case("content-encoding") && !h.name.eq_ignore_ascii_case("content-length"))
+                    .map(|h| (h.name.clone(), h.value.clone()))
+                    .collect(),
+                body: content.text.clone(),
+                encoding,
+            });
+        } else {
+            skipped += 1;
+            continue;
+        }
+
+        match prepare_rule(rule) {
+            Ok(rule) => rules.push(rule),
+            Err(_) => skipped += 1,
+        }
+    }
+
+    (rules, skipped)
+}
+
+/// `network_inspector` action "export_har": the captured requests as a HAR document,
+/// returned or written to `path`
+pub async fn handle_export_har<R: Runtime>(
+    app: &AppHandle<R>,
+    mut request: NetworkInspectorRequest,
+) -> Result<SocketResponse, Error> {
+    let path = request.path.take();
+    let filter = request.filter.get_or_insert_with(Default::default);
+    filter.limit.get_or_insert(MAX_EXPORTED_ENTRIES);
+
+    let captured = retrieve_network_requests(app.clone(), request)
+        .await
+        .map_err(|e| Error::window_operation_failed("export_har", e.to_string()))?;
+    let har = to_har(&captured.requests);
+    let entry_count = har.log.entries.len();
+
+    let data = match path {
+        Some(path) => {
+            let document = serde_json::to_string_pretty(&har)
+                .map_err(|e| Error::serialization_error(format!("Failed to serialize HAR: {}", e)))?;
+            std::fs::write(&path, document).map_err(|e| Error::Io {
+                message: format!("Failed to write HAR file {}: {}", path, e),
+            })?;
+            info!("[TAURI_MCP] Exported {} request(s) to {}", entry_count, path);
+            json!({ "path": path, "entry_count": entry_count })
+        }
+        None => json!({ "har": har, "entry_count": entry_count }),
+    };
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
+
+/// `network_inspector` action "import_har": install the entries of a HAR document (from `path`
+/// or inline in `har`) as network mocks in the webview
+pub async fn handle_import_har<R: Runtime>(
+    app: &AppHandle<R>,
+    request: NetworkInspectorRequest,
+) -> Result<SocketResponse, Error> {
+    let document = match (request.har, request.path.as_deref()) {
+        (Some(har), _) => har,
+        (None, Some(path)) => {
+            let text = std::fs::read_to_string(path).map_err(|e| Error::Io {
+                message: format!("Failed to read HAR file {}: {}", path, e),
+            })?;
+            serde_json::from_str(&text)
+                .map_err(|e| Error::serialization_error(format!("{} is not valid JSON: {}", path, e)))?
+        }
+        (None, None) => {
+            return Err(Error::invalid_parameter("path", "a HAR file path or an inline \"har\" document", "nothing"));
+        }
+    };
+    let har: Har = serde_json::from_value(document)
+        .map_err(|e| Error::serialization_error(format!("Invalid HAR document: {}", e)))?;
+
+    let (rules, skipped_count) = to_mock_rules(&har);
+    let imported_count = rules.len();
+    let window_label = request.window_label.unwrap_or_default();
+
+    let rules = apply_mock_action(
+        app,
+        &window_label,
+        json!({ "action": "add", "rules": rules }),
+        Duration::from_secs(15),
+    )
+    .await?;
+    info!(
+        "[TAURI_MCP] Imported {} HAR entries as mocks in {}, {} skipped",
+        imported_count, window_label, skipped_count
+    );
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(json!({
+            "imported_count": imported_count,
+            "skipped_count": skipped_count,
+            "rules": rules,
+        })),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::network_inspector::tests::{app_with_records, record};
+
+    fn entry(status: u16, content: HarContent) -> HarEntry {
+        HarEntry {
+            started_date_time: iso8601(0),
+            time: 0.0,
+            request: HarRequest {
+                method: "get".to_string(),
+                url: "https://example.com/logo.png?v=1".to_string(),
+                http_version: String::new(),
+                cookies: Vec::new(),
+                headers: Vec::new(),
+                query_string: Vec::new(),
+                post_data: None,
+                headers_size: -1,
+                body_size: -1,
+            },
+            response: HarResponse {
+                status,
+                status_text: String::new(),
+                http_version: String::new(),
+                cookies: Vec::new(),
+                headers: vec![
+                    HarNameValue { name: "Content-Type".to_string(), value: "image/png".to_string() },
+                    HarNameValue { name: "Content-Length".to_string(), value: "4".to_string() },
+                ],
+                content,
+                redirect_url: String::new(),
+                headers_size: -1,
+                body_size: -1,
+            },
+            cache: json!({}),
+            timings: HarTimings::default(),
+            resource_type: None,
+            error: None,
+            mocked: false,
+        }
+    }
+
+    fn har(entries: Vec<HarEntry>) -> Har {
+        Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator { name: "test".to_string(), version: "0".to_string() },
+                entries,
+            },
+        }
+    }
+
+    #[test]
+    fn iso8601_formats_utc_dates() {
+        assert_eq!(iso8601(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(iso8601(951_782_400_000), "2000-02-29T00:00:00.000Z");
+        assert_eq!(iso8601(1_718_007_330_123), "2024-06-10T08:15:30.123Z");
+    }
+
+    #[test]
+    fn exact_pattern_escapes_regex_characters() {
+        assert_eq!(exact_pattern("https://a.b/c?d=(1)"), r"^https:\/\/a\.b\/c\?d=\(1\)$");
+    }
+
+    #[test]
+    fn to_har_orders_entries_and_copies_fields() {
+        let requests: Vec<NetworkRequest> = vec![
+            serde_json::from_value(record("late", "fetch", true)).unwrap(),
+            serde_json::from_value(json!({
+                "id": "early",
+                "url": "https://example.com/search?q=tauri&page=2",
+                "method": "POST",
+                "request_type": "xhr",
+                "status_code": null,
+                "request_headers": { "Content-Type": "application/json" },
+                "response_headers": {},
+                "request_body": "{\"q\":1}",
+                "error": "Failed to fetch",
+                "start_time_ms": 1_000,
+            }))
+            .unwrap(),
+        ];
+
+        let har = to_har(&requests);
+        assert_eq!(har.log.version, "1.2");
+        let [early, late] = &har.log.entries[..] else { panic!("expected two entries") };
+
+        assert_eq!(early.request.url, "https://example.com/search?q=tauri&page=2");
+        assert_eq!(early.request.query_string.len(), 2);
+        assert_eq!(early.request.query_string[1].value, "2");
+        let post_data = early.request.post_data.as_ref().unwrap();
+        assert_eq!((post_data.mime_type.as_str(), post_data.text.as_str()), ("application/json", "{\"q\":1}"));
+        assert_eq!(early.response.status, 0);
+        assert_eq!(early.error.as_deref(), Some("Failed to fetch"));
+
+        assert_eq!(late.response.status, 200);
+        assert_eq!(late.response.content.mime_type, "application/json");
+        assert_eq!(late.time, 20.0);
+        assert!(late.mocked);
+    }
+
+    #[test]
+    fn binary_bodies_stay_base64_in_mock_rules() {
+        let bytes = [0x89, b'P', 0xff, 0x00];
+        let content = HarContent {
+            size: 4,
+            mime_type: "image/png".to_string(),
+            text: Some(base64::encode(bytes)),
+            encoding: Some("base64".to_string()),
+        };
+
+        let (rules, skipped) = to_mock_rules(&har(vec![entry(200, content)]));
+        assert_eq!(skipped, 0);
+        let rule = &rules[0];
+        assert_eq!(rule.method.as_deref(), Some("GET"));
+        assert_eq!(rule.url_pattern, exact_pattern("https://example.com/logo.png?v=1"));
+
+        let response = rule.response.as_ref().unwrap();
+        assert_eq!(response.encoding.as_deref(), Some("base64"));
+        assert_eq!(base64::decode(response.body.as_deref().unwrap()).unwrap(), bytes);
+        assert!(response.headers.contains_key("Content-Type"));
+        assert!(!response.headers.contains_key("Content-Length"));
+    }
+
+    #[test]
+    fn unreplayable_entries_are_skipped() {
+        let text = |body: &str| HarContent { text: Some(body.to_string()), ..Default::default() };
+        let invalid_base64 = HarContent {
+            text: Some("not base64!".to_string()),
+            encoding: Some("base64".to_string()),
+            ..Default::default()
+        };
+
+        let (rules, skipped) = to_mock_rules(&har(vec![
+            entry(200, text("ok")),
+            entry(302, text("")),
+            entry(101, text("")),
+            entry(200, invalid_base64),
+            entry(0, text("")),
+        ]));
+        assert_eq!(skipped, 3);
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].response.as_ref().unwrap().body.as_deref(), Some("ok"));
+        assert_eq!(rules[1].fail.as_deref(), Some("Failed to fetch"));
+    }
+
+    #[tokio::test]
+    async fn export_with_the_default_filter_contains_entries() {
+        let (app, _) = app_with_records(vec![record("a", "fetch", false), record("b", "ipc", false)]);
+        let request: NetworkInspectorRequest =
+            serde_json::from_value(json!({ "action": "export_har", "window_label": "main" })).unwrap();
+
+        let response = handle_export_har(app.handle(), request).await.unwrap();
+        let data = response.data.unwrap();
+        assert_eq!(data["entry_count"], 2);
+        assert_eq!(data["har"]["log"]["entries"].as_array().unwrap().len(), 2);
+    }
+}
@@ -1,12 +1,18 @@
 use serde_json::Value;
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, Runtime};
 use log::info;
 
+use crate::TauriMcpExt;
 use crate::error::Error;
 use crate::socket_server::SocketResponse;
+use crate::tools::windows::{DEFAULT_WINDOW_LABEL, list_windows};
 use std::env;
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+// health_check takes no arguments
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct HealthCheckRequest {}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HealthCheckResponse {
     pub status: String,
@@ -18,7 +24,7 @@ pub struct HealthCheckResponse {
     pub webview_status: WebviewStatus,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BuildInfo {
     pub version: String,
@@ -26,7 +32,7 @@ pub struct BuildInfo {
     pub profile: String,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SystemInfo {
     pub os: String,
@@ -35,18 +41,20 @@ pub struct SystemInfo {
     pub cpu_count: usize,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ConnectionStatus {
     pub socket_server_running: bool,
     pub event_system_available: bool,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WebviewStatus {
     pub webview_available: bool,
     pub main_window_available: bool,
+    /// Labels of every open window, see `list_windows` for details
+    pub window_labels: Vec<String>,
 }
 
 pub fn handle_health_check<R: Runtime>(
@@ -58,7 +66,7 @@ pub fn handle_health_check<R: Runtime>(
     let capabilities = detect_capabilities(app);
     let webview_status = check_webview_status(app);
     let connection_status = ConnectionStatus {
-        socket_server_running: true,
+        socket_server_running: app.tauri_mcp().is_socket_server_running(),
         event_system_available: true,
     };
 
@@ -91,37 +99,22 @@ pub fn handle_health_check<R: Runtime>(
     })
 }
 
-fn detect_capabilities<R: Runtime>(_app: &AppHandle<R>) -> Vec<String> {
-    vec![
-        "take_screenshot".to_string(),
-        "get_dom".to_string(),
-        "execute_js".to_string(),
-        "manage_window".to_string(),
-        "simulate_text_input".to_string(),
-        "simulate_mouse_movement".to_string(),
-        "get_element_position".to_string(),
-        "send_text_to_element".to_string(),
-        "manage_local_storage".to_string(),
-        "hot_reload".to_string(),
-        "get_console_logs".to_string(),
-        "inject_console_capture".to_string(),
-        "network_inspector".to_string(),
-        "inject_network_capture".to_string(),
-        "state_dump".to_string(),
-        "get_exceptions".to_string(),
-        "inject_error_tracker".to_string(),
-        "clear_exceptions".to_string(),
-        "get_performance_metrics".to_string(),
-        "health_check".to_string(),
-    ]
+// Capabilities are the tools currently registered, built-in and host-provided
+fn detect_capabilities<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    app.tauri_mcp().tools().names()
 }
 
 fn check_webview_status<R: Runtime>(app: &AppHandle<R>) -> WebviewStatus {
-    let main_window_available = app.get_webview_window("main").is_some();
+    let window_labels: Vec<String> = list_windows(app)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|window| window.label)
+        .collect();
 
     WebviewStatus {
-        webview_available: true,
-        main_window_available,
+        webview_available: !window_labels.is_empty(),
+        main_window_available: window_labels.iter().any(|label| label == DEFAULT_WINDOW_LABEL),
+        window_labels,
     }
 }
 
@@ -1,18 +1,22 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, Runtime};
 
 use crate::error::Error;
 use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
 
-#[derive(Debug, Deserialize)]
-struct HotReloadPayload {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct HotReloadPayload {
     /// The label of the window to reload. Defaults to "main" if not provided.
     window_label: Option<String>,
+    /// Webview to reload inside a multi-webview window
+    webview_label: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct HotReloadResult {
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct HotReloadResult {
     success: bool,
     window_label: String,
     message: String,
@@ -28,12 +32,9 @@ pub async fn handle_hot_reload<R: Runtime>(
     let payload: HotReloadPayload = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for hot_reload: {}", e)))?;
 
-    let window_label = payload.window_label.unwrap_or_else(|| "main".to_string());
-
-    // Get the window by label
-    let window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
+    // Get the webview by label
+    let window = resolve_webview(app, payload.window_label.as_deref(), payload.webview_label.as_deref())?;
+    let window_label = window.label().to_string();
 
     // Get the current URL and reload by navigating to it
     // This is the safest cross-platform approach using Tauri's public API
@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+use tauri::ipc::{CallbackFn, InvokeBody, InvokeResponse, InvokeResponseBody};
+use tauri::utils::acl::capability::Capability;
+use tauri::utils::acl::manifest::Manifest;
+use tauri::utils::acl::resolved::{Resolved, ResolvedCommand};
+use tauri::utils::acl::{ExecutionContext, has_app_manifest};
+use tauri::utils::platform::Target;
+use tauri::webview::InvokeRequest;
+use tauri::{AppHandle, Runtime, Url};
+use tokio::sync::oneshot;
+use log::info;
+
+use crate::TauriMcpExt;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
+use crate::PluginConfig;
+
+/// What the plugin knows about the host application's commands.
+/// Invoke handlers cannot be introspected, so names and ACL come from the plugin configuration.
+#[derive(Default)]
+pub(crate) struct AppCommands {
+    names: BTreeSet<String>,
+    acl: Option<AppAcl>,
+}
+
+struct AppAcl {
+    resolved: Resolved,
+    // Without an app manifest Tauri only checks plugin commands
+    has_app_manifest: bool,
+}
+
+impl AppCommands {
+    pub(crate) fn from_config<R: Runtime>(config: &PluginConfig<R>) -> crate::Result<Self> {
+        let acl = match &config.acl {
+            Some((manifests, capabilities)) => {
+                let manifests: BTreeMap<String, Manifest> = serde_json::from_str(manifests)
+                    .map_err(|e| Error::serialization_error(format!("Invalid ACL manifests: {}", e)))?;
+                let capabilities: BTreeMap<String, Capability> = serde_json::from_str(capabilities)
+                    .map_err(|e| Error::serialization_error(format!("Invalid capabilities: {}", e)))?;
+                let resolved = Resolved::resolve(&manifests, capabilities, Target::current())
+                    .map_err(|e| Error::Anyhow { message: format!("Failed to resolve ACL: {}", e) })?;
+                info!(
+                    "[TAURI_MCP] Resolved ACL: {} allowed and {} denied command(s)",
+                    resolved.allowed_commands.len(),
+                    resolved.denied_commands.len()
+                );
+                Some(AppAcl {
+                    has_app_manifest: has_app_manifest(&manifests),
+                    resolved,
+                })
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            names: config.app_commands.iter().cloned().collect(),
+            acl,
+        })
+    }
+
+    /// Whether a webview may call the command, mirroring Tauri's check in `Webview::on_message`.
+    /// None when no ACL was configured.
+    fn is_allowed(&self, command: &str, window: &str, webview: &str, origin: Option<&Url>) -> Option<bool> {
+        let acl = self.acl.as_ref()?;
+        if !command.starts_with("plugin:") && !acl.has_app_manifest {
+            return Some(true);
+        }
+
+        let matches_origin = |cmd: &ResolvedCommand| match (&cmd.context, origin) {
+            (ExecutionContext::Local, None) => true,
+            (ExecutionContext::Remote { url: pattern }, Some(url)) => pattern.test(url),
+            _ => false,
+        };
+        let denied = acl
+            .resolved
+            .denied_commands
+            .get(command)
+            .is_some_and(|cmds| cmds.iter().any(&matches_origin));
+        if denied {
+            return Some(false);
+        }
+        Some(acl.resolved.allowed_commands.get(command).is_some_and(|cmds| {
+            cmds.iter().any(|cmd| {
+                matches_origin(cmd)
+                    && (cmd.webviews.iter().any(|w| w.matches(webview))
+                        || cmd.windows.iter().any(|w| w.matches(window)))
+            })
+        }))
+    }
+
+    /// Every command known from the declared names and the ACL
+    fn all(&self) -> BTreeSet<String> {
+        let mut all = self.names.clone();
+        if let Some(acl) = &self.acl {
+            all.extend(acl.resolved.allowed_commands.keys().cloned());
+            all.extend(acl.resolved.denied_commands.keys().cloned());
+        }
+        all
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct InvokeCommandRequest {
+    window_label: Option<String>,
+    /// Webview the command is invoked from, inside a multi-webview window
+    webview_label: Option<String>,
+    /// Command name, e.g. "greet" or "plugin:fs|read_text_file"
+    command: String,
+    /// Arguments as a JSON object, keyed like the JS `invoke` call (camelCase by default)
+    args: Option<Value>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct InvokeCommandResponse {
+    pub command: String,
+    /// Webview the command ran in
+    pub webview_label: String,
+    /// Value the command resolved with. Binary responses are base64 encoded.
+    pub result: Value,
+}
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct ListCommandsRequest {
+    window_label: Option<String>,
+    /// Webview to check, inside a multi-webview window
+    webview_label: Option<String>,
+    /// Only list commands containing this text
+    filter: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CommandAccess {
+    pub command: String,
+    /// True for commands provided by plugins ("plugin:<name>|<command>")
+    pub plugin: bool,
+    /// Whether the webview may call the command. Null when the app did not provide its ACL.
+    pub allowed: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ListCommandsResponse {
+    pub window_label: String,
+    pub webview_label: String,
+    /// "local" for the app's own pages, otherwise the remote URL the ACL was checked against
+    pub origin: String,
+    /// Whether the app provided its ACL, so `allowed` is known
+    pub acl_configured: bool,
+    pub commands: Vec<CommandAccess>,
+}
+
+/// Whether Tauri treats the page as the app's own, so local capabilities apply
+fn is_local_url<R: Runtime>(app: &AppHandle<R>, url: &Url) -> bool {
+    if url.scheme() == "tauri" || url.host_str() == Some("tauri.localhost") {
+        return true;
+    }
+    app.config()
+        .build
+        .dev_url
+        .as_ref()
+        .is_some_and(|dev_url| dev_url.origin() == url.origin())
+}
+
+/// Run one of the host application's commands through its invoke handler, as the webview would.
+/// Tauri applies the capability/ACL checks itself, so denied commands are rejected.
+pub async fn handle_invoke_command<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: InvokeCommandRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for invoke_command: {}", e)))?;
+
+    if request.command.is_empty() {
+        return Err(Error::invalid_parameter("command", "a command name", "\"\""));
+    }
+    let args = request.args.unwrap_or_else(|| json!({}));
+    if !args.is_object() {
+        return Err(Error::invalid_parameter("args", "a JSON object", args.to_string()));
+    }
+
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+    let webview_label = webview.label().to_string();
+    // The page URL decides which capabilities apply, exactly like a call from the page itself
+    let url = webview
+        .url()
+        .map_err(|e| Error::window_operation_failed("invoke_command", e.to_string()))?;
+
+    let invoke = InvokeRequest {
+        cmd: request.command.clone(),
+        callback: CallbackFn(0),
+        error: CallbackFn(1),
+        url,
+        body: InvokeBody::Json(args),
+        headers: Default::default(),
+        invoke_key: app.invoke_key().to_string(),
+    };
+
+    info!("[TAURI_MCP] Invoking command {} in {}", request.command, webview_label);
+
+    let (tx, rx) = oneshot::channel();
+    webview.on_message(
+        invoke,
+        Box::new(move |_webview, _cmd, response, _callback, _error| {
+            let _ = tx.send(response);
+        }),
+    );
+
+    let timeout_ms = request.timeout_ms.unwrap_or(10000);
+    let response = match tokio::time::timeout(Duration::from_millis(timeout_ms), rx).await {
+        Ok(Ok(response)) => response,
+        // The handler dropped the resolver without answering, e.g. no such command
+        Ok(Err(_)) => {
+            return Err(Error::window_operation_failed(
+                "invoke_command",
+                format!("command {} did not respond", request.command),
+            ));
+        }
+        Err(_) => return Err(Error::timeout_error("invoke_command", timeout_ms)),
+    };
+
+    match response {
+        InvokeResponse::Ok(body) => {
+            let result = match body {
+                InvokeResponseBody::Json(json) => serde_json::from_str(&json)
+                    .map_err(|e| Error::serialization_error(format!("Invalid command result: {}", e)))?,
+                InvokeResponseBody::Raw(bytes) => Value::String(base64::encode(bytes)),
+            };
+            let data = serde_json::to_value(InvokeCommandResponse {
+                command: request.command,
+                webview_label,
+                result,
+            })
+            .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+            Ok(SocketResponse {
+                success: true,
+                data: Some(data),
+                error: None,
+            })
+        }
+        InvokeResponse::Err(error) => {
+            let message = match &error.0 {
+                Value::String(message) => message.clone(),
+                other => other.to_string(),
+            };
+            info!("[TAURI_MCP] Command {} rejected: {}", request.command, message);
+
+            // The rejection value is what `invoke` would have thrown in the page
+            Ok(SocketResponse {
+                success: false,
+                data: Some(json!({ "command": request.command, "webview_label": webview_label, "error": error.0 })),
+                error: Some(message),
+            })
+        }
+    }
+}
+
+/// Report which commands a webview may call, according to the ACL the app provided
+pub fn handle_list_commands<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: ListCommandsRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for list_commands: {}", e)))?;
+
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+    let window_label = webview.window().label().to_string();
+    let url = webview
+        .url()
+        .map_err(|e| Error::window_operation_failed("list_commands", e.to_string()))?;
+    let remote = (!is_local_url(app, &url)).then_some(url);
+
+    let known = app.tauri_mcp().app_commands();
+    let commands: Vec<CommandAccess> = known
+        .all()
+        .into_iter()
+        .filter(|command| request.filter.as_deref().is_none_or(|filter| command.contains(filter)))
+        .map(|command| CommandAccess {
+            plugin: command.starts_with("plugin:"),
+            allowed: known.is_allowed(&command, &window_label, webview.label(), remote.as_ref()),
+            command,
+        })
+        .collect();
+
+    let data = serde_json::to_value(ListCommandsResponse {
+        window_label,
+        webview_label: webview.label().to_string(),
+        origin: remote.map_or_else(|| "local".to_string(), |url| url.to_string()),
+        acl_configured: known.acl.is_some(),
+        commands,
+    })
+    .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
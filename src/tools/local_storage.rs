@@ -1,13 +1,15 @@
 use serde::{Serialize, Serializer};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fmt;
-use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+use tauri::{AppHandle, Runtime};
 
+use crate::bridge;
 use crate::error::Error;
 use crate::models::LocalStorageRequest;
 use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
 
 // Define a custom error type for localStorage operations
 #[derive(Debug)]
@@ -40,22 +42,32 @@ impl Serialize for LocalStorageError {
     }
 }
 
-// Support conversion from timeout error
-impl From<mpsc::RecvTimeoutError> for LocalStorageError {
-    fn from(err: mpsc::RecvTimeoutError) -> Self {
-        LocalStorageError::Timeout(format!(
-            "Timeout waiting for localStorage response: {}",
-            err
-        ))
+// Support conversion from webview bridge errors
+impl From<Error> for LocalStorageError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::TimeoutError { .. } => LocalStorageError::Timeout(format!(
+                "Timeout waiting for localStorage response: {}",
+                err
+            )),
+            other => LocalStorageError::WebviewOperation(other.to_string()),
+        }
     }
 }
+// Response model: the window's localStorage entries, values parsed as JSON where possible
+#[derive(Debug, Default, Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(transparent)]
+pub struct LocalStorageResponse {
+    pub entries: BTreeMap<String, Value>,
+}
+
 // Handler function for the socket server
 pub async fn handle_get_local_storage<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
     // Parse params from payload
-    let params: LocalStorageRequest = serde_json::from_value(payload)
+    let mut params: LocalStorageRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for localStorage: {}", e)))?;
 
     // Validate input parameters
@@ -96,14 +108,9 @@ pub async fn handle_get_local_storage<R: Runtime>(
         }
     };
 
-    // Get the window
-    let window_label = params
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
-    let _window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
+    // Resolve the target webview, which the implementation then addresses by label
+    let webview = resolve_webview(app, params.window_label.as_deref(), params.webview_label.as_deref())?;
+    params.window_label = Some(webview.label().to_string());
 
     // Call the implementation function with cloned app handle and params
     let result = perform_local_storage_operation(app.clone(), params.clone()).await;
@@ -130,52 +137,39 @@ pub async fn handle_get_local_storage<R: Runtime>(
 async fn perform_local_storage_operation<R: Runtime>(
     app: AppHandle<R>,
     params: LocalStorageRequest,
-) -> Result<Value, LocalStorageError> {
+) -> Result<LocalStorageResponse, LocalStorageError> {
     // Get window label
     let window_label = params
         .window_label
         .clone()
         .unwrap_or_else(|| "main".to_string());
 
-    // Emit event to the window
-    app.emit_to(&window_label, "get-local-storage", &params)
-        .map_err(|e| LocalStorageError::WebviewOperation(format!("Failed to emit event to {}: {}", window_label, e)))?;
-
-    // Set up channel for response
-    let (tx, rx) = mpsc::channel();
-
-    // Listen for response
-    app.once("get-local-storage-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
-
-    // Wait for response with timeout
-    match rx.recv_timeout(Duration::from_secs(5)) {
-        Ok(result_string) => {
-            // Parse the response
-            let response: Value = serde_json::from_str(&result_string).map_err(|e| {
-                LocalStorageError::JavaScriptError(format!("Failed to parse response: {}", e))
-            })?;
-
-            // Check if result contains an error
-            if let Some(error) = response.get("error") {
-                if let Some(error_str) = error.as_str() {
-                    return Err(LocalStorageError::JavaScriptError(error_str.to_string()));
-                } else {
-                    return Err(LocalStorageError::JavaScriptError(
-                        "Unknown error".to_string(),
-                    ));
-                }
-            }
-
-            // Get data from response
-            if let Some(data) = response.get("data") {
-                Ok(data.clone())
-            } else {
-                Ok(Value::Null)
-            }
+    // Emit event to the window and wait for its response
+    let response = bridge::request(
+        &app,
+        &window_label,
+        "get-local-storage",
+        serde_json::to_value(params)
+            .map_err(|e| Error::serialization_error(format!("Failed to serialize request: {}", e)))?,
+        Duration::from_secs(5),
+    )
+    .await?;
+
+    // Check if result contains an error
+    if let Some(error) = response.get("error") {
+        if let Some(error_str) = error.as_str() {
+            return Err(LocalStorageError::JavaScriptError(error_str.to_string()));
+        } else {
+            return Err(LocalStorageError::JavaScriptError(
+                "Unknown error".to_string(),
+            ));
         }
-        Err(e) => Err(e.into()),
+    }
+
+    // Get data from response
+    match response.get("data") {
+        Some(data) if !data.is_null() => serde_json::from_value(data.clone())
+            .map_err(|e| LocalStorageError::JavaScriptError(format!("Invalid localStorage result: {}", e))),
+        _ => Ok(LocalStorageResponse::default()),
     }
 }
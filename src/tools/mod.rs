@@ -2,46 +2,72 @@ use serde_json::Value;
 use tauri::{AppHandle, Runtime};
 use log::info;
 
+use crate::TauriMcpExt;
 use crate::shared::commands;
 use crate::socket_server::SocketResponse;
 
 // Export command modules
+pub mod accessibility;
+pub mod annotated_screenshot;
 pub mod console_logs;
+pub mod describe_commands;
 pub mod devtools_bridge;
 pub mod error_tracker;
+pub mod events;
 pub mod execute_js;
+pub mod har;
 pub mod health_check;
 pub mod hot_reload;
+pub mod invoke_command;
 pub mod local_storage;
+pub mod mouse_action;
 pub mod mouse_movement;
 pub mod network_inspector;
+pub mod network_mock;
 pub mod performance;
 pub mod ping;
+pub mod press_keys;
+pub mod registry;
 pub mod state_dump;
 pub mod storage_inspector;
 pub mod take_screenshot;
 pub mod text_input;
+pub mod visual_compare;
+pub mod wait_for;
 pub mod webview;
 pub mod window_manager;
+pub mod windows;
 
 // Re-export command handler functions
+pub use accessibility::handle_get_accessibility_tree;
+pub use annotated_screenshot::handle_take_annotated_screenshot;
 pub use console_logs::{handle_get_console_logs, handle_inject_console_capture};
+pub use describe_commands::handle_describe_commands;
 pub use devtools_bridge::handle_devtools_bridge;
 pub use error_tracker::{handle_get_exceptions, handle_inject_error_tracker, handle_clear_exceptions};
+pub use events::{handle_emit_event, handle_listen_events};
 pub use execute_js::handle_execute_js;
 pub use health_check::handle_health_check;
 pub use hot_reload::handle_hot_reload;
+pub use invoke_command::{handle_invoke_command, handle_list_commands};
 pub use local_storage::handle_get_local_storage;
+pub use mouse_action::handle_mouse_action;
 pub use mouse_movement::handle_simulate_mouse_movement;
 pub use network_inspector::{handle_network_inspector, handle_inject_network_capture};
+pub use network_mock::handle_network_mock;
 pub use performance::handle_get_performance_metrics;
 pub use ping::handle_ping;
+pub use press_keys::handle_press_keys;
+pub use registry::{McpTool, ToolFuture, ToolRegistry};
 pub use state_dump::handle_state_dump;
 pub use storage_inspector::handle_get_storage_inspector;
 pub use take_screenshot::handle_take_screenshot;
 pub use text_input::handle_simulate_text_input;
+pub use visual_compare::handle_visual_compare;
+pub use wait_for::handle_wait_for;
 pub use webview::{handle_get_dom, handle_get_element_position, handle_send_text_to_element};
 pub use window_manager::handle_manage_window;
+pub use windows::handle_list_windows;
 
 /// Handle command routing for socket requests
 pub async fn handle_command<R: Runtime>(
@@ -57,31 +83,21 @@ pub async fn handle_command<R: Runtime>(
             .unwrap_or_else(|_| "[failed to serialize]".to_string())
     );
 
-    let result = match command {
-        commands::PING => handle_ping(app, payload),
-        commands::TAKE_SCREENSHOT => handle_take_screenshot(app, payload).await,
-        commands::GET_DOM => handle_get_dom(app, payload).await,
-        commands::MANAGE_LOCAL_STORAGE => handle_get_local_storage(app, payload).await,
-        commands::EXECUTE_JS => handle_execute_js(app, payload).await,
-        commands::MANAGE_WINDOW => handle_manage_window(app, payload).await,
-        commands::SIMULATE_TEXT_INPUT => handle_simulate_text_input(app, payload).await,
-        commands::SIMULATE_MOUSE_MOVEMENT => handle_simulate_mouse_movement(app, payload).await,
-        commands::GET_ELEMENT_POSITION => handle_get_element_position(app, payload).await,
-        commands::SEND_TEXT_TO_ELEMENT => handle_send_text_to_element(app, payload).await,
-        commands::HOT_RELOAD => handle_hot_reload(app, payload).await,
-        commands::GET_CONSOLE_LOGS => handle_get_console_logs(app, payload).await,
-        commands::INJECT_CONSOLE_CAPTURE => handle_inject_console_capture(app, payload).await,
-        commands::NETWORK_INSPECTOR => handle_network_inspector(app, payload).await,
-        commands::INJECT_NETWORK_CAPTURE => handle_inject_network_capture(app, payload).await,
-        commands::STATE_DUMP => handle_state_dump(app, payload).await,
-        commands::DEVTOOLS_BRIDGE => handle_devtools_bridge(app, payload).await,
-        commands::GET_EXCEPTIONS => handle_get_exceptions(app, payload).await,
-        commands::INJECT_ERROR_TRACKER => handle_inject_error_tracker(app, payload).await,
-        commands::CLEAR_EXCEPTIONS => handle_clear_exceptions(app, payload).await,
-        commands::GET_PERFORMANCE_METRICS => handle_get_performance_metrics(app, payload).await,
-        commands::STORAGE_INSPECTOR => handle_get_storage_inspector(app, payload).await,
-        commands::HEALTH_CHECK => handle_health_check(app, payload),
-        _ => Ok(SocketResponse {
+    // Dispatch through the registry so host-provided tools are served alongside built-ins
+    let result = match app.tauri_mcp().tools().get(command) {
+        Some(tool) => match tool.call(app.clone(), payload).await {
+            Ok(data) => Ok(SocketResponse {
+                success: true,
+                data: Some(data),
+                error: None,
+            }),
+            Err(e) => Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(e.to_string()),
+            }),
+        },
+        None => Ok(SocketResponse {
             success: false,
             data: None,
             error: Some(format!("Unknown command: {}", command)),
@@ -130,3 +146,54 @@ pub async fn handle_command<R: Runtime>(
 
     result
 }
+
+/// Route a built-in command to its handler
+pub(crate) async fn handle_builtin<R: Runtime>(
+    app: &AppHandle<R>,
+    command: &str,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    match command {
+        commands::PING => handle_ping(app, payload),
+        commands::TAKE_SCREENSHOT => handle_take_screenshot(app, payload).await,
+        commands::GET_DOM => handle_get_dom(app, payload).await,
+        commands::GET_ACCESSIBILITY_TREE => handle_get_accessibility_tree(app, payload).await,
+        commands::MANAGE_LOCAL_STORAGE => handle_get_local_storage(app, payload).await,
+        commands::EXECUTE_JS => handle_execute_js(app, payload).await,
+        commands::MANAGE_WINDOW => handle_manage_window(app, payload).await,
+        commands::SIMULATE_TEXT_INPUT => handle_simulate_text_input(app, payload).await,
+        commands::SIMULATE_MOUSE_MOVEMENT => handle_simulate_mouse_movement(app, payload).await,
+        commands::GET_ELEMENT_POSITION => handle_get_element_position(app, payload).await,
+        commands::SEND_TEXT_TO_ELEMENT => handle_send_text_to_element(app, payload).await,
+        commands::HOT_RELOAD => handle_hot_reload(app, payload).await,
+        commands::GET_CONSOLE_LOGS => handle_get_console_logs(app, payload),
+        commands::INJECT_CONSOLE_CAPTURE => handle_inject_console_capture(app, payload),
+        commands::NETWORK_INSPECTOR => handle_network_inspector(app, payload).await,
+        commands::INJECT_NETWORK_CAPTURE => handle_inject_network_capture(app, payload).await,
+        commands::NETWORK_MOCK => handle_network_mock(app, payload).await,
+        commands::STATE_DUMP => handle_state_dump(app, payload).await,
+        commands::DEVTOOLS_BRIDGE => handle_devtools_bridge(app, payload).await,
+        commands::GET_EXCEPTIONS => handle_get_exceptions(app, payload).await,
+        commands::INJECT_ERROR_TRACKER => handle_inject_error_tracker(app, payload).await,
+        commands::CLEAR_EXCEPTIONS => handle_clear_exceptions(app, payload).await,
+        commands::GET_PERFORMANCE_METRICS => handle_get_performance_metrics(app, payload).await,
+        commands::STORAGE_INSPECTOR => handle_get_storage_inspector(app, payload).await,
+        commands::HEALTH_CHECK => handle_health_check(app, payload),
+        commands::DESCRIBE_COMMANDS => handle_describe_commands(app, payload),
+        commands::LIST_WINDOWS => handle_list_windows(app, payload),
+        commands::INVOKE_COMMAND => handle_invoke_command(app, payload).await,
+        commands::LIST_COMMANDS => handle_list_commands(app, payload),
+        commands::EMIT_EVENT => handle_emit_event(app, payload),
+        commands::LISTEN_EVENTS => handle_listen_events(app, payload),
+        commands::WAIT_FOR => handle_wait_for(app, payload).await,
+        commands::PRESS_KEYS => handle_press_keys(app, payload).await,
+        commands::MOUSE_ACTION => handle_mouse_action(app, payload).await,
+        commands::VISUAL_COMPARE => handle_visual_compare(app, payload).await,
+        commands::TAKE_ANNOTATED_SCREENSHOT => handle_take_annotated_screenshot(app, payload).await,
+        _ => Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Unknown command: {}", command)),
+        }),
+    }
+}
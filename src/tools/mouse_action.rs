@@ -0,0 +1,304 @@
+use enigo::{Axis, Button, Coordinate, Direction, Enigo, Mouse, Settings};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime, Webview};
+use log::info;
+
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::mouse_movement::ScreenMapping;
+use crate::tools::webview::find_element_rect;
+use crate::tools::windows::resolve_webview;
+
+const ACTIONS: &str =
+    "\"click\", \"double_click\", \"triple_click\", \"down\", \"up\", \"drag\", \"scroll\" or \"hover\"";
+
+// Short enough for every OS to count consecutive clicks as one double or triple click
+const MULTI_CLICK_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct MouseActionRequest {
+    window_label: Option<String>,
+    /// Webview the coordinates and selectors refer to, inside a multi-webview window
+    webview_label: Option<String>,
+    /// "click", "double_click", "triple_click", "down", "up", "drag", "scroll" or "hover"
+    action: String,
+    /// Target in CSS pixels from the webview's top-left corner. Where the mouse is now if no target is given.
+    x: Option<f64>,
+    y: Option<f64>,
+    /// Target the center of this element instead of x/y. It is scrolled into view first.
+    selector_value: Option<String>,
+    /// "id", "class", "tag", "text", "xpath", "css" (default), "ref" (from get_accessibility_tree) or "mark" (from take_annotated_screenshot)
+    selector_type: Option<String>,
+    /// "left" (default), "right" or "middle"
+    button: Option<String>,
+    /// For "click": keep the button pressed this long before releasing it
+    hold_ms: Option<u64>,
+    /// For "drag": where to drop, in CSS pixels
+    to_x: Option<f64>,
+    to_y: Option<f64>,
+    /// For "drag": drop on the center of this element instead of to_x/to_y
+    to_selector_value: Option<String>,
+    to_selector_type: Option<String>,
+    /// For "drag" and "hover": intermediate mouse moves along the path (default 10)
+    steps: Option<u32>,
+    /// For "drag" and "hover": time the path takes (default 300)
+    duration_ms: Option<u64>,
+    /// For "scroll": wheel clicks, positive scrolls down
+    delta_y: Option<i32>,
+    /// For "scroll": wheel clicks, positive scrolls right
+    delta_x: Option<i32>,
+}
+
+/// A point in CSS pixels, relative to the webview
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CssPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MouseActionResponse {
+    pub action: String,
+    /// Where the action started, in CSS pixels. Null when it happened at the current mouse position.
+    pub start: Option<CssPoint>,
+    /// Where a drag ended, in CSS pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<CssPoint>,
+    /// Mouse position on screen afterwards, in physical pixels
+    pub position: (i32, i32),
+    pub duration_ms: u64,
+}
+
+/// Resolve a target given as coordinates or as a selector
+async fn resolve_point<R: Runtime>(
+    app: &AppHandle<R>,
+    webview: &Webview<R>,
+    (x, y): (Option<f64>, Option<f64>),
+    (selector_value, selector_type): (Option<&str>, Option<&str>),
+    (x_param, y_param): (&str, &str),
+) -> Result<Option<CssPoint>, Error> {
+    if let Some(selector_value) = selector_value {
+        let rect = find_element_rect(app, webview, selector_type, selector_value, "mouse_action").await?;
+        return Ok(Some(CssPoint {
+            x: rect.x + rect.width / 2.0,
+            y: rect.y + rect.height / 2.0,
+        }));
+    }
+
+    match (x, y) {
+        (Some(x), Some(y)) => Ok(Some(CssPoint { x, y })),
+        (None, None) => Ok(None),
+        (Some(_), None) => Err(Error::invalid_parameter(y_param, "a coordinate together with the other one", "nothing")),
+        (None, Some(_)) => Err(Error::invalid_parameter(x_param, "a coordinate together with the other one", "nothing")),
+    }
+}
+
+/// Move along a straight line in `steps` moves spread over `duration`
+fn move_along(enigo: &mut Enigo, from: (i32, i32), to: (i32, i32), steps: u32, duration: Duration) -> Result<(), Error> {
+    let pause = duration / steps;
+    for step in 1..=steps {
+        let progress = step as f64 / steps as f64;
+        let x = from.0 + ((to.0 - from.0) as f64 * progress).round() as i32;
+        let y = from.1 + ((to.1 - from.1) as f64 * progress).round() as i32;
+        enigo
+            .move_mouse(x, y, Coordinate::Abs)
+            .map_err(|e| Error::communication_error(format!("Failed to move mouse: {}", e)))?;
+        thread::sleep(pause);
+    }
+    Ok(())
+}
+
+fn button_event(enigo: &mut Enigo, button: Button, direction: Direction) -> Result<(), Error> {
+    enigo
+        .button(button, direction)
+        .map_err(|e| Error::communication_error(format!("Failed to {:?} mouse button: {}", direction, e)))
+}
+
+/// What to do once the points are on screen
+enum Plan {
+    Click { count: u32, hold: Duration },
+    Down,
+    Up,
+    Drag { to: (i32, i32) },
+    Scroll { delta_x: i32, delta_y: i32 },
+    Hover,
+}
+
+fn run_plan(
+    plan: Plan,
+    start: Option<(i32, i32)>,
+    button: Button,
+    steps: u32,
+    duration: Duration,
+) -> Result<(i32, i32), Error> {
+    let mut enigo = Enigo::new(&Settings::default())
+        .map_err(|e| Error::communication_error(format!("Failed to initialize Enigo: {}", e)))?;
+
+    let location = |enigo: &Enigo| {
+        enigo
+            .location()
+            .map_err(|e| Error::communication_error(format!("Failed to get mouse position: {}", e)))
+    };
+
+    match plan {
+        // A smooth path from wherever the mouse is now, so hover effects along the way fire
+        Plan::Hover => {
+            let from = location(&enigo)?;
+            move_along(&mut enigo, from, start.expect("hover has a target"), steps, duration)?;
+        }
+        _ => {
+            if let Some((x, y)) = start {
+                enigo
+                    .move_mouse(x, y, Coordinate::Abs)
+                    .map_err(|e| Error::communication_error(format!("Failed to move mouse: {}", e)))?;
+            }
+        }
+    }
+
+    match plan {
+        Plan::Click { count, hold } => {
+            for i in 0..count {
+                if i > 0 {
+                    thread::sleep(MULTI_CLICK_INTERVAL);
+                }
+                if hold.is_zero() {
+                    button_event(&mut enigo, button, Direction::Click)?;
+                } else {
+                    button_event(&mut enigo, button, Direction::Press)?;
+                    thread::sleep(hold);
+                    button_event(&mut enigo, button, Direction::Release)?;
+                }
+            }
+        }
+        Plan::Down => button_event(&mut enigo, button, Direction::Press)?,
+        Plan::Up => button_event(&mut enigo, button, Direction::Release)?,
+        Plan::Drag { to } => {
+            let from = start.expect("drag has a start");
+            button_event(&mut enigo, button, Direction::Press)?;
+            // Drag-and-drop libraries want to see the mouse moving with the button down
+            let moved = move_along(&mut enigo, from, to, steps, duration);
+            button_event(&mut enigo, button, Direction::Release)?;
+            moved?;
+        }
+        Plan::Scroll { delta_x, delta_y } => {
+            if delta_y != 0 {
+                enigo
+                    .scroll(delta_y, Axis::Vertical)
+                    .map_err(|e| Error::communication_error(format!("Failed to scroll: {}", e)))?;
+            }
+            if delta_x != 0 {
+                enigo
+                    .scroll(delta_x, Axis::Horizontal)
+                    .map_err(|e| Error::communication_error(format!("Failed to scroll: {}", e)))?;
+            }
+        }
+        Plan::Hover => {}
+    }
+
+    location(&enigo)
+}
+
+/// Click, drag, scroll or hover with the real mouse, at coordinates or on an element
+pub async fn handle_mouse_action<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: MouseActionRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for mouse_action: {}", e)))?;
+
+    let button = match request.button.as_deref().unwrap_or("left") {
+        "left" => Button::Left,
+        "right" => Button::Right,
+        "middle" => Button::Middle,
+        other => return Err(Error::invalid_parameter("button", "\"left\", \"right\" or \"middle\"", other)),
+    };
+    let steps = request.steps.unwrap_or(10).max(1);
+    let duration = Duration::from_millis(request.duration_ms.unwrap_or(300));
+
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+    let start = resolve_point(
+        app,
+        &webview,
+        (request.x, request.y),
+        (request.selector_value.as_deref(), request.selector_type.as_deref()),
+        ("x", "y"),
+    )
+    .await?;
+    let end = match request.action.as_str() {
+        "drag" => Some(
+            resolve_point(
+                app,
+                &webview,
+                (request.to_x, request.to_y),
+                (request.to_selector_value.as_deref(), request.to_selector_type.as_deref()),
+                ("to_x", "to_y"),
+            )
+            .await?
+            .ok_or_else(|| Error::invalid_parameter("to_x", "a drop target (to_x/to_y or to_selector_value)", "nothing"))?,
+        ),
+        _ => None,
+    };
+    // Resolved after the selectors, which may have scrolled the page but never move the window
+    let mapping = ScreenMapping::new(app, &webview)?;
+    let to_screen = |point: CssPoint| mapping.to_screen(point.x, point.y);
+
+    let needs_target = |param| Error::invalid_parameter(param, "x/y or selector_value for this action", "nothing");
+    let plan = match request.action.as_str() {
+        "click" => Plan::Click { count: 1, hold: Duration::from_millis(request.hold_ms.unwrap_or(0)) },
+        "double_click" => Plan::Click { count: 2, hold: Duration::ZERO },
+        "triple_click" => Plan::Click { count: 3, hold: Duration::ZERO },
+        "down" => Plan::Down,
+        "up" => Plan::Up,
+        "drag" => {
+            if start.is_none() {
+                return Err(needs_target("x"));
+            }
+            Plan::Drag { to: to_screen(end.expect("drag has an end")) }
+        }
+        "scroll" => {
+            let (delta_x, delta_y) = (request.delta_x.unwrap_or(0), request.delta_y.unwrap_or(0));
+            if delta_x == 0 && delta_y == 0 {
+                return Err(Error::invalid_parameter("delta_y", "a non-zero delta_x or delta_y", "0"));
+            }
+            Plan::Scroll { delta_x, delta_y }
+        }
+        "hover" => {
+            if start.is_none() {
+                return Err(needs_target("x"));
+            }
+            Plan::Hover
+        }
+        other => return Err(Error::invalid_parameter("action", ACTIONS, other)),
+    };
+
+    info!(
+        "[TAURI_MCP] Mouse {} in {} at {:?}",
+        request.action,
+        webview.label(),
+        start
+    );
+
+    let start_time = Instant::now();
+    let screen_start = start.map(to_screen);
+    let position = tokio::task::spawn_blocking(move || run_plan(plan, screen_start, button, steps, duration))
+        .await
+        .map_err(|e| Error::Anyhow { message: format!("Mouse simulation task failed: {}", e) })??;
+
+    let data = serde_json::to_value(MouseActionResponse {
+        action: request.action,
+        start,
+        end,
+        position,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+    })
+    .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
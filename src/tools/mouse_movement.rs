@@ -1,14 +1,54 @@
 use serde_json::Value;
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, PhysicalPosition, Runtime, Webview};
 
 use crate::error::Error;
 use crate::models::MouseMovementRequest;
 use crate::shared::{MouseMovementParams, MouseMovementResult};
 use crate::socket_server::SocketResponse;
+use crate::tools::windows::{resolve_webview, webview_offset};
 use enigo::{Button, Coordinate, Direction, Enigo, Mouse, Settings};
 use std::time::Instant;
 use log::info;
 
+/// Maps CSS pixel coordinates inside a webview to absolute screen coordinates
+pub(crate) struct ScreenMapping {
+    /// Screen position of the webview's top-left corner
+    origin: PhysicalPosition<i32>,
+    scale_factor: f64,
+}
+
+impl ScreenMapping {
+    pub(crate) fn new<R: Runtime>(app: &AppHandle<R>, webview: &Webview<R>) -> crate::Result<Self> {
+        let window = webview.window();
+        // Get window position (outer includes window borders/decorations)
+        let window_position = window
+            .outer_position()
+            .map_err(|e| Error::window_operation_failed("get window position", format!("{}", e)))?;
+        // Coordinates are relative to the webview, which may sit inside a larger window
+        let webview_offset = webview_offset(app, webview)?;
+        // Get window scale factor for high DPI screens
+        let scale_factor = window
+            .scale_factor()
+            .map_err(|e| Error::window_operation_failed("get window scale factor", format!("{}", e)))?;
+
+        Ok(Self {
+            origin: PhysicalPosition::new(window_position.x + webview_offset.x, window_position.y + webview_offset.y),
+            scale_factor,
+        })
+    }
+
+    pub(crate) fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    pub(crate) fn to_screen(&self, x: f64, y: f64) -> (i32, i32) {
+        (
+            (x * self.scale_factor) as i32 + self.origin.x,
+            (y * self.scale_factor) as i32 + self.origin.y,
+        )
+    }
+}
+
 pub async fn simulate_mouse_movement_async<R: Runtime>(
     app: &AppHandle<R>,
     params: MouseMovementRequest,
@@ -19,17 +59,12 @@ pub async fn simulate_mouse_movement_async<R: Runtime>(
     );
 
     // Get the window reference
-    let window = app
-        .get_webview_window("main")
-        .ok_or_else(|| Error::window_not_found("main"))?;
-
-    // Get window position (outer includes window borders/decorations)
-    let window_position = window
-        .outer_position()
-        .map_err(|e| Error::window_operation_failed("get window position", format!("{}", e)))?;
+    let webview = resolve_webview(app, params.window_label.as_deref(), params.webview_label.as_deref())?;
+    let window = webview.window();
+    let mapping = ScreenMapping::new(app, &webview)?;
     info!(
-        "[MOUSE_MOVEMENT] Window outer position: {:?}",
-        window_position
+        "[MOUSE_MOVEMENT] Webview origin on screen: {:?}",
+        mapping.origin
     );
 
     // Also get inner position for comparison
@@ -47,10 +82,7 @@ pub async fn simulate_mouse_movement_async<R: Runtime>(
         .map_err(|e| Error::window_operation_failed("get window size", format!("{}", e)))?;
     info!("[MOUSE_MOVEMENT] Window inner size: {:?}", window_size);
 
-    // Get window scale factor for high DPI screens
-    let scale_factor = window
-        .scale_factor()
-        .map_err(|e| Error::window_operation_failed("get window scale factor", format!("{}", e)))?;
+    let scale_factor = mapping.scale_factor();
     info!("[MOUSE_MOVEMENT] Window scale factor: {}", scale_factor);
 
     let x = params.x;
@@ -94,11 +126,10 @@ pub async fn simulate_mouse_movement_async<R: Runtime>(
             scale_factor, scaled_x, scaled_y
         );
 
-        let final_x = scaled_x + window_position.x;
-        let final_y = scaled_y + window_position.y;
+        let (final_x, final_y) = mapping.to_screen(x as f64, y as f64);
         info!(
-            "[MOUSE_MOVEMENT] 3. After adding window position ({}, {}): ({}, {})",
-            window_position.x, window_position.y, final_x, final_y
+            "[MOUSE_MOVEMENT] 3. After adding webview origin ({}, {}): ({}, {})",
+            mapping.origin.x, mapping.origin.y, final_x, final_y
         );
 
         // Calculate what it would be with inner position for comparison
@@ -177,6 +208,8 @@ pub fn simulate_mouse_movement_shared<R: Runtime>(
         relative: params.relative,
         click: params.click,
         button: params.button,
+        window_label: None,
+        webview_label: None,
     };
 
     // Run async method
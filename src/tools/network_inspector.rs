@@ -1,12 +1,14 @@
 use serde::{Serialize, Serializer};
 use serde_json::Value;
 use std::fmt;
-use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+use tauri::{AppHandle, Emitter, Runtime};
 
+use crate::bridge;
 use crate::error::Error;
 use crate::socket_server::SocketResponse;
+use crate::tools::har;
+use crate::tools::windows::resolve_webview;
 
 // HTTP method enumeration
 #[allow(dead_code)]
@@ -49,6 +51,7 @@ impl HttpMethod {
 pub enum RequestType {
     Fetch,
     Xhr,
+    Ipc,
 }
 
 #[allow(dead_code)]
@@ -57,6 +60,7 @@ impl RequestType {
         match s.to_lowercase().as_str() {
             "fetch" => Some(RequestType::Fetch),
             "xhr" => Some(RequestType::Xhr),
+            "ipc" => Some(RequestType::Ipc),
             _ => None,
         }
     }
@@ -91,13 +95,16 @@ impl Serialize for NetworkInspectorError {
     }
 }
 
-// Support conversion from timeout error
-impl From<mpsc::RecvTimeoutError> for NetworkInspectorError {
-    fn from(err: mpsc::RecvTimeoutError) -> Self {
-        NetworkInspectorError::TimeoutError(format!(
-            "Timeout waiting for network inspector response: {}",
-            err
-        ))
+// Support conversion from webview bridge errors
+impl From<Error> for NetworkInspectorError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::TimeoutError { .. } => NetworkInspectorError::TimeoutError(format!(
+                "Timeout waiting for network inspector response: {}",
+                err
+            )),
+            other => NetworkInspectorError::WebviewOperation(other.to_string()),
+        }
     }
 }
 
@@ -110,12 +117,13 @@ pub struct HeadersMap {
 }
 
 // Single network request entry
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct NetworkRequest {
     pub id: String,
     pub url: String,
     pub method: String,
-    pub request_type: String, // "fetch" or "xhr"
+    /// "fetch", "xhr" or "ipc" (a Tauri `invoke` call)
+    pub request_type: String,
     pub status_code: Option<u16>,
     pub request_headers: std::collections::HashMap<String, String>,
     pub response_headers: std::collections::HashMap<String, String>,
@@ -125,37 +133,82 @@ pub struct NetworkRequest {
     pub start_time_ms: u64,
     pub end_time_ms: Option<u64>,
     pub duration_ms: Option<u64>,
+    /// Whether a `network_mock` rule answered, delayed or failed the request
+    #[serde(default)]
+    pub mocked: bool,
+    pub mock_rule_id: Option<String>,
+    /// Tauri command of an "ipc" request. Its arguments are in `request_body`, the result
+    /// (or the rejection, also copied to `error`) in `response_body`.
+    pub command: Option<String>,
 }
 
-// Request filter for querying
-#[derive(Debug, Clone, serde::Deserialize)]
+// Request filter for querying. Unset fields are left out when sent to the webview,
+// which treats every field that is present as a condition.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct NetworkRequestFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub min_duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub request_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub start_time_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub end_time_ms: Option<u64>,
+    /// Only mocked (true) or only real (false) requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mocked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<usize>,
 }
 
 // Request model for network inspection
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
 pub struct NetworkInspectorRequest {
     pub window_label: Option<String>,
-    pub action: String, // "get_requests", "clear_requests", "start_capture", "stop_capture"
+    /// Webview to use inside a multi-webview window
+    pub webview_label: Option<String>,
+    /// "get_requests", "clear_requests", "start_capture", "stop_capture", "get_capture_status",
+    /// "export_har" or "import_har"
+    pub action: String,
     pub filter: Option<NetworkRequestFilter>,
+    /// HAR file to write ("export_har", returned inline if omitted) or read ("import_har")
+    pub path: Option<String>,
+    /// HAR document to import instead of reading `path`
+    pub har: Option<Value>,
 }
 
 // Response model for network requests
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
 pub struct NetworkInspectorResponse {
     pub requests: Vec<NetworkRequest>,
     pub total_count: usize,
     pub returned_count: usize,
     pub capture_active: bool,
+    /// Capture state reported by the webview, for the capture control actions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<NetworkCaptureStatus>,
+}
+
+/// Capture state of a webview
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct NetworkCaptureStatus {
+    /// Whether requests are being recorded
+    pub capture_active: bool,
+    pub fetch_hooked: bool,
+    pub xhr_hooked: bool,
+    /// Requests currently kept
+    pub buffer_size: usize,
+    pub max_buffer_size: usize,
+    /// Requests evicted from the full buffer since it was last cleared
+    pub dropped_count: u64,
 }
 
 /// Handler function for network inspection
@@ -163,26 +216,27 @@ pub async fn handle_network_inspector<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
-    let request: NetworkInspectorRequest = serde_json::from_value(payload)
+    let mut request: NetworkInspectorRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for network inspector: {}", e)))?;
 
-    // Get the window label or use "main" as default
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
+    // Resolve the target webview, which the helpers then address by label
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+    request.window_label = Some(webview.label().to_string());
 
-    // Verify the window exists
-    let _window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
+    // HAR actions answer with their own response shape
+    match request.action.as_str() {
+        "export_har" => return har::handle_export_har(app, request).await,
+        "import_har" => return har::handle_import_har(app, request).await,
+        _ => {}
+    }
 
     // Handle different actions
     let result = match request.action.as_str() {
         "get_requests" => retrieve_network_requests(app.clone(), request).await,
-        "clear_requests" => clear_network_requests(app.clone(), request).await,
-        "start_capture" => start_network_capture(app.clone(), request).await,
-        "stop_capture" => stop_network_capture(app.clone(), request).await,
+        "clear_requests" => control_network_capture(app.clone(), request, "clear-network-requests").await,
+        "start_capture" => control_network_capture(app.clone(), request, "start-network-capture").await,
+        "stop_capture" => control_network_capture(app.clone(), request, "stop-network-capture").await,
+        "get_capture_status" => control_network_capture(app.clone(), request, "get-network-capture-status").await,
         _ => Err(NetworkInspectorError::ParseError(format!(
             "Unknown action: {}",
             request.action
@@ -209,34 +263,29 @@ pub async fn handle_network_inspector<R: Runtime>(
     }
 }
 
+// Request model for network capture injection
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+pub struct InjectionRequest {
+    window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    webview_label: Option<String>,
+}
+
 /// Inject the network capture script into the webview
 pub async fn handle_inject_network_capture<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
-    #[derive(serde::Deserialize)]
-    pub struct InjectionRequest {
-        window_label: Option<String>,
-    }
-
     let request: InjectionRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for injection: {}", e)))?;
 
-    let window_label = request
-        .window_label
-        .unwrap_or_else(|| "main".to_string());
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
 
-    // Verify the window exists
-    let window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
-
-    // Send injection event to the window
-    window
-        .emit("inject-network-capture", ())
+    // Send injection event to the webview
+    app.emit_to(webview.label(), "inject-network-capture", ())
         .map_err(|e| Error::communication_error_with_context(
             "Failed to emit injection event",
-            format!("window: {}, error: {}", window_label, e),
+            format!("window: {}, error: {}", webview.label(), e),
         ))?;
 
     Ok(SocketResponse {
@@ -247,7 +296,7 @@ pub async fn handle_inject_network_capture<R: Runtime>(
 }
 
 /// Helper function to retrieve network requests from the webview
-async fn retrieve_network_requests<R: Runtime>(
+pub(crate) async fn retrieve_network_requests<R: Runtime>(
     app: AppHandle<R>,
     request: NetworkInspectorRequest,
 ) -> Result<NetworkInspectorResponse, NetworkInspectorError> {
@@ -256,150 +305,234 @@ async fn retrieve_network_requests<R: Runtime>(
         .clone()
         .unwrap_or_else(|| "main".to_string());
 
-    let filter = request.filter.unwrap_or_else(|| NetworkRequestFilter {
-        url_pattern: None,
-        method: None,
-        status_code: None,
-        min_duration_ms: None,
-        max_duration_ms: None,
-        request_type: None,
-        start_time_ms: None,
-        end_time_ms: None,
-        limit: Some(100),
-    });
-
-    // Build the filter payload
-    let filter_payload = serde_json::json!({
-        "url_pattern": filter.url_pattern,
-        "method": filter.method,
-        "status_code": filter.status_code,
-        "min_duration_ms": filter.min_duration_ms,
-        "max_duration_ms": filter.max_duration_ms,
-        "request_type": filter.request_type,
-        "start_time_ms": filter.start_time_ms,
-        "end_time_ms": filter.end_time_ms,
-        "limit": filter.limit.unwrap_or(100),
-    });
-
-    // Emit event to retrieve network requests from webview
-    app.emit_to(&window_label, "get-network-requests", filter_payload)
-        .map_err(|e| NetworkInspectorError::WebviewOperation(format!("Failed to emit event: {}", e)))?;
-
-    // Set up channel for response
-    let (tx, rx) = mpsc::channel::<String>();
-
-    // Listen for response
-    app.once("get-network-requests-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
-
-    // Wait for response with timeout (15 seconds for potentially large responses)
-    match rx.recv_timeout(Duration::from_secs(15)) {
-        Ok(result_string) => {
-            // Parse the response
-            let response: Value = serde_json::from_str(&result_string)
-                .map_err(|e| NetworkInspectorError::ParseError(format!("Failed to parse response: {}", e)))?;
-
-            // Check if result contains an error
-            if let Some(error) = response.get("error") {
-                if let Some(error_str) = error.as_str() {
-                    return Err(NetworkInspectorError::WebviewOperation(error_str.to_string()));
-                }
-            }
-
-            // Extract requests array from response
-            let requests: Vec<NetworkRequest> = response
-                .get("requests")
-                .and_then(|r| serde_json::from_value(r.clone()).ok())
-                .unwrap_or_default();
-
-            let total_count = response
-                .get("total_count")
-                .and_then(|c| c.as_u64())
-                .map(|c| c as usize)
-                .unwrap_or(requests.len());
-
-            let returned_count = requests.len();
-
-            let capture_active = response
-                .get("capture_active")
-                .and_then(|c| c.as_bool())
-                .unwrap_or(false);
-
-            Ok(NetworkInspectorResponse {
-                requests,
-                total_count,
-                returned_count,
-                capture_active,
-            })
-        }
-        Err(e) => Err(e.into()),
+    let mut filter = request.filter.unwrap_or_default();
+    filter.limit.get_or_insert(100);
+    let filter_payload = serde_json::to_value(&filter)
+        .map_err(|e| NetworkInspectorError::ParseError(format!("Invalid filter: {}", e)))?;
+
+    // Emit event to the window and wait for its response
+    let response = bridge::request(
+        &app,
+        &window_label,
+        "get-network-requests",
+        filter_payload,
+        Duration::from_secs(15),
+    )
+    .await?;
+
+    let success = response.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !success {
+        let error = response
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error occurred");
+        return Err(NetworkInspectorError::WebviewOperation(error.to_string()));
     }
-}
 
-/// Helper function to clear network requests from the webview
-async fn clear_network_requests<R: Runtime>(
-    app: AppHandle<R>,
-    request: NetworkInspectorRequest,
-) -> Result<NetworkInspectorResponse, NetworkInspectorError> {
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
+    // Extract requests array from response
+    let requests: Vec<NetworkRequest> = response
+        .get("requests")
+        .and_then(|r| serde_json::from_value(r.clone()).ok())
+        .unwrap_or_default();
+
+    let total_count = response
+        .get("total_count")
+        .and_then(|c| c.as_u64())
+        .map(|c| c as usize)
+        .unwrap_or(requests.len());
 
-    // Emit event to clear network requests
-    app.emit_to(&window_label, "clear-network-requests", ())
-        .map_err(|e| NetworkInspectorError::WebviewOperation(format!("Failed to emit event: {}", e)))?;
+    let returned_count = requests.len();
+
+    let capture_active = response
+        .get("capture_active")
+        .and_then(|c| c.as_bool())
+        .unwrap_or(false);
 
     Ok(NetworkInspectorResponse {
-        requests: vec![],
-        total_count: 0,
-        returned_count: 0,
-        capture_active: true,
+        requests,
+        total_count,
+        returned_count,
+        capture_active,
+        status: None,
     })
 }
 
-/// Helper function to start network capture
-async fn start_network_capture<R: Runtime>(
+/// Helper function to start, stop or clear the capture in the webview, or read its status.
+/// The guest answers every control event with the state it ended up in.
+async fn control_network_capture<R: Runtime>(
     app: AppHandle<R>,
     request: NetworkInspectorRequest,
+    event: &str,
 ) -> Result<NetworkInspectorResponse, NetworkInspectorError> {
     let window_label = request
         .window_label
         .clone()
         .unwrap_or_else(|| "main".to_string());
 
-    // Emit event to start capture
-    app.emit_to(&window_label, "start-network-capture", ())
-        .map_err(|e| NetworkInspectorError::WebviewOperation(format!("Failed to emit event: {}", e)))?;
+    let response = bridge::request(&app, &window_label, event, Value::Null, Duration::from_secs(5)).await?;
+
+    let success = response.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !success {
+        let error = response
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error occurred");
+        return Err(NetworkInspectorError::WebviewOperation(error.to_string()));
+    }
+
+    let status: NetworkCaptureStatus = serde_json::from_value(response.get("data").cloned().unwrap_or(Value::Null))
+        .map_err(|e| NetworkInspectorError::ParseError(format!("Invalid capture status: {}", e)))?;
 
     Ok(NetworkInspectorResponse {
         requests: vec![],
-        total_count: 0,
+        total_count: status.buffer_size,
         returned_count: 0,
-        capture_active: true,
+        capture_active: status.capture_active,
+        status: Some(status),
     })
 }
 
-/// Helper function to stop network capture
-async fn stop_network_capture<R: Runtime>(
-    app: AppHandle<R>,
-    request: NetworkInspectorRequest,
-) -> Result<NetworkInspectorResponse, NetworkInspectorError> {
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tauri::Listener;
+    use tauri::test::MockRuntime;
+
+    /// App with a "main" webview that answers `get-network-requests` from `records` the way
+    /// guest-js does: every filter field present in the payload narrows the result.
+    /// Returns the payloads the page received.
+    pub(crate) fn app_with_records(records: Vec<Value>) -> (tauri::App<MockRuntime>, Arc<Mutex<Vec<Value>>>) {
+        let app = tauri::test::mock_builder()
+            .plugin(crate::init_with_config(
+                crate::PluginConfig::new("network-test".to_string()).start_socket_server(false),
+            ))
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        tauri::WebviewWindowBuilder::new(&app, "main", Default::default()).build().unwrap();
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let handle = app.handle().clone();
+        let seen = received.clone();
+        app.listen_any("get-network-requests", move |event| {
+            let filter: Value = serde_json::from_str(event.payload()).unwrap();
+            seen.lock().unwrap().push(filter.clone());
+
+            let conditions: Vec<(&String, &Value)> = filter
+                .as_object()
+                .unwrap()
+                .iter()
+                .filter(|(key, _)| *key != "requestId" && *key != "limit")
+                .collect();
+            let matching: Vec<Value> = records
+                .iter()
+                .filter(|record| conditions.iter().all(|(key, value)| record.get(key.as_str()) == Some(*value)))
+                .cloned()
+                .collect();
+            let response = serde_json::json!({
+                "requestId": filter["requestId"],
+                "success": true,
+                "total_count": matching.len(),
+                "requests": matching,
+                "capture_active": true,
+            });
+            let handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                handle.emit("get-network-requests-response", response).unwrap();
+            });
+        });
+
+        (app, received)
+    }
 
-    // Emit event to stop capture
-    app.emit_to(&window_label, "stop-network-capture", ())
-        .map_err(|e| NetworkInspectorError::WebviewOperation(format!("Failed to emit event: {}", e)))?;
+    pub(crate) fn record(id: &str, request_type: &str, mocked: bool) -> Value {
+        serde_json::json!({
+            "id": id,
+            "url": format!("https://example.com/{}", id),
+            "method": "GET",
+            "request_type": request_type,
+            "status_code": 200,
+            "request_headers": {},
+            "response_headers": { "content-type": "application/json" },
+            "response_body": "{}",
+            "start_time_ms": 1_700_000_000_000u64,
+            "end_time_ms": 1_700_000_000_020u64,
+            "duration_ms": 20,
+            "mocked": mocked,
+        })
+    }
 
-    Ok(NetworkInspectorResponse {
-        requests: vec![],
-        total_count: 0,
-        returned_count: 0,
-        capture_active: false,
-    })
+    #[test]
+    fn unset_filter_fields_are_not_sent() {
+        let filter = NetworkRequestFilter { limit: Some(100), ..Default::default() };
+        assert_eq!(serde_json::to_value(&filter).unwrap(), serde_json::json!({ "limit": 100 }));
+
+        let filter = NetworkRequestFilter { request_type: Some("ipc".to_string()), ..Default::default() };
+        assert_eq!(serde_json::to_value(&filter).unwrap(), serde_json::json!({ "request_type": "ipc" }));
+    }
+
+    #[tokio::test]
+    async fn get_requests_without_a_filter_returns_everything() {
+        let (app, received) = app_with_records(vec![record("a", "fetch", false), record("b", "xhr", false)]);
+
+        let response = handle_network_inspector(app.handle(), serde_json::json!({ "action": "get_requests" }))
+            .await
+            .unwrap();
+        assert_eq!(response.data.unwrap()["returned_count"], 2);
+
+        let payload = received.lock().unwrap()[0].clone();
+        assert!(payload.as_object().unwrap().values().all(|value| !value.is_null()));
+    }
+
+    #[tokio::test]
+    async fn mocked_requests_are_reported_as_mocked() {
+        let mut mocked = record("mocked", "fetch", true);
+        mocked["mock_rule_id"] = "mock_1".into();
+        let (app, _) = app_with_records(vec![record("real", "fetch", false), mocked]);
+
+        let all = handle_network_inspector(app.handle(), serde_json::json!({ "action": "get_requests" }))
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        let flagged: Vec<&Value> = all["requests"].as_array().unwrap().iter().filter(|r| r["mocked"] == true).collect();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0]["mock_rule_id"], "mock_1");
+
+        let only_mocked = handle_network_inspector(
+            app.handle(),
+            serde_json::json!({ "action": "get_requests", "filter": { "mocked": true } }),
+        )
+        .await
+        .unwrap()
+        .data
+        .unwrap();
+        assert_eq!(only_mocked["returned_count"], 1);
+        assert_eq!(only_mocked["requests"][0]["id"], "mocked");
+        assert_eq!(only_mocked["requests"][0]["mocked"], true);
+    }
+
+    #[tokio::test]
+    async fn request_type_ipc_alone_returns_the_invoke_calls() {
+        let mut invoke = record("greet", "ipc", false);
+        invoke["url"] = "ipc://localhost/greet".into();
+        invoke["command"] = "greet".into();
+        let (app, received) = app_with_records(vec![record("a", "fetch", false), invoke]);
+
+        let response = handle_network_inspector(
+            app.handle(),
+            serde_json::json!({ "action": "get_requests", "filter": { "request_type": "ipc" } }),
+        )
+        .await
+        .unwrap();
+        let data = response.data.unwrap();
+        assert_eq!(data["returned_count"], 1);
+        assert_eq!(data["requests"][0]["command"], "greet");
+        assert_eq!(data["requests"][0]["request_type"], "ipc");
+
+        let payload = received.lock().unwrap()[0].clone();
+        assert_eq!(payload["request_type"], "ipc");
+        assert_eq!(payload["limit"], 100);
+        assert_eq!(payload.as_object().unwrap().len(), 3, "only request_type, limit and requestId: {}", payload);
+    }
 }
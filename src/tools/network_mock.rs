@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::bridge;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::network_inspector::HttpMethod;
+use crate::tools::windows::resolve_webview;
+
+/// Source of rule IDs, unique across webviews for the life of the app
+static NEXT_RULE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Canned response returned instead of performing the request
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MockResponse {
+    /// HTTP status, 200-599 (default 200)
+    #[serde(default = "default_status")]
+    pub status: u16,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// "base64" when `body` holds base64 encoded bytes, for binary responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+/// Interception rule. The most recently added rule that matches a request is applied.
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NetworkMockRule {
+    /// Assigned by the plugin when the rule is added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Regex (case-insensitive) or substring the request URL must match
+    pub url_pattern: String,
+    /// Only match this HTTP method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// Regex (case-insensitive) or substring the request body must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_pattern: Option<String>,
+    /// Answer with this response instead of performing the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<MockResponse>,
+    /// Delay before answering (or before the real request is sent, if the rule has no response)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Fail the request like a network error with this message, e.g. "Failed to fetch"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail: Option<String>,
+    /// Requests the rule has been applied to
+    #[serde(default)]
+    pub hit_count: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct NetworkMockRequest {
+    window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    webview_label: Option<String>,
+    /// "add", "list", "remove" or "clear"
+    action: String,
+    /// Rule to install, for "add"
+    rule: Option<NetworkMockRule>,
+    /// Rule to remove, for "remove"
+    rule_id: Option<String>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NetworkMockResponse {
+    /// ID of the rule added by "add"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    /// Rules active in the webview after the action, oldest first
+    pub rules: Vec<NetworkMockRule>,
+}
+
+/// Check a rule before it is sent to the webview and give it an ID
+pub(crate) fn prepare_rule(mut rule: NetworkMockRule) -> Result<NetworkMockRule, Error> {
+    if rule.url_pattern.is_empty() {
+        return Err(Error::invalid_parameter("rule.url_pattern", "a non-empty pattern", "\"\""));
+    }
+    if let Some(method) = rule.method.as_deref() {
+        if HttpMethod::from_str(method).is_none() {
+            return Err(Error::invalid_parameter("rule.method", "an HTTP method such as GET or POST", method));
+        }
+        rule.method = Some(method.to_uppercase());
+    }
+    if let Some(response) = &rule.response
+        && !(200..=599).contains(&response.status)
+    {
+        return Err(Error::invalid_parameter(
+            "rule.response.status",
+            "200-599",
+            response.status.to_string(),
+        ));
+    }
+    if let Some(response) = &rule.response
+        && let Some(encoding) = response.encoding.as_deref()
+    {
+        if encoding != "base64" {
+            return Err(Error::invalid_parameter("rule.response.encoding", "\"base64\"", encoding));
+        }
+        if base64::decode(response.body.as_deref().unwrap_or_default()).is_err() {
+            return Err(Error::invalid_parameter(
+                "rule.response.body",
+                "base64 encoded bytes",
+                "invalid base64",
+            ));
+        }
+    }
+    match (&rule.response, &rule.fail, rule.latency_ms) {
+        (Some(_), Some(_), _) => {
+            return Err(Error::invalid_parameter(
+                "rule",
+                "either a response or a failure, not both",
+                "response and fail",
+            ));
+        }
+        (None, None, None) => {
+            return Err(Error::invalid_parameter(
+                "rule",
+                "a response, a failure or latency_ms",
+                "none of them",
+            ));
+        }
+        _ => {}
+    }
+
+    rule.id = Some(format!("mock_{}", NEXT_RULE_ID.fetch_add(1, Ordering::Relaxed)));
+    rule.hit_count = 0;
+    Ok(rule)
+}
+
+/// Send a mock action to a webview and return the rules active there afterwards
+pub(crate) async fn apply_mock_action<R: Runtime>(
+    app: &AppHandle<R>,
+    webview_label: &str,
+    payload: Value,
+    timeout: Duration,
+) -> Result<Vec<NetworkMockRule>, Error> {
+    let response = bridge::request(app, webview_label, "network-mock", payload, timeout).await?;
+
+    let success = response.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !success {
+        let error = response
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error occurred");
+        return Err(Error::window_operation_failed("network_mock", error));
+    }
+
+    response
+        .pointer("/data/rules")
+        .map(|rules| serde_json::from_value(rules.clone()))
+        .transpose()
+        .map_err(|e| Error::serialization_error(format!("Invalid network mock rules: {}", e)))
+        .map(Option::unwrap_or_default)
+}
+
+/// Install, list or remove network mock rules in a webview.
+/// Rules live in the page and are dropped when it reloads.
+pub async fn handle_network_mock<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: NetworkMockRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for network_mock: {}", e)))?;
+
+    let js_payload = match request.action.as_str() {
+        "add" => {
+            let rule = request
+                .rule
+                .ok_or_else(|| Error::invalid_parameter("rule", "a rule for \"add\"", "nothing"))?;
+            json!({ "action": "add", "rule": prepare_rule(rule)? })
+        }
+        "remove" => {
+            let rule_id = request
+                .rule_id
+                .ok_or_else(|| Error::invalid_parameter("rule_id", "a rule ID for \"remove\"", "nothing"))?;
+            json!({ "action": "remove", "ruleId": rule_id })
+        }
+        "list" | "clear" => json!({ "action": &request.action }),
+        other => {
+            return Err(Error::invalid_parameter(
+                "action",
+                "\"add\", \"list\", \"remove\" or \"clear\"",
+                other,
+            ));
+        }
+    };
+    let added_id = js_payload
+        .pointer("/rule/id")
+        .and_then(|id| id.as_str())
+        .map(str::to_string);
+
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+
+    let rules = apply_mock_action(
+        app,
+        webview.label(),
+        js_payload,
+        Duration::from_millis(request.timeout_ms.unwrap_or(5000)),
+    )
+    .await?;
+    info!(
+        "[TAURI_MCP] Network mock {} in {}, {} rule(s) active",
+        request.action,
+        webview.label(),
+        rules.len()
+    );
+
+    let data = serde_json::to_value(NetworkMockResponse {
+        rule_id: added_id,
+        rules,
+    })
+    .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
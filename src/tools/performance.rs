@@ -1,14 +1,19 @@
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tauri::{AppHandle, Runtime, Manager, Emitter, Listener};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Runtime};
 use log::info;
-use std::sync::mpsc;
 
+use crate::bridge;
 use crate::error::Error;
 use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
 pub struct PerformanceMetricsRequest {
     window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    webview_label: Option<String>,
     include_navigation: Option<bool>,
     include_resources: Option<bool>,
     include_user_timing: Option<bool>,
@@ -18,15 +23,186 @@ pub struct PerformanceMetricsRequest {
     timeout_ms: Option<u64>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
 pub struct ResourceFilter {
-    resource_type: Option<Vec<String>>, // "script", "stylesheet", "image", "fetch", "xmlhttprequest", etc.
+    /// "script", "stylesheet", "image", "fetch", "xmlhttprequest", etc.
+    resource_type: Option<Vec<String>>,
     min_duration_ms: Option<f64>,
     max_duration_ms: Option<f64>,
     #[allow(dead_code)]
     url_pattern: Option<String>,
 }
 
+/// Metrics collected in the page, with the reasons sections could not be read
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PerformanceMetricsResponse {
+    pub metrics: PerformanceMetrics,
+    pub collected_at_ms: u64,
+    pub errors: Vec<String>,
+}
+
+/// Sections are missing when excluded by the request or when the page has no such entries
+#[derive(Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PerformanceMetrics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub navigation_timing: Option<NavigationTiming>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub navigation_timing_v2: Option<NavigationTimingV2>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_timing: Option<ResourceTiming>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_timing: Option<UserTiming>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_usage: Option<MemoryUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_tasks: Option<LongTasks>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paint_timing: Option<Vec<PaintTiming>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub largest_contentful_paint: Option<LargestContentfulPaint>,
+}
+
+/// From `performance.timing`, in milliseconds relative to navigation start where not a span
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NavigationTiming {
+    pub dns_lookup_ms: f64,
+    pub tcp_connection_ms: f64,
+    pub request_time_ms: f64,
+    pub response_time_ms: f64,
+    pub dom_interactive_ms: f64,
+    pub dom_complete_ms: f64,
+    pub page_load_ms: f64,
+    pub unload_ms: f64,
+    pub redirect_ms: f64,
+    pub total_page_load_ms: f64,
+    pub time_to_interactive_ms: f64,
+    pub first_paint_ms: Option<f64>,
+}
+
+/// From the `navigation` PerformanceNavigationTiming entry
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NavigationTimingV2 {
+    pub transfer_size: u64,
+    pub encoded_body_size: u64,
+    pub decoded_body_size: u64,
+    pub server_timing: Vec<ServerTiming>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ServerTiming {
+    pub name: String,
+    pub duration: f64,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ResourceTiming {
+    /// Resources grouped by initiator type
+    pub by_type: BTreeMap<String, Vec<ResourceEntry>>,
+    pub summary: ResourceSummary,
+    /// The first 100 resources
+    pub resources: Vec<ResourceEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ResourceEntry {
+    pub name: String,
+    /// Initiator type, e.g. "script", "img" or "fetch"
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    pub start_time_ms: f64,
+    pub duration_ms: f64,
+    pub transfer_size: u64,
+    pub encoded_body_size: u64,
+    pub decoded_body_size: u64,
+    /// "cached" or "network"
+    pub cache_behavior: String,
+    pub dns_lookup_ms: f64,
+    pub tcp_connection_ms: f64,
+    pub request_time_ms: f64,
+    pub response_time_ms: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ResourceSummary {
+    pub total_resources: usize,
+    pub total_duration_ms: f64,
+    pub largest_transfer_size_bytes: u64,
+    pub cached_resources: usize,
+    pub network_resources: usize,
+}
+
+/// The first 100 marks and measures
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct UserTiming {
+    pub marks: Vec<UserTimingEntry>,
+    pub measures: Vec<UserTimingEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct UserTimingEntry {
+    pub name: String,
+    pub start_time_ms: f64,
+    pub duration_ms: f64,
+    #[serde(default)]
+    pub detail: Value,
+}
+
+/// From `performance.memory`. Only `available` and `reason` are set when the API is missing.
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MemoryUsage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub js_heap_size_limit_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_js_heap_size_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_js_heap_size_bytes: Option<u64>,
+    /// Percentage with two decimals, as a string
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heap_usage_percent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_bytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Tasks longer than 50 ms, the first 50 of them
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LongTasks {
+    pub count: usize,
+    pub tasks: Vec<LongTask>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LongTask {
+    pub start_time_ms: f64,
+    pub duration_ms: f64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PaintTiming {
+    /// "first-paint" or "first-contentful-paint"
+    pub name: String,
+    pub start_time_ms: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LargestContentfulPaint {
+    pub name: String,
+    pub start_time_ms: f64,
+    pub render_time_ms: f64,
+    pub load_time_ms: f64,
+    pub size: u64,
+    pub url: Option<String>,
+    /// Tag name of the element
+    pub element: Option<String>,
+}
+
 pub async fn handle_get_performance_metrics<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
@@ -34,16 +210,7 @@ pub async fn handle_get_performance_metrics<R: Runtime>(
     let request: PerformanceMetricsRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for performance metrics: {}", e)))?;
 
-    // Get the window label or use "main" as default
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
-
-    // Verify the window exists
-    let _window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
 
     // Generate the introspection JavaScript code
     let js_code = generate_performance_metrics_code(
@@ -55,30 +222,16 @@ pub async fn handle_get_performance_metrics<R: Runtime>(
         request.resource_filter.clone(),
     );
 
-    // Execute the JavaScript in the window
-    let (tx, rx) = mpsc::channel();
-
-    app.emit_to(&window_label, "execute-js", &js_code)
-        .map_err(|e| Error::communication_error_with_context(
-            "Failed to emit execute-js event",
-            format!("window: {}, error: {}", window_label, e),
-        ))?;
-
-    // Listen for response
-    app.once("execute-js-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
-
-    // Wait for the response with timeout
+    // Execute the JavaScript in the window and wait for its response
     let timeout = std::time::Duration::from_millis(request.timeout_ms.unwrap_or(10000));
-    let result_string = rx
-        .recv_timeout(timeout)
-        .map_err(|_| Error::timeout_error("performance metrics execution", request.timeout_ms.unwrap_or(10000)))?;
-
-    // Parse the response
-    let response_value: Value = serde_json::from_str(&result_string)
-        .map_err(|e| Error::serialization_error(format!("Failed to parse performance metrics response: {}", e)))?;
+    let response_value = bridge::request(
+        app,
+        webview.label(),
+        "execute-js",
+        json!({ "code": js_code }),
+        timeout,
+    )
+    .await?;
 
     // Check if result contains an error
     if let Some(error) = response_value.get("error") {
@@ -95,7 +248,7 @@ pub async fn handle_get_performance_metrics<R: Runtime>(
     if let Some(result) = response_value.get("result") {
         if let Some(result_str) = result.as_str() {
             // Parse the metrics result
-            match serde_json::from_str::<Value>(result_str) {
+            match serde_json::from_str::<PerformanceMetricsResponse>(result_str) {
                 Ok(metrics) => {
                     let data = serde_json::to_value(metrics)
                         .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
@@ -111,12 +264,9 @@ pub async fn handle_get_performance_metrics<R: Runtime>(
                 Err(e) => {
                     info!("[TAURI_MCP] Failed to parse performance metrics result: {}", e);
                     Ok(SocketResponse {
-                        success: true,
-                        data: Some(json!({
-                            "raw_result": result_str,
-                            "parse_error": e.to_string()
-                        })),
-                        error: None,
+                        success: false,
+                        data: None,
+                        error: Some(format!("Invalid performance metrics result: {}", e)),
                     })
                 }
             }
@@ -532,3 +682,51 @@ fn generate_performance_metrics_code(
 
     code
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_results_parse_into_the_response_type() {
+        // Shaped like the object built by generate_performance_metrics_code
+        let result = json!({
+            "metrics": {
+                "navigation_timing": {
+                    "dns_lookup_ms": 0.0, "tcp_connection_ms": 0.0, "request_time_ms": 2.0, "response_time_ms": 1.0,
+                    "dom_interactive_ms": 120.0, "dom_complete_ms": 180.0,
+                    // loadEventEnd is still 0 while the page loads
+                    "page_load_ms": -1700000000000.0, "unload_ms": 0.0, "redirect_ms": 0.0,
+                    "total_page_load_ms": -1700000000000.0, "time_to_interactive_ms": 120.0, "first_paint_ms": null
+                },
+                "resource_timing": {
+                    "by_type": { "script": [{
+                        "name": "http://localhost/app.js", "type": "script", "start_time_ms": 10.5, "duration_ms": 3.2,
+                        "transfer_size": 0, "encoded_body_size": 100, "decoded_body_size": 100, "cache_behavior": "cached",
+                        "dns_lookup_ms": 0.0, "tcp_connection_ms": 0.0, "request_time_ms": 1.1, "response_time_ms": 0.4
+                    }] },
+                    "summary": {
+                        "total_resources": 1, "total_duration_ms": 3.2, "largest_transfer_size_bytes": 0,
+                        "cached_resources": 1, "network_resources": 0
+                    },
+                    "resources": []
+                },
+                "user_timing": {
+                    "marks": [{ "name": "ready", "start_time_ms": 90.0, "duration_ms": 0.0, "detail": null }],
+                    "measures": []
+                },
+                "memory_usage": { "available": false, "reason": "performance.memory API not available" },
+                "paint_timing": [{ "name": "first-paint", "start_time_ms": 42.1 }]
+            },
+            "collected_at_ms": 1718000000000u64,
+            "errors": []
+        });
+
+        let response: PerformanceMetricsResponse = serde_json::from_value(result.clone()).unwrap();
+        assert!(response.metrics.long_tasks.is_none());
+        assert_eq!(response.metrics.memory_usage.as_ref().unwrap().available, Some(false));
+
+        // Sections the page left out stay out
+        assert_eq!(serde_json::to_value(&response).unwrap(), result);
+    }
+}
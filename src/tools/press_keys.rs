@@ -0,0 +1,438 @@
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::bridge;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
+
+/// A key, named like the DOM's `KeyboardEvent.key`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NamedKey {
+    Control,
+    Shift,
+    Alt,
+    Meta,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Space,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    CapsLock,
+    Function(u8),
+    Char(char),
+}
+
+impl NamedKey {
+    fn parse(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Some(NamedKey::Char(c));
+        }
+
+        let lower = name.to_ascii_lowercase();
+        Some(match lower.as_str() {
+            "control" | "ctrl" => NamedKey::Control,
+            "shift" => NamedKey::Shift,
+            "alt" | "option" => NamedKey::Alt,
+            "meta" | "cmd" | "command" | "super" | "win" | "windows" => NamedKey::Meta,
+            // Tauri's accelerator convention: Command on macOS, Control elsewhere
+            "cmdorctrl" | "cmdorcontrol" | "commandorcontrol" => {
+                if cfg!(target_os = "macos") {
+                    NamedKey::Meta
+                } else {
+                    NamedKey::Control
+                }
+            }
+            "enter" | "return" => NamedKey::Enter,
+            "escape" | "esc" => NamedKey::Escape,
+            "tab" => NamedKey::Tab,
+            "backspace" => NamedKey::Backspace,
+            "delete" | "del" => NamedKey::Delete,
+            "space" | "spacebar" => NamedKey::Space,
+            "arrowup" | "up" => NamedKey::ArrowUp,
+            "arrowdown" | "down" => NamedKey::ArrowDown,
+            "arrowleft" | "left" => NamedKey::ArrowLeft,
+            "arrowright" | "right" => NamedKey::ArrowRight,
+            "home" => NamedKey::Home,
+            "end" => NamedKey::End,
+            "pageup" | "pgup" => NamedKey::PageUp,
+            "pagedown" | "pgdn" => NamedKey::PageDown,
+            "insert" | "ins" => NamedKey::Insert,
+            "capslock" => NamedKey::CapsLock,
+            "plus" => NamedKey::Char('+'),
+            _ => {
+                let number = lower.strip_prefix('f')?.parse::<u8>().ok()?;
+                if !(1..=20).contains(&number) {
+                    return None;
+                }
+                NamedKey::Function(number)
+            }
+        })
+    }
+
+    fn is_modifier(self) -> bool {
+        matches!(self, NamedKey::Control | NamedKey::Shift | NamedKey::Alt | NamedKey::Meta)
+    }
+
+    /// `KeyboardEvent.key`
+    fn dom_key(self) -> String {
+        match self {
+            NamedKey::Space => " ".to_string(),
+            NamedKey::Function(n) => format!("F{}", n),
+            NamedKey::Char(c) => c.to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+
+    /// `KeyboardEvent.code`, for the US layout
+    fn dom_code(self) -> String {
+        match self {
+            NamedKey::Control | NamedKey::Shift | NamedKey::Alt | NamedKey::Meta => format!("{:?}Left", self),
+            NamedKey::Function(n) => format!("F{}", n),
+            NamedKey::Char(c) if c.is_ascii_alphabetic() => format!("Key{}", c.to_ascii_uppercase()),
+            NamedKey::Char(c) if c.is_ascii_digit() => format!("Digit{}", c),
+            NamedKey::Char(_) => String::new(),
+            other => format!("{:?}", other),
+        }
+    }
+
+    fn enigo_key(self, with_modifiers: bool) -> Result<Key, Error> {
+        Ok(match self {
+            NamedKey::Control => Key::Control,
+            NamedKey::Shift => Key::Shift,
+            NamedKey::Alt => Key::Alt,
+            NamedKey::Meta => Key::Meta,
+            NamedKey::Enter => Key::Return,
+            NamedKey::Escape => Key::Escape,
+            NamedKey::Tab => Key::Tab,
+            NamedKey::Backspace => Key::Backspace,
+            NamedKey::Delete => Key::Delete,
+            NamedKey::Space => Key::Space,
+            NamedKey::ArrowUp => Key::UpArrow,
+            NamedKey::ArrowDown => Key::DownArrow,
+            NamedKey::ArrowLeft => Key::LeftArrow,
+            NamedKey::ArrowRight => Key::RightArrow,
+            NamedKey::Home => Key::Home,
+            NamedKey::End => Key::End,
+            NamedKey::PageUp => Key::PageUp,
+            NamedKey::PageDown => Key::PageDown,
+            #[cfg(not(target_os = "macos"))]
+            NamedKey::Insert => Key::Insert,
+            #[cfg(target_os = "macos")]
+            NamedKey::Insert => {
+                return Err(Error::invalid_parameter("keys", "a key macOS keyboards have", "Insert"));
+            }
+            NamedKey::CapsLock => Key::CapsLock,
+            NamedKey::Function(n) => FUNCTION_KEYS[n as usize - 1],
+            // In a chord the modifiers say whether Shift is down, so "Control+Shift+P" presses p
+            NamedKey::Char(c) if with_modifiers => Key::Unicode(c.to_ascii_lowercase()),
+            NamedKey::Char(c) => Key::Unicode(c),
+        })
+    }
+}
+
+const FUNCTION_KEYS: [Key; 20] = [
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10,
+    Key::F11, Key::F12, Key::F13, Key::F14, Key::F15, Key::F16, Key::F17, Key::F18, Key::F19, Key::F20,
+];
+
+/// Split "Control+Shift+P" into its keys. A literal plus is written "+" on its own or as "Control++".
+fn parse_chord(chord: &str) -> Result<Vec<NamedKey>, Error> {
+    let invalid = || {
+        Error::invalid_parameter(
+            "keys",
+            "chords like \"Enter\", \"Control+S\" or \"CmdOrCtrl+Shift+P\"",
+            chord,
+        )
+    };
+
+    let (modifiers, last) = if chord == "+" {
+        ("", "+")
+    } else if let Some(modifiers) = chord.strip_suffix("++") {
+        (modifiers, "+")
+    } else {
+        chord.rsplit_once('+').unwrap_or(("", chord))
+    };
+    if last.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut keys = Vec::new();
+    for name in modifiers.split('+').filter(|name| !modifiers.is_empty() || !name.is_empty()) {
+        keys.push(NamedKey::parse(name).ok_or_else(invalid)?);
+    }
+    keys.push(NamedKey::parse(last).ok_or_else(invalid)?);
+    Ok(keys)
+}
+
+/// One entry of `keys`: a chord string, or a chord with its own action and timing
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum KeyStepRequest {
+    Chord(String),
+    Step {
+        /// e.g. "Control+Shift+P", "Enter" or "a"
+        chord: String,
+        /// "press" (default) presses and releases, "down" holds the keys, "up" releases them
+        action: Option<String>,
+        /// Press the chord's last key this many times while the modifiers are held (default 1)
+        repeat: Option<u32>,
+        /// Pause after this step, overriding the request's `delay_ms`
+        delay_ms: Option<u64>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum KeyAction {
+    Press,
+    Down,
+    Up,
+}
+
+struct KeyStep {
+    keys: Vec<NamedKey>,
+    action: KeyAction,
+    repeat: u32,
+    delay: Duration,
+}
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct PressKeysRequest {
+    window_label: Option<String>,
+    /// Webview to send DOM key events to, inside a multi-webview window
+    webview_label: Option<String>,
+    /// Chords pressed one after another, e.g. ["Control+Shift+P", "Enter"]
+    keys: Vec<KeyStepRequest>,
+    /// "os" (default) sends real key presses to the focused window; "dom" dispatches
+    /// KeyboardEvents in the webview, which works without OS focus or a display
+    mode: Option<String>,
+    /// Pause after each step and between repeats, default 50
+    delay_ms: Option<u64>,
+    /// Pause after focusing the window before the first key, for "os" (default 200)
+    initial_delay_ms: Option<u64>,
+    /// For "dom": focus this element first. Keys go to the focused element otherwise.
+    selector_value: Option<String>,
+    /// "id", "class", "tag", "text", "xpath", "css" (default), "ref" (from get_accessibility_tree) or "mark" (from take_annotated_screenshot)
+    selector_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PressKeysResponse {
+    pub mode: String,
+    /// Key down events sent
+    pub keys_pressed: u32,
+    pub duration_ms: u64,
+    /// Keys left down by "down" steps without a matching "up"
+    pub held_keys: Vec<String>,
+}
+
+fn parse_steps(request: &PressKeysRequest) -> Result<Vec<KeyStep>, Error> {
+    if request.keys.is_empty() {
+        return Err(Error::invalid_parameter("keys", "at least one chord", "[]"));
+    }
+    let default_delay = request.delay_ms.unwrap_or(50);
+
+    request
+        .keys
+        .iter()
+        .map(|step| {
+            let (chord, action, repeat, delay_ms) = match step {
+                KeyStepRequest::Chord(chord) => (chord, None, None, None),
+                KeyStepRequest::Step { chord, action, repeat, delay_ms } => {
+                    (chord, action.as_deref(), *repeat, *delay_ms)
+                }
+            };
+            let action = match action.unwrap_or("press") {
+                "press" => KeyAction::Press,
+                "down" => KeyAction::Down,
+                "up" => KeyAction::Up,
+                other => return Err(Error::invalid_parameter("action", "\"press\", \"down\" or \"up\"", other)),
+            };
+            Ok(KeyStep {
+                keys: parse_chord(chord)?,
+                action,
+                repeat: repeat.unwrap_or(1).max(1),
+                delay: Duration::from_millis(delay_ms.unwrap_or(default_delay)),
+            })
+        })
+        .collect()
+}
+
+/// Press the steps with enigo. Keys still down when a step fails are released,
+/// so a failed shortcut does not leave a modifier stuck.
+fn run_os_steps(steps: &[KeyStep]) -> Result<(u32, Vec<String>), Error> {
+    let mut enigo = Enigo::new(&Settings::default())
+        .map_err(|e| Error::communication_error(format!("Failed to initialize Enigo: {}", e)))?;
+    let mut held: Vec<NamedKey> = Vec::new();
+    let mut pressed = 0;
+
+    let result = (|| {
+        for step in steps {
+            let with_modifiers = step.keys.iter().any(|key| key.is_modifier());
+            let key_event = |enigo: &mut Enigo, key: NamedKey, direction| {
+                enigo
+                    .key(key.enigo_key(with_modifiers)?, direction)
+                    .map_err(|e| Error::communication_error(format!("Failed to press {}: {}", key.dom_key(), e)))
+            };
+
+            match step.action {
+                KeyAction::Press => {
+                    let (last, modifiers) = step.keys.split_last().expect("chords have a key");
+                    for key in modifiers {
+                        key_event(&mut enigo, *key, Direction::Press)?;
+                        held.push(*key);
+                        pressed += 1;
+                    }
+                    for i in 0..step.repeat {
+                        if i > 0 {
+                            thread::sleep(step.delay);
+                        }
+                        key_event(&mut enigo, *last, Direction::Click)?;
+                        pressed += 1;
+                    }
+                    for key in modifiers.iter().rev() {
+                        key_event(&mut enigo, *key, Direction::Release)?;
+                        held.retain(|held| held != key);
+                    }
+                }
+                KeyAction::Down => {
+                    for key in &step.keys {
+                        key_event(&mut enigo, *key, Direction::Press)?;
+                        if !held.contains(key) {
+                            held.push(*key);
+                        }
+                        pressed += 1;
+                    }
+                }
+                KeyAction::Up => {
+                    for key in step.keys.iter().rev() {
+                        key_event(&mut enigo, *key, Direction::Release)?;
+                        held.retain(|held| held != key);
+                    }
+                }
+            }
+            thread::sleep(step.delay);
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        for key in held.iter().rev() {
+            if let Ok(enigo_key) = key.enigo_key(true) {
+                let _ = enigo.key(enigo_key, Direction::Release);
+            }
+        }
+        return Err(e);
+    }
+    Ok((pressed, held.iter().map(|key| key.dom_key()).collect()))
+}
+
+/// Press keys and shortcuts, as real OS key presses or as DOM events in the webview
+pub async fn handle_press_keys<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: PressKeysRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for press_keys: {}", e)))?;
+
+    let steps = parse_steps(&request)?;
+    let mode = request.mode.clone().unwrap_or_else(|| "os".to_string());
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+
+    info!(
+        "[TAURI_MCP] Pressing {} key step(s) in {} ({} mode)",
+        steps.len(),
+        webview.label(),
+        mode
+    );
+
+    let start_time = Instant::now();
+    let (keys_pressed, held_keys) = match mode.as_str() {
+        "os" => {
+            // Keystrokes go to the focused window, so bring the target forward first
+            webview.window().set_focus()?;
+            let initial_delay = Duration::from_millis(request.initial_delay_ms.unwrap_or(200));
+            tokio::time::sleep(initial_delay).await;
+
+            tokio::task::spawn_blocking(move || run_os_steps(&steps))
+                .await
+                .map_err(|e| Error::Anyhow { message: format!("Key simulation task failed: {}", e) })??
+        }
+        "dom" => {
+            let total_delay: Duration = steps.iter().map(|step| step.delay * step.repeat).sum();
+            let js_steps: Vec<Value> = steps
+                .iter()
+                .map(|step| {
+                    json!({
+                        "keys": step.keys.iter().map(|key| json!({ "key": key.dom_key(), "code": key.dom_code() })).collect::<Vec<_>>(),
+                        "action": step.action,
+                        "repeat": step.repeat,
+                        "delayMs": step.delay.as_millis() as u64,
+                    })
+                })
+                .collect();
+            let js_payload = json!({
+                "steps": js_steps,
+                "selectorType": request.selector_type.as_deref().unwrap_or("css"),
+                "selectorValue": request.selector_value,
+            });
+
+            let response = bridge::request(
+                app,
+                webview.label(),
+                "press-keys",
+                js_payload,
+                total_delay + Duration::from_secs(5),
+            )
+            .await?;
+
+            let success = response.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+            if !success {
+                let error = response
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("Unknown error occurred");
+                return Err(Error::window_operation_failed("press_keys", error));
+            }
+
+            let keys_pressed = response.pointer("/data/keysPressed").and_then(Value::as_u64).unwrap_or(0) as u32;
+            let held_keys = response
+                .pointer("/data/heldKeys")
+                .and_then(|held| serde_json::from_value(held.clone()).ok())
+                .unwrap_or_default();
+            (keys_pressed, held_keys)
+        }
+        other => return Err(Error::invalid_parameter("mode", "\"os\" or \"dom\"", other)),
+    };
+
+    let data = serde_json::to_value(PressKeysResponse {
+        mode,
+        keys_pressed,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+        held_keys,
+    })
+    .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
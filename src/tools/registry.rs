@@ -0,0 +1,142 @@
+use futures::future::BoxFuture;
+use log::info;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime};
+
+use crate::shared::commands::{self, CommandInfo};
+
+/// Future returned by [`McpTool::call`]
+pub type ToolFuture = BoxFuture<'static, crate::Result<Value>>;
+
+/// A tool that can be invoked over the socket, both as a plugin command and as an MCP tool.
+///
+/// Built-in tools are implemented on top of this trait too; host applications add
+/// their own with [`PluginConfig::tool`](crate::PluginConfig::tool).
+///
+/// ```ignore
+/// struct ResetDatabase;
+///
+/// impl<R: Runtime> McpTool<R> for ResetDatabase {
+///     fn name(&self) -> &str { "reset_database" }
+///     fn description(&self) -> &str { "Drops all rows and reloads the fixtures" }
+///
+///     fn call(&self, app: AppHandle<R>, _arguments: Value) -> ToolFuture {
+///         Box::pin(async move {
+///             app.state::<Db>().reset().await?;
+///             Ok(json!({ "reset": true }))
+///         })
+///     }
+/// }
+/// ```
+pub trait McpTool<R: Runtime>: Send + Sync {
+    /// Unique name used as the socket command and MCP tool name
+    fn name(&self) -> &str;
+
+    /// Human readable description advertised to agents
+    fn description(&self) -> &str;
+
+    /// JSON schema of the arguments object
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    /// JSON schema of the value returned by [`McpTool::call`]. Unconstrained by default.
+    fn output_schema(&self) -> Value {
+        json!({})
+    }
+
+    /// Run the tool. Errors are reported to the caller as a failed response.
+    fn call(&self, app: AppHandle<R>, arguments: Value) -> ToolFuture;
+}
+
+/// Ordered set of tools served by the plugin, looked up by name
+pub struct ToolRegistry<R: Runtime> {
+    tools: Vec<Arc<dyn McpTool<R>>>,
+}
+
+impl<R: Runtime> Default for ToolRegistry<R> {
+    fn default() -> Self {
+        Self { tools: Vec::new() }
+    }
+}
+
+impl<R: Runtime> ToolRegistry<R> {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry holding all built-in tools
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        for info in commands::ALL {
+            registry.register(Arc::new(BuiltinTool { info }));
+        }
+        registry
+    }
+
+    /// Add a tool. A tool with the same name as an existing one replaces it.
+    pub fn register(&mut self, tool: Arc<dyn McpTool<R>>) {
+        if let Some(existing) = self.tools.iter_mut().find(|t| t.name() == tool.name()) {
+            info!("[TAURI_MCP] Replacing registered tool: {}", tool.name());
+            *existing = tool;
+        } else {
+            self.tools.push(tool);
+        }
+    }
+
+    /// Look up a tool by name
+    pub fn get(&self, name: &str) -> Option<Arc<dyn McpTool<R>>> {
+        self.tools.iter().find(|t| t.name() == name).cloned()
+    }
+
+    /// Iterate over the registered tools in registration order
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn McpTool<R>>> {
+        self.tools.iter()
+    }
+
+    /// Names of all registered tools
+    pub fn names(&self) -> Vec<String> {
+        self.tools.iter().map(|t| t.name().to_string()).collect()
+    }
+}
+
+// Adapter exposing one of the plugin's own command handlers as a tool
+struct BuiltinTool {
+    info: &'static CommandInfo,
+}
+
+impl<R: Runtime> McpTool<R> for BuiltinTool {
+    fn name(&self) -> &str {
+        self.info.name
+    }
+
+    fn description(&self) -> &str {
+        self.info.description
+    }
+
+    fn input_schema(&self) -> Value {
+        (self.info.input_schema)()
+    }
+
+    fn output_schema(&self) -> Value {
+        (self.info.output_schema)()
+    }
+
+    fn call(&self, app: AppHandle<R>, arguments: Value) -> ToolFuture {
+        let command = self.info.name;
+        Box::pin(async move {
+            let response = super::handle_builtin(&app, command, arguments).await?;
+            if response.success {
+                Ok(response.data.unwrap_or(Value::Null))
+            } else {
+                Err(crate::Error::Anyhow {
+                    message: response
+                        .error
+                        .unwrap_or_else(|| format!("Command {} failed", command)),
+                })
+            }
+        })
+    }
+}
@@ -1,12 +1,13 @@
 use serde::{Serialize, Serializer};
 use serde_json::Value;
 use std::fmt;
-use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+use tauri::{AppHandle, Runtime};
 
+use crate::bridge;
 use crate::error::Error;
 use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
 
 // Define a custom error type for storage inspector operations
 #[derive(Debug)]
@@ -39,13 +40,16 @@ impl Serialize for StorageInspectorError {
     }
 }
 
-// Support conversion from timeout error
-impl From<mpsc::RecvTimeoutError> for StorageInspectorError {
-    fn from(err: mpsc::RecvTimeoutError) -> Self {
-        StorageInspectorError::Timeout(format!(
-            "Timeout waiting for storage inspector response: {}",
-            err
-        ))
+// Support conversion from webview bridge errors
+impl From<Error> for StorageInspectorError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::TimeoutError { .. } => StorageInspectorError::Timeout(format!(
+                "Timeout waiting for storage inspector response: {}",
+                err
+            )),
+            other => StorageInspectorError::WebviewOperation(other.to_string()),
+        }
     }
 }
 
@@ -72,17 +76,17 @@ impl StorageType {
 }
 
 // Storage item (key-value pair)
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct StorageItem {
-    pub key: String,
+    /// Storage key, or the record's key path value (or index) for IndexedDB
+    pub key: Value,
+    /// Parsed as JSON where possible
     pub value: Value,
     pub size_bytes: usize,
 }
 
 // IndexedDB object store info
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct IndexedDBStore {
     pub name: String,
     pub key_path: Option<Value>,
@@ -92,18 +96,17 @@ pub struct IndexedDBStore {
 }
 
 // IndexedDB database info
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct IndexedDBDatabase {
     pub name: String,
-    pub version: u32,
+    pub version: u64,
     pub stores: Vec<IndexedDBStore>,
 }
 
 // Storage query result
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct StorageQueryResult {
+    /// "localStorage", "sessionStorage" or "indexedDB/<database>/<store>"
     pub storage_type: String,
     pub items: Vec<StorageItem>,
     pub total_items: usize,
@@ -114,26 +117,55 @@ pub struct StorageQueryResult {
 }
 
 // IndexedDB query result
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct IndexedDBQueryResult {
     pub databases: Vec<IndexedDBDatabase>,
+    /// Up to 50 records of every object store
     pub items_by_store: std::collections::HashMap<String, Vec<StorageItem>>,
     pub total_items: usize,
     pub total_size_bytes: usize,
 }
 
+// Confirmation of clear_storage
+#[derive(Debug, Clone, Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct StorageClearedResult {
+    pub success: bool,
+    pub message: String,
+}
+
+// Response model: the result of the requested action
+#[derive(Debug, Clone, Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum StorageInspectorResponse {
+    /// get_storage and query_indexeddb
+    Items(StorageQueryResult),
+    /// list_indexeddb
+    Databases(IndexedDBQueryResult),
+    /// clear_storage
+    Cleared(StorageClearedResult),
+}
+
 // Request model for storage inspection
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
 pub struct StorageInspectorRequest {
     pub window_label: Option<String>,
-    pub action: String, // "get_storage", "clear_storage", "list_indexeddb", "query_indexeddb"
-    pub storage_type: Option<String>, // "localStorage", "sessionStorage", "indexedDB"
-    pub key_pattern: Option<String>, // regex or substring for filtering
+    /// Webview to use inside a multi-webview window
+    pub webview_label: Option<String>,
+    /// "get_storage", "clear_storage", "list_indexeddb" or "query_indexeddb"
+    pub action: String,
+    /// "localStorage", "sessionStorage" or "indexedDB"
+    pub storage_type: Option<String>,
+    /// Regex or substring for filtering keys
+    pub key_pattern: Option<String>,
+    // Left out when unset so the webview applies its defaults
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<usize>,
-    pub db_name: Option<String>, // for IndexedDB operations
-    pub store_name: Option<String>, // for IndexedDB operations
+    /// Database name, for IndexedDB operations
+    pub db_name: Option<String>,
+    /// Object store name, for IndexedDB operations
+    pub store_name: Option<String>,
 }
 
 // Handler function for the socket server
@@ -142,7 +174,7 @@ pub async fn handle_get_storage_inspector<R: Runtime>(
     payload: Value,
 ) -> Result<SocketResponse, Error> {
     // Parse params from payload
-    let params: StorageInspectorRequest = serde_json::from_value(payload)
+    let mut params: StorageInspectorRequest = serde_json::from_value(payload)
         .map_err(|e| Error::serialization_error(format!("Invalid payload for storage inspector: {}", e)))?;
 
     // Validate input parameters
@@ -186,14 +218,9 @@ pub async fn handle_get_storage_inspector<R: Runtime>(
         }
     }
 
-    // Get the window
-    let window_label = params
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
-    let _window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::window_not_found(&window_label))?;
+    // Resolve the target webview, which the implementation then addresses by label
+    let webview = resolve_webview(app, params.window_label.as_deref(), params.webview_label.as_deref())?;
+    params.window_label = Some(webview.label().to_string());
 
     // Call the implementation function with cloned app handle and params
     let result = perform_storage_inspector_operation(app.clone(), params.clone()).await;
@@ -220,52 +247,97 @@ pub async fn handle_get_storage_inspector<R: Runtime>(
 async fn perform_storage_inspector_operation<R: Runtime>(
     app: AppHandle<R>,
     params: StorageInspectorRequest,
-) -> Result<Value, StorageInspectorError> {
+) -> Result<StorageInspectorResponse, StorageInspectorError> {
     // Get window label
     let window_label = params
         .window_label
         .clone()
         .unwrap_or_else(|| "main".to_string());
 
-    // Emit event to the window
-    app.emit_to(&window_label, "inspect-storage", &params)
-        .map_err(|e| StorageInspectorError::WebviewOperation(format!("Failed to emit event: {}", e)))?;
-
-    // Set up channel for response
-    let (tx, rx) = mpsc::channel();
-
-    // Listen for response
-    app.once("inspect-storage-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
-
-    // Wait for response with timeout (increased to 10 seconds for IndexedDB operations)
-    match rx.recv_timeout(Duration::from_secs(10)) {
-        Ok(result_string) => {
-            // Parse the response
-            let response: Value = serde_json::from_str(&result_string).map_err(|e| {
-                StorageInspectorError::JavaScriptError(format!("Failed to parse response: {}", e))
-            })?;
-
-            // Check if result contains an error
-            if let Some(error) = response.get("error") {
-                if let Some(error_str) = error.as_str() {
-                    return Err(StorageInspectorError::JavaScriptError(error_str.to_string()));
-                } else {
-                    return Err(StorageInspectorError::JavaScriptError(
-                        "Unknown error".to_string(),
-                    ));
-                }
-            }
+    // Emit event to the window and wait for its response
+    let response = bridge::request(
+        &app,
+        &window_label,
+        "inspect-storage",
+        serde_json::to_value(params)
+            .map_err(|e| Error::serialization_error(format!("Failed to serialize request: {}", e)))?,
+        Duration::from_secs(10),
+    )
+    .await?;
 
-            // Get data from response
-            if let Some(data) = response.get("data") {
-                Ok(data.clone())
-            } else {
-                Ok(Value::Null)
-            }
+    // Check if result contains an error (the guest reports `error: null` on success)
+    if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+        if let Some(error_str) = error.as_str() {
+            return Err(StorageInspectorError::JavaScriptError(error_str.to_string()));
+        } else {
+            return Err(StorageInspectorError::JavaScriptError(
+                "Unknown error".to_string(),
+            ));
         }
-        Err(e) => Err(e.into()),
+    }
+
+    // Get data from response
+    serde_json::from_value(response.get("data").cloned().unwrap_or(Value::Null))
+        .map_err(|e| StorageInspectorError::JavaScriptError(format!("Invalid storage inspector result: {}", e)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(data: Value) -> StorageInspectorResponse {
+        serde_json::from_value(data).unwrap()
+    }
+
+    #[test]
+    fn every_action_result_has_its_own_shape() {
+        let items = parse(json!({
+            "storage_type": "localStorage",
+            "items": [{ "key": "theme", "value": { "dark": true }, "size_bytes": 13 }],
+            "total_items": 1,
+            "total_size_bytes": 13,
+            "paginated": false,
+            "page": 0,
+            "page_size": 50,
+        }));
+        assert!(matches!(items, StorageInspectorResponse::Items(ref result) if result.items[0].key == "theme"));
+
+        // IndexedDB records are keyed by their key path value or index
+        let records = parse(json!({
+            "storage_type": "indexedDB/app/todos",
+            "items": [{ "key": 0, "value": { "id": 7 }, "size_bytes": 8 }],
+            "total_items": 1,
+            "total_size_bytes": 8,
+            "paginated": false,
+            "page": 0,
+            "page_size": 50,
+        }));
+        assert!(matches!(records, StorageInspectorResponse::Items(ref result) if result.items[0].key == 0));
+
+        let databases = parse(json!({
+            "databases": [{
+                "name": "app",
+                "version": 3,
+                "stores": [{ "name": "todos", "key_path": "id", "auto_increment": true, "indexes": ["done"], "item_count": 1 }],
+            }],
+            "items_by_store": { "todos": [{ "key": 7, "value": { "id": 7 }, "size_bytes": 8 }] },
+            "total_items": 1,
+            "total_size_bytes": 8,
+        }));
+        assert!(matches!(databases, StorageInspectorResponse::Databases(ref result) if result.databases[0].stores.len() == 1));
+
+        let cleared = parse(json!({ "success": true, "message": "localStorage cleared" }));
+        assert!(matches!(cleared, StorageInspectorResponse::Cleared(ref result) if result.success));
+    }
+
+    #[test]
+    fn unset_pages_are_left_to_the_webview() {
+        let request: StorageInspectorRequest =
+            serde_json::from_value(json!({ "action": "get_storage", "storage_type": "localStorage" })).unwrap();
+        let sent = serde_json::to_value(request).unwrap();
+        assert!(sent.get("page").is_none());
+        assert!(sent.get("page_size").is_none());
     }
 }
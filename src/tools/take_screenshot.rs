@@ -1,24 +1,128 @@
 use crate::error::{Error, Result};
-use crate::shared::ScreenshotParams;
+use crate::shared::{CssRect, PixelRect, ScreenshotFormat, ScreenshotParams, SizeFallback};
 use base64;
 use image::DynamicImage;
 use serde_json::Value;
-use tauri::{AppHandle, Runtime};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime, Webview};
 use log::info;
 use crate::TauriMcpExt;
-use crate::models::ScreenshotRequest;
+use crate::models::{ScreenshotRegion, ScreenshotRequest};
 use crate::socket_server::SocketResponse;
+use crate::tools::webview::find_element_rect;
+
+/// The element or region a screenshot request asks for, padded and clipped to the webview.
+/// None when the whole webview is wanted.
+pub async fn resolve_region<R: Runtime>(
+    app: &AppHandle<R>,
+    webview: &Webview<R>,
+    request: &ScreenshotRequest,
+) -> Result<Option<ScreenshotRegion>> {
+    let rect = if let Some(selector_value) = &request.selector_value {
+        let rect = find_element_rect(
+            app,
+            webview,
+            request.selector_type.as_deref(),
+            selector_value,
+            "take_screenshot",
+        )
+        .await?;
+        // Give the page a frame to paint in case the element was scrolled into view
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        rect
+    } else {
+        match (request.x, request.y, request.width, request.height) {
+            (None, None, None, None) => return Ok(None),
+            (Some(x), Some(y), Some(width), Some(height)) => CssRect { x, y, width, height },
+            _ => {
+                return Err(Error::invalid_parameter(
+                    "x",
+                    "x, y, width and height together",
+                    "only some of them",
+                ));
+            }
+        }
+    };
+    let padding = request.padding.unwrap_or(0.0);
+    if padding < 0.0 {
+        return Err(Error::invalid_parameter("padding", "a non-negative number", padding.to_string()));
+    }
+    if rect.width + 2.0 * padding <= 0.0 || rect.height + 2.0 * padding <= 0.0 {
+        return Err(Error::invalid_parameter(
+            "width",
+            "a region with a positive size",
+            format!("{}x{}", rect.width, rect.height),
+        ));
+    }
+
+    let scale_factor = webview.window().scale_factor()?;
+    let size = webview.size()?;
+    // Whole physical pixels covering the padded rect, clipped to the webview
+    let left = ((rect.x - padding) * scale_factor).floor().clamp(0.0, size.width as f64);
+    let top = ((rect.y - padding) * scale_factor).floor().clamp(0.0, size.height as f64);
+    let right = ((rect.x + rect.width + padding) * scale_factor).ceil().clamp(0.0, size.width as f64);
+    let bottom = ((rect.y + rect.height + padding) * scale_factor).ceil().clamp(0.0, size.height as f64);
+    if right <= left || bottom <= top {
+        return Err(Error::window_operation_failed(
+            "take_screenshot",
+            "The requested region is outside the visible webview",
+        ));
+    }
+
+    Ok(Some(ScreenshotRegion {
+        css: CssRect {
+            x: left / scale_factor,
+            y: top / scale_factor,
+            width: (right - left) / scale_factor,
+            height: (bottom - top) / scale_factor,
+        },
+        physical: PixelRect {
+            x: left as u32,
+            y: top as u32,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        },
+    }))
+}
 
 /// Common function to process and compress an image - used by platform implementations
 pub fn process_image(mut dynamic_image: DynamicImage, params: &ScreenshotParams) -> Result<String> {
     // Extract parameters from the shared struct
-    let quality = params.quality.unwrap_or(85) as u8;
+    let quality = params.quality.unwrap_or(85).clamp(1, 100) as u8;
     let max_width = params.max_width.map(|w| w as u32);
     let max_size_bytes = params
         .max_size_mb
         .map(|mb| (mb * 1024.0 * 1024.0) as u64)
         .unwrap_or(2 * 1024 * 1024);
 
+    // Keep only the requested region, clipped to what was captured
+    if let Some(crop) = params.crop {
+        let x = crop.x.min(dynamic_image.width());
+        let y = crop.y.min(dynamic_image.height());
+        let width = crop.width.min(dynamic_image.width() - x);
+        let height = crop.height.min(dynamic_image.height() - y);
+        if width == 0 || height == 0 {
+            return Err(Error::window_operation_failed(
+                "image cropping",
+                format!(
+                    "Region {}x{} at ({}, {}) is outside the {}x{} capture",
+                    crop.width,
+                    crop.height,
+                    crop.x,
+                    crop.y,
+                    dynamic_image.width(),
+                    dynamic_image.height()
+                ),
+            ));
+        }
+        info!("[SCREENSHOT] Cropping to {}x{} at ({}, {})", width, height, x, y);
+        dynamic_image = dynamic_image.crop_imm(x, y, width, height);
+    }
+
+    if params.strict_size {
+        return encode_within_limits(&dynamic_image, params, quality, max_width, max_size_bytes);
+    }
+
     // Use max_width if specified, otherwise use a default if image is very large
     let effective_max_width = max_width.unwrap_or_else(|| {
         if dynamic_image.width() > 1920 {
@@ -48,106 +152,144 @@ pub fn process_image(mut dynamic_image: DynamicImage, params: &ScreenshotParams)
         );
     }
 
-    // Use JPG for better compression
-    let mut output_data = Vec::new();
+    let format = params.format.unwrap_or_default();
+    let fallback = params.size_fallback.unwrap_or_default();
     let mut current_quality = quality;
+    let mut output_data = encode_image(&dynamic_image, format, current_quality)?;
 
-    // Try encoding with JPEG
-    match dynamic_image.write_to(
-        &mut std::io::Cursor::new(&mut output_data),
-        image::ImageOutputFormat::Jpeg(current_quality),
-    ) {
-        Ok(_) => {
-            // Reduce quality if needed to meet max size
-            while output_data.len() as u64 > max_size_bytes && current_quality > 30 {
-                info!(
-                    "[SCREENSHOT] Output size {} bytes exceeds max {}. Reducing quality to {}",
-                    output_data.len(),
-                    max_size_bytes,
-                    current_quality - 10
-                );
-
-                // Reduce quality and try again
-                current_quality -= 10;
-                output_data.clear();
-
-                if let Err(e) = dynamic_image.write_to(
-                    &mut std::io::Cursor::new(&mut output_data),
-                    image::ImageOutputFormat::Jpeg(current_quality),
-                ) {
-                    return Err(Error::window_operation_failed(
-                        "image encoding",
-                        format!("Failed to re-encode JPEG: {}", e),
-                    ));
-                }
-            }
+    if format.is_lossy() && fallback == SizeFallback::QualityFirst {
+        lower_quality(&dynamic_image, &mut output_data, &mut current_quality, max_size_bytes)?;
+    }
+    downscale(&mut dynamic_image, &mut output_data, format, current_quality, max_size_bytes)?;
+    if format.is_lossy() && fallback == SizeFallback::DownscaleFirst {
+        lower_quality(&dynamic_image, &mut output_data, &mut current_quality, max_size_bytes)?;
+    }
 
-            // If still too large, resize the image
-            if output_data.len() as u64 > max_size_bytes && dynamic_image.width() > 800 {
-                info!("[SCREENSHOT] Image still too large after quality reduction. Resizing...");
-                let scale_factor = 0.8; // reduce by 20% each iteration
-
-                while output_data.len() as u64 > max_size_bytes && dynamic_image.width() > 800 {
-                    // Resize image
-                    let new_width = (dynamic_image.width() as f32 * scale_factor) as u32;
-                    let new_height = (dynamic_image.height() as f32 * scale_factor) as u32;
-
-                    info!("[SCREENSHOT] Resizing to {}x{}", new_width, new_height);
-                    dynamic_image = dynamic_image.resize(
-                        new_width,
-                        new_height,
-                        image::imageops::FilterType::Triangle,
-                    );
-
-                    // Re-encode with current quality
-                    output_data.clear();
-                    if let Err(e) = dynamic_image.write_to(
-                        &mut std::io::Cursor::new(&mut output_data),
-                        image::ImageOutputFormat::Jpeg(current_quality),
-                    ) {
-                        return Err(Error::window_operation_failed(
-                            "image resizing",
-                            format!("Failed to encode resized image: {}", e),
-                        ));
-                    }
-
-                    // Give up if we're getting very small
-                    if dynamic_image.width() <= 800 {
-                        break;
-                    }
-                }
-            }
+    // Convert to base64
+    let base64_data = base64::encode(&output_data);
+    let data_url = format!("data:{};base64,{}", format.mime_type(), base64_data);
+
+    info!(
+        "[SCREENSHOT] Final image size: {}x{}, data size: {} bytes, format: {:?}, quality: {}",
+        dynamic_image.width(),
+        dynamic_image.height(),
+        output_data.len(),
+        format,
+        current_quality
+    );
 
-            // Convert to base64
-            let base64_data = base64::encode(&output_data);
-            let data_url = format!("data:image/jpeg;base64,{}", base64_data);
+    // Final check - reject if still too large
+    if base64_data.len() > 5 * 1024 * 1024 {
+        return Err(Error::window_operation_failed(
+            "screenshot compression",
+            format!(
+                "Screenshot is still too large: {} bytes. Try using a smaller max_width.",
+                base64_data.len()
+            ),
+        ));
+    }
+
+    Ok(data_url)
+}
 
-            info!(
-                "[SCREENSHOT] Final image size: {}x{}, data size: {} bytes, quality: {}",
-                dynamic_image.width(),
-                dynamic_image.height(),
+// Encode at the captured size, failing where process_image would otherwise shrink the image
+fn encode_within_limits(
+    image: &DynamicImage,
+    params: &ScreenshotParams,
+    quality: u8,
+    max_width: Option<u32>,
+    max_size_bytes: u64,
+) -> Result<String> {
+    if let Some(max_width) = max_width
+        && image.width() > max_width
+    {
+        return Err(Error::window_operation_failed(
+            "screenshot size",
+            format!("Capture is {}px wide, more than the limit of {}px", image.width(), max_width),
+        ));
+    }
+
+    let format = params.format.unwrap_or_default();
+    let output_data = encode_image(image, format, quality)?;
+    if output_data.len() as u64 > max_size_bytes {
+        return Err(Error::window_operation_failed(
+            "screenshot size",
+            format!(
+                "Capture is {} bytes, more than the limit of {} bytes",
                 output_data.len(),
-                current_quality
-            );
-
-            // Final check - reject if still too large
-            if base64_data.len() > 5 * 1024 * 1024 {
-                return Err(Error::window_operation_failed(
-                    "screenshot compression",
-                    format!(
-                        "Screenshot is still too large: {} bytes. Try using a smaller max_width.",
-                        base64_data.len()
-                    ),
-                ));
-            }
+                max_size_bytes
+            ),
+        ));
+    }
 
-            Ok(data_url)
-        }
-        Err(e) => Err(Error::window_operation_failed(
-            "JPEG encoding",
-            format!("Failed to encode JPEG: {}", e),
-        )),
+    info!(
+        "[SCREENSHOT] Image at native size {}x{}, data size: {} bytes, format: {:?}",
+        image.width(),
+        image.height(),
+        output_data.len(),
+        format
+    );
+    Ok(format!("data:{};base64,{}", format.mime_type(), base64::encode(&output_data)))
+}
+
+fn encode_image(image: &DynamicImage, format: ScreenshotFormat, quality: u8) -> Result<Vec<u8>> {
+    let output_format = match format {
+        ScreenshotFormat::Jpeg => image::ImageOutputFormat::Jpeg(quality),
+        ScreenshotFormat::Png => image::ImageOutputFormat::Png,
+        ScreenshotFormat::Webp => image::ImageOutputFormat::WebP,
+    };
+    let mut output_data = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut output_data), output_format)
+        .map_err(|e| {
+            Error::window_operation_failed("image encoding", format!("Failed to encode {:?}: {}", format, e))
+        })?;
+    Ok(output_data)
+}
+
+// Reduce JPEG quality in steps of 10, down to 30, until the image fits
+fn lower_quality(
+    image: &DynamicImage,
+    output_data: &mut Vec<u8>,
+    current_quality: &mut u8,
+    max_size_bytes: u64,
+) -> Result<()> {
+    while output_data.len() as u64 > max_size_bytes && *current_quality > 30 {
+        info!(
+            "[SCREENSHOT] Output size {} bytes exceeds max {}. Reducing quality to {}",
+            output_data.len(),
+            max_size_bytes,
+            current_quality.saturating_sub(10).max(30)
+        );
+        *current_quality = current_quality.saturating_sub(10).max(30);
+        *output_data = encode_image(image, ScreenshotFormat::Jpeg, *current_quality)?;
+    }
+    Ok(())
+}
+
+// Shrink by 20% at a time until the image fits or is no wider than 800 pixels
+fn downscale(
+    image: &mut DynamicImage,
+    output_data: &mut Vec<u8>,
+    format: ScreenshotFormat,
+    quality: u8,
+    max_size_bytes: u64,
+) -> Result<()> {
+    if output_data.len() as u64 <= max_size_bytes || image.width() <= 800 {
+        return Ok(());
+    }
+    info!("[SCREENSHOT] Image too large at {} bytes. Resizing...", output_data.len());
+    let scale_factor = 0.8; // reduce by 20% each iteration
+
+    while output_data.len() as u64 > max_size_bytes && image.width() > 800 {
+        let new_width = (image.width() as f32 * scale_factor) as u32;
+        let new_height = (image.height() as f32 * scale_factor) as u32;
+
+        info!("[SCREENSHOT] Resizing to {}x{}", new_width, new_height);
+        *image = image.resize(new_width, new_height, image::imageops::FilterType::Triangle);
+        *output_data = encode_image(image, format, quality)?;
     }
+    Ok(())
 }
 
 pub async fn handle_take_screenshot<R: Runtime>(
@@ -0,0 +1,643 @@
+use image::{ImageFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::TauriMcpExt;
+use crate::error::Error;
+use crate::models::{ScreenshotRegion, ScreenshotRequest};
+use crate::shared::{PixelRect, ScreenshotFormat, ScreenshotParams};
+use crate::socket_server::SocketResponse;
+use crate::tools::take_screenshot::process_image;
+
+// Largest YIQ distance between two colors, used to scale the threshold
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+// Changed pixels closer than this are reported as one region
+const REGION_CELL: u32 = 8;
+
+// Regions beyond this are only counted
+const MAX_REPORTED_REGIONS: usize = 50;
+
+// Files written next to a baseline on a mismatch, as `<name>.<suffix>.png`
+const ARTIFACT_SUFFIXES: [&str; 2] = ["diff", "actual"];
+
+// Largest capture compared. Captures are never downscaled, so anything bigger is an error
+const MAX_CAPTURE_WIDTH: i32 = 7680;
+const MAX_CAPTURE_MB: f32 = 64.0;
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct VisualCompareRequest {
+    /// Baseline name; the image is stored as `<name>.png` in the baseline directory
+    name: String,
+    window_label: Option<String>,
+    /// Webview to capture inside a multi-webview window
+    webview_label: Option<String>,
+    /// Compare only this element
+    selector_value: Option<String>,
+    /// "id", "class", "tag", "text", "xpath", "css" (default), "ref" (from get_accessibility_tree) or "mark" (from take_annotated_screenshot)
+    selector_type: Option<String>,
+    /// Compare only this region, in CSS pixels from the webview's top-left corner
+    x: Option<f64>,
+    y: Option<f64>,
+    width: Option<f64>,
+    height: Option<f64>,
+    /// CSS pixels added around the element or region on every side
+    padding: Option<f64>,
+    /// Color difference a pixel may have before it counts as changed, from 0 (exact) to 1 (default 0.1)
+    threshold: Option<f64>,
+    /// Skip pixels that look like anti-aliased edges, which differ between renders (default true)
+    ignore_antialiasing: Option<bool>,
+    /// Areas to leave out, in pixels of the compared image (as reported in `changed_regions`)
+    ignore_regions: Option<Vec<PixelRect>>,
+    /// Highest mismatch percentage that still counts as a match (default 0)
+    max_mismatch_percentage: Option<f64>,
+    /// Save the capture as the new baseline instead of comparing
+    update_baseline: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct VisualCompareResponse {
+    pub name: String,
+    /// "matched", "mismatched", "baseline_created" or "baseline_updated"
+    pub status: String,
+    /// Whether the mismatch percentage is within `max_mismatch_percentage`
+    pub matches: bool,
+    /// Changed pixels as a percentage of the compared pixels
+    pub mismatch_percentage: f64,
+    pub mismatched_pixels: u64,
+    /// Differing pixels ignored as anti-aliasing
+    pub antialiased_pixels: u64,
+    /// Size of the capture
+    pub width: u32,
+    pub height: u32,
+    /// Size of the baseline, when it differs from the capture. The area only one of them covers counts as changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_size: Option<(u32, u32)>,
+    /// Bounding boxes of changed areas, largest first, in pixels of the compared image
+    pub changed_regions: Vec<PixelRect>,
+    /// Number of changed areas, including those beyond the reported ones
+    pub region_count: usize,
+    /// Element or region that was captured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<ScreenshotRegion>,
+    pub baseline_path: String,
+    /// Diff written next to the baseline on a mismatch: changes in red, anti-aliasing in yellow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_path: Option<String>,
+    /// Capture written next to the baseline on a mismatch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_path: Option<String>,
+    /// The diff image as a PNG data URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_image: Option<String>,
+}
+
+/// Baseline names become file names, so they may not leave the baseline directory, nor
+/// collide with the `<name>.diff.png` and `<name>.actual.png` written next to another baseline
+fn is_valid_baseline_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && !ARTIFACT_SUFFIXES.iter().any(|suffix| name.ends_with(&format!(".{}", suffix)))
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+/// Result of comparing two images pixel by pixel
+struct Comparison {
+    mismatched: u64,
+    antialiased: u64,
+    compared: u64,
+    diff: RgbaImage,
+    changed: Vec<bool>,
+}
+
+// Color blended onto white, as pixelmatch does, so transparent pixels compare by what is visible
+fn blend(pixel: &Rgba<u8>) -> (f64, f64, f64) {
+    let alpha = pixel[3] as f64 / 255.0;
+    let channel = |c: u8| 255.0 + (c as f64 - 255.0) * alpha;
+    (channel(pixel[0]), channel(pixel[1]), channel(pixel[2]))
+}
+
+fn brightness((r, g, b): (f64, f64, f64)) -> f64 {
+    r * 0.298_895_31 + g * 0.586_622_47 + b * 0.114_482_23
+}
+
+/// Perceptual distance between two colors in YIQ space; only brightness if `y_only`
+fn color_delta(a: &Rgba<u8>, b: &Rgba<u8>, y_only: bool) -> f64 {
+    if a == b {
+        return 0.0;
+    }
+    let (a, b) = (blend(a), blend(b));
+    let y = brightness(a) - brightness(b);
+    if y_only {
+        return y;
+    }
+    let i = (a.0 * 0.595_977_99 - a.1 * 0.274_176_49 - a.2 * 0.321_801_5)
+        - (b.0 * 0.595_977_99 - b.1 * 0.274_176_49 - b.2 * 0.321_801_5);
+    let q = (a.0 * 0.211_470_17 - a.1 * 0.522_617_15 + a.2 * 0.311_146_98)
+        - (b.0 * 0.211_470_17 - b.1 * 0.522_617_15 + b.2 * 0.311_146_98);
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+fn neighbours(image: &RgbaImage, x: u32, y: u32) -> impl Iterator<Item = (u32, u32)> {
+    let (x0, y0) = (x.saturating_sub(1), y.saturating_sub(1));
+    let (x1, y1) = ((x + 1).min(image.width() - 1), (y + 1).min(image.height() - 1));
+    (x0..=x1)
+        .flat_map(move |nx| (y0..=y1).map(move |ny| (nx, ny)))
+        .filter(move |&(nx, ny)| nx != x || ny != y)
+}
+
+fn on_edge(image: &RgbaImage, x: u32, y: u32) -> bool {
+    x == 0 || y == 0 || x == image.width() - 1 || y == image.height() - 1
+}
+
+// Whether at least three neighbours have exactly the same color
+fn has_many_siblings(image: &RgbaImage, x: u32, y: u32) -> bool {
+    let pixel = image.get_pixel(x, y);
+    let mut same = u32::from(on_edge(image, x, y));
+    for (nx, ny) in neighbours(image, x, y) {
+        if image.get_pixel(nx, ny) == pixel {
+            same += 1;
+            if same > 2 {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Whether a pixel sits on an anti-aliased edge: it lies between a darker and a brighter
+/// neighbour, and one of those is part of a solid area in both images
+fn is_antialiased(image: &RgbaImage, other: &RgbaImage, x: u32, y: u32) -> bool {
+    let pixel = image.get_pixel(x, y);
+    let mut zeroes = u32::from(on_edge(image, x, y));
+    let (mut min, mut max) = (0.0, 0.0);
+    let (mut darkest, mut brightest) = ((x, y), (x, y));
+    for (nx, ny) in neighbours(image, x, y) {
+        let delta = color_delta(pixel, image.get_pixel(nx, ny), true);
+        if delta == 0.0 {
+            zeroes += 1;
+            if zeroes > 2 {
+                return false;
+            }
+        } else if delta < min {
+            min = delta;
+            darkest = (nx, ny);
+        } else if delta > max {
+            max = delta;
+            brightest = (nx, ny);
+        }
+    }
+    if min == 0.0 || max == 0.0 {
+        return false;
+    }
+    (has_many_siblings(image, darkest.0, darkest.1) && has_many_siblings(other, darkest.0, darkest.1))
+        || (has_many_siblings(image, brightest.0, brightest.1) && has_many_siblings(other, brightest.0, brightest.1))
+}
+
+fn is_ignored(ignore: &[PixelRect], x: u32, y: u32) -> bool {
+    ignore
+        .iter()
+        .any(|r| x >= r.x && y >= r.y && x - r.x < r.width && y - r.y < r.height)
+}
+
+/// Compare two images over the area either covers; pixels only one of them has count as changed
+fn compare(baseline: &RgbaImage, actual: &RgbaImage, threshold: f64, ignore_antialiasing: bool, ignore: &[PixelRect]) -> Comparison {
+    let width = baseline.width().max(actual.width());
+    let height = baseline.height().max(actual.height());
+    let max_delta = MAX_YIQ_DELTA * threshold * threshold;
+    let same_size = baseline.dimensions() == actual.dimensions();
+
+    let mut comparison = Comparison {
+        mismatched: 0,
+        antialiased: 0,
+        compared: 0,
+        diff: RgbaImage::new(width, height),
+        changed: vec![false; (width * height) as usize],
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            let base = (x < baseline.width() && y < baseline.height()).then(|| baseline.get_pixel(x, y));
+            let current = (x < actual.width() && y < actual.height()).then(|| actual.get_pixel(x, y));
+            // Unchanged areas fade to a light gray copy of the baseline, to show where changes are
+            let faded = |pixel: Option<&Rgba<u8>>| {
+                let gray = pixel.map_or(255.0, |pixel| brightness(blend(pixel)));
+                let value = (255.0 + (gray - 255.0) * 0.1) as u8;
+                Rgba([value, value, value, 255])
+            };
+
+            if is_ignored(ignore, x, y) {
+                comparison.diff.put_pixel(x, y, faded(base.or(current)));
+                continue;
+            }
+            comparison.compared += 1;
+
+            let color = match (base, current) {
+                (Some(base), Some(current)) => {
+                    let delta = color_delta(base, current, false);
+                    if delta <= max_delta {
+                        None
+                    } else if ignore_antialiasing
+                        && same_size
+                        && (is_antialiased(baseline, actual, x, y) || is_antialiased(actual, baseline, x, y))
+                    {
+                        comparison.antialiased += 1;
+                        Some(Rgba([255, 255, 0, 255]))
+                    } else {
+                        Some(Rgba([255, 0, 0, 255]))
+                    }
+                }
+                _ => Some(Rgba([255, 0, 0, 255])),
+            };
+
+            match color {
+                Some(color) => {
+                    if color[1] == 0 {
+                        comparison.mismatched += 1;
+                        comparison.changed[(y * width + x) as usize] = true;
+                    }
+                    comparison.diff.put_pixel(x, y, color);
+                }
+                None => comparison.diff.put_pixel(x, y, faded(base)),
+            }
+        }
+    }
+    comparison
+}
+
+/// Bounding boxes of changed areas. Changes in neighbouring cells of REGION_CELL pixels join one area.
+fn changed_regions(changed: &[bool], width: u32, height: u32) -> Vec<PixelRect> {
+    let columns = width.div_ceil(REGION_CELL);
+    let rows = height.div_ceil(REGION_CELL);
+    // Exact bounds of the changed pixels in each cell
+    let mut cells: Vec<Option<(u32, u32, u32, u32)>> = vec![None; (columns * rows) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            if changed[(y * width + x) as usize] {
+                let cell = &mut cells[((y / REGION_CELL) * columns + x / REGION_CELL) as usize];
+                *cell = Some(match *cell {
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                    None => (x, y, x, y),
+                });
+            }
+        }
+    }
+
+    let mut visited = vec![false; cells.len()];
+    let mut regions = Vec::new();
+    for start in 0..cells.len() {
+        if visited[start] || cells[start].is_none() {
+            continue;
+        }
+        visited[start] = true;
+        let mut bounds = cells[start].expect("cell has changes");
+        let mut stack = vec![start];
+        while let Some(index) = stack.pop() {
+            let (column, row) = (index as u32 % columns, index as u32 / columns);
+            for ny in row.saturating_sub(1)..=(row + 1).min(rows - 1) {
+                for nx in column.saturating_sub(1)..=(column + 1).min(columns - 1) {
+                    let next = (ny * columns + nx) as usize;
+                    if let Some((x0, y0, x1, y1)) = cells[next]
+                        && !visited[next]
+                    {
+                        visited[next] = true;
+                        bounds = (bounds.0.min(x0), bounds.1.min(y0), bounds.2.max(x1), bounds.3.max(y1));
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        let (x0, y0, x1, y1) = bounds;
+        regions.push(PixelRect {
+            x: x0,
+            y: y0,
+            width: x1 - x0 + 1,
+            height: y1 - y0 + 1,
+        });
+    }
+    regions.sort_by_key(|region| std::cmp::Reverse(region.width as u64 * region.height as u64));
+    regions
+}
+
+fn decode_data_url(data_url: &str) -> Result<RgbaImage, Error> {
+    let encoded = data_url.split_once(',').map_or(data_url, |(_, data)| data);
+    let bytes = base64::decode(encoded)
+        .map_err(|e| Error::serialization_error(format!("Invalid screenshot data: {}", e)))?;
+    let image = image::load_from_memory_with_format(&bytes, ImageFormat::Png)
+        .map_err(|e| Error::serialization_error(format!("Invalid screenshot image: {}", e)))?;
+    Ok(image.to_rgba8())
+}
+
+fn save_png(image: &RgbaImage, path: &Path) -> Result<(), Error> {
+    image
+        .save_with_format(path, ImageFormat::Png)
+        .map_err(|e| Error::window_operation_failed("visual_compare", format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// Compare a capture with the stored baseline. Runs on a blocking thread.
+fn compare_with_baseline(
+    request: &VisualCompareRequest,
+    actual: RgbaImage,
+    baseline_path: &Path,
+    sibling: impl Fn(&str) -> PathBuf,
+) -> Result<VisualCompareResponse, Error> {
+    let mut response = VisualCompareResponse {
+        name: request.name.clone(),
+        status: String::new(),
+        matches: true,
+        mismatch_percentage: 0.0,
+        mismatched_pixels: 0,
+        antialiased_pixels: 0,
+        width: actual.width(),
+        height: actual.height(),
+        baseline_size: None,
+        changed_regions: Vec::new(),
+        region_count: 0,
+        region: None,
+        baseline_path: baseline_path.display().to_string(),
+        diff_path: None,
+        actual_path: None,
+        diff_image: None,
+    };
+    let [diff_path, actual_path] = ARTIFACT_SUFFIXES.map(&sibling);
+
+    let update = request.update_baseline.unwrap_or(false);
+    if update || !baseline_path.exists() {
+        if let Some(parent) = baseline_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                Error::window_operation_failed("visual_compare", format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+        response.status = if baseline_path.exists() { "baseline_updated" } else { "baseline_created" }.to_string();
+        save_png(&actual, baseline_path)?;
+        // Leftovers from an earlier mismatch no longer apply
+        let _ = std::fs::remove_file(&diff_path);
+        let _ = std::fs::remove_file(&actual_path);
+        return Ok(response);
+    }
+
+    let baseline = image::open(baseline_path)
+        .map_err(|e| {
+            Error::window_operation_failed("visual_compare", format!("Failed to read {}: {}", baseline_path.display(), e))
+        })?
+        .to_rgba8();
+    if baseline.dimensions() != actual.dimensions() {
+        response.baseline_size = Some(baseline.dimensions());
+    }
+
+    let comparison = compare(
+        &baseline,
+        &actual,
+        request.threshold.unwrap_or(0.1).clamp(0.0, 1.0),
+        request.ignore_antialiasing.unwrap_or(true),
+        request.ignore_regions.as_deref().unwrap_or_default(),
+    );
+    let (width, height) = comparison.diff.dimensions();
+    let mut regions = changed_regions(&comparison.changed, width, height);
+
+    response.mismatched_pixels = comparison.mismatched;
+    response.antialiased_pixels = comparison.antialiased;
+    response.mismatch_percentage = if comparison.compared == 0 {
+        0.0
+    } else {
+        comparison.mismatched as f64 * 100.0 / comparison.compared as f64
+    };
+    response.matches = response.mismatch_percentage <= request.max_mismatch_percentage.unwrap_or(0.0);
+    response.region_count = regions.len();
+    regions.truncate(MAX_REPORTED_REGIONS);
+    response.changed_regions = regions;
+
+    if comparison.mismatched == 0 {
+        response.status = "matched".to_string();
+        let _ = std::fs::remove_file(&diff_path);
+        let _ = std::fs::remove_file(&actual_path);
+        return Ok(response);
+    }
+
+    response.status = if response.matches { "matched" } else { "mismatched" }.to_string();
+    save_png(&comparison.diff, &diff_path)?;
+    save_png(&actual, &actual_path)?;
+    response.diff_path = Some(diff_path.display().to_string());
+    response.actual_path = Some(actual_path.display().to_string());
+
+    // Encoded like screenshots, so the size limits for responses apply
+    let diff_params = ScreenshotParams {
+        window_label: None,
+        format: Some(ScreenshotFormat::Png),
+        quality: None,
+        max_width: None,
+        max_size_mb: None,
+        size_fallback: None,
+        application_name: None,
+        crop: None,
+        strict_size: false,
+    };
+    response.diff_image = Some(process_image(image::DynamicImage::ImageRgba8(comparison.diff), &diff_params)?);
+    Ok(response)
+}
+
+/// Capture a window or element and compare it with a stored baseline image
+pub async fn handle_visual_compare<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: VisualCompareRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for visual_compare: {}", e)))?;
+
+    if !is_valid_baseline_name(&request.name) {
+        return Err(Error::invalid_parameter(
+            "name",
+            "a file name made of alphanumeric characters, '-', '_' and '.', not ending in .diff or .actual",
+            &request.name,
+        ));
+    }
+    if let Some(threshold) = request.threshold
+        && !(0.0..=1.0).contains(&threshold)
+    {
+        return Err(Error::invalid_parameter("threshold", "a number from 0 to 1", threshold.to_string()));
+    }
+
+    // Lossless and at native resolution, so unchanged pages compare equal whatever their
+    // content; only the returned diff image is shrunk to fit a response
+    let capture = app
+        .tauri_mcp()
+        .take_native_screenshot_async(ScreenshotRequest {
+            window_label: request.window_label.clone().unwrap_or_else(|| "main".to_string()),
+            webview_label: request.webview_label.clone(),
+            selector_value: request.selector_value.clone(),
+            selector_type: request.selector_type.clone(),
+            x: request.x,
+            y: request.y,
+            width: request.width,
+            height: request.height,
+            padding: request.padding,
+            format: Some(ScreenshotFormat::Png),
+            max_width: Some(MAX_CAPTURE_WIDTH),
+            max_size_mb: Some(MAX_CAPTURE_MB),
+            ..Default::default()
+        })
+        .await?;
+    let data_url = match (capture.success, capture.data) {
+        (true, Some(data_url)) => data_url,
+        _ => {
+            return Err(Error::window_operation_failed(
+                "visual_compare",
+                capture.error.unwrap_or_else(|| "Screenshot failed".to_string()),
+            ));
+        }
+    };
+    let actual = decode_data_url(&data_url)?;
+
+    let directory = app.tauri_mcp().baseline_dir().to_path_buf();
+    let baseline_path = directory.join(format!("{}.png", request.name));
+    info!(
+        "[TAURI_MCP] Comparing {}x{} capture with baseline {}",
+        actual.width(),
+        actual.height(),
+        baseline_path.display()
+    );
+
+    let name = request.name.clone();
+    let request_for_task = request.clone();
+    let mut response = tokio::task::spawn_blocking(move || {
+        compare_with_baseline(&request_for_task, actual, &baseline_path, |suffix| {
+            directory.join(format!("{}.{}.png", name, suffix))
+        })
+    })
+    .await
+    .map_err(|e| Error::Anyhow { message: format!("Comparison task failed: {}", e) })??;
+    response.region = capture.region;
+
+    info!(
+        "[TAURI_MCP] Visual comparison {}: {} ({:.3}% mismatched)",
+        request.name, response.status, response.mismatch_percentage
+    );
+
+    let data = serde_json::to_value(response)
+        .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    // Black on the left half, white on the right: a hard vertical edge
+    fn edge_image() -> RgbaImage {
+        RgbaImage::from_fn(6, 6, |x, _| if x < 3 { BLACK } else { WHITE })
+    }
+
+    #[test]
+    fn baseline_names_stay_in_the_directory_and_clear_of_artifacts() {
+        assert!(is_valid_baseline_name("login-form.v2"));
+        assert!(!is_valid_baseline_name(""));
+        assert!(!is_valid_baseline_name(".hidden"));
+        assert!(!is_valid_baseline_name("../escape"));
+        assert!(!is_valid_baseline_name("login.diff"));
+        assert!(!is_valid_baseline_name("login.actual"));
+        assert!(is_valid_baseline_name("diff"));
+    }
+
+    #[test]
+    fn identical_images_match() {
+        let image = edge_image();
+        let comparison = compare(&image, &image, 0.1, true, &[]);
+        assert_eq!(comparison.mismatched, 0);
+        assert_eq!(comparison.antialiased, 0);
+        assert_eq!(comparison.compared, 36);
+        assert!(changed_regions(&comparison.changed, 6, 6).is_empty());
+    }
+
+    #[test]
+    fn a_single_changed_pixel_is_reported() {
+        let baseline = RgbaImage::from_pixel(6, 6, WHITE);
+        let mut actual = baseline.clone();
+        actual.put_pixel(4, 1, BLACK);
+
+        let comparison = compare(&baseline, &actual, 0.1, true, &[]);
+        assert_eq!(comparison.mismatched, 1);
+        assert_eq!(*comparison.diff.get_pixel(4, 1), Rgba([255, 0, 0, 255]));
+        assert_eq!(
+            changed_regions(&comparison.changed, 6, 6),
+            vec![PixelRect { x: 4, y: 1, width: 1, height: 1 }]
+        );
+
+        // Left out entirely when the pixel is in an ignored region
+        let ignored = compare(&baseline, &actual, 0.1, true, &[PixelRect { x: 4, y: 0, width: 2, height: 2 }]);
+        assert_eq!(ignored.mismatched, 0);
+        assert_eq!(ignored.compared, 32);
+    }
+
+    #[test]
+    fn small_differences_stay_under_the_threshold() {
+        let baseline = RgbaImage::from_pixel(4, 4, WHITE);
+        let mut actual = baseline.clone();
+        actual.put_pixel(1, 1, Rgba([250, 250, 250, 255]));
+
+        assert_eq!(compare(&baseline, &actual, 0.1, false, &[]).mismatched, 0);
+        assert_eq!(compare(&baseline, &actual, 0.0, false, &[]).mismatched, 1);
+    }
+
+    #[test]
+    fn anti_aliased_edge_pixels_are_ignored() {
+        let baseline = edge_image();
+        let mut actual = baseline.clone();
+        // A gray pixel on the edge, between solid black and solid white
+        actual.put_pixel(3, 2, Rgba([128, 128, 128, 255]));
+
+        assert!(is_antialiased(&actual, &baseline, 3, 2));
+        assert!(!is_antialiased(&baseline, &actual, 3, 2));
+
+        let comparison = compare(&baseline, &actual, 0.1, true, &[]);
+        assert_eq!((comparison.mismatched, comparison.antialiased), (0, 1));
+        assert_eq!(*comparison.diff.get_pixel(3, 2), Rgba([255, 255, 0, 255]));
+
+        let strict = compare(&baseline, &actual, 0.1, false, &[]);
+        assert_eq!((strict.mismatched, strict.antialiased), (1, 0));
+    }
+
+    #[test]
+    fn size_differences_count_as_changed() {
+        let baseline = RgbaImage::from_pixel(4, 4, WHITE);
+        let actual = RgbaImage::from_pixel(4, 5, WHITE);
+
+        let comparison = compare(&baseline, &actual, 0.1, true, &[]);
+        assert_eq!(comparison.mismatched, 4);
+        assert_eq!(
+            changed_regions(&comparison.changed, 4, 5),
+            vec![PixelRect { x: 0, y: 4, width: 4, height: 1 }]
+        );
+    }
+
+    #[test]
+    fn changes_in_neighbouring_cells_merge_into_one_region() {
+        let (width, height) = (40, 24);
+        let mut changed = vec![false; (width * height) as usize];
+        let mut set = |x: u32, y: u32| changed[(y * width + x) as usize] = true;
+        // Diagonally adjacent cells merge
+        set(3, 3);
+        set(12, 12);
+        // A cell apart from the others stays on its own
+        set(35, 20);
+
+        let regions = changed_regions(&changed, width, height);
+        assert_eq!(
+            regions,
+            vec![
+                PixelRect { x: 3, y: 3, width: 10, height: 10 },
+                PixelRect { x: 35, y: 20, width: 1, height: 1 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime, Webview};
+use log::info;
+
+use crate::bridge;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::windows::resolve_webview;
+
+const MODES: &str = "\"selector_visible\", \"selector_hidden\", \"text\", \"url_change\", \"network_idle\" or \"js\"";
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct WaitForRequest {
+    window_label: Option<String>,
+    /// Webview to use inside a multi-webview window
+    webview_label: Option<String>,
+    /// "selector_visible", "selector_hidden", "text", "url_change", "network_idle" or "js"
+    mode: String,
+    /// Element to wait for, for the selector modes
+    selector_value: Option<String>,
+    /// "id", "class", "tag", "text", "xpath", "css" (default), "ref" (from get_accessibility_tree) or "mark" (from take_annotated_screenshot)
+    selector_type: Option<String>,
+    /// Text that must appear in the page, for "text"
+    text: Option<String>,
+    /// For "url_change": wait until the URL contains this text instead of for any change
+    url_contains: Option<String>,
+    /// For "network_idle": how long no request may start or finish (default 500)
+    idle_ms: Option<u64>,
+    /// JavaScript expression that must become truthy, for "js". Promises are awaited.
+    code: Option<String>,
+    /// Default 10000
+    timeout_ms: Option<u64>,
+    /// Time between checks, default 100
+    interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WaitForResponse {
+    pub mode: String,
+    /// Time from the start of the wait until the condition held
+    pub waited_ms: u64,
+    /// Number of checks performed
+    pub checks: u32,
+    /// What the last check saw: the new URL, the predicate's value, network activity or element state
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<Value>,
+}
+
+/// A condition checked on every poll
+enum Condition {
+    /// Evaluated by the webview
+    Page(Value),
+    UrlChange { from: String, contains: Option<String> },
+    NetworkIdle { idle_ms: u64 },
+}
+
+fn required<'a>(value: &'a Option<String>, param: &str) -> Result<&'a str, Error> {
+    value
+        .as_deref()
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| Error::invalid_parameter(param, "a value for this mode", "nothing"))
+}
+
+impl Condition {
+    fn from_request<R: Runtime>(request: &WaitForRequest, webview: &Webview<R>) -> Result<Self, Error> {
+        Ok(match request.mode.as_str() {
+            "selector_visible" | "selector_hidden" => Condition::Page(json!({
+                "mode": request.mode,
+                "selectorType": request.selector_type.as_deref().unwrap_or("css"),
+                "selector": required(&request.selector_value, "selector_value")?,
+            })),
+            "text" => Condition::Page(json!({ "mode": "text", "text": required(&request.text, "text")? })),
+            "js" => Condition::Page(json!({ "mode": "js", "code": required(&request.code, "code")? })),
+            "url_change" => Condition::UrlChange {
+                from: webview
+                    .url()
+                    .map_err(|e| Error::window_operation_failed("wait_for", e.to_string()))?
+                    .to_string(),
+                contains: request.url_contains.clone(),
+            },
+            "network_idle" => Condition::NetworkIdle {
+                idle_ms: request.idle_ms.unwrap_or(500),
+            },
+            other => return Err(Error::invalid_parameter("mode", MODES, other)),
+        })
+    }
+
+    /// Whether the condition holds now, with what was observed.
+    /// Ok(None) means the page could not answer, e.g. because it is navigating.
+    async fn check<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        webview: &Webview<R>,
+        elapsed: Duration,
+        timeout: Duration,
+    ) -> Result<Option<(bool, Option<Value>)>, Error> {
+        match self {
+            Condition::UrlChange { from, contains } => {
+                let url = webview
+                    .url()
+                    .map_err(|e| Error::window_operation_failed("wait_for", e.to_string()))?
+                    .to_string();
+                let satisfied = match contains {
+                    Some(contains) => url.contains(contains.as_str()),
+                    None => url != *from,
+                };
+                Ok(Some((satisfied, Some(Value::String(url)))))
+            }
+            Condition::Page(payload) => page_check(app, webview, payload.clone(), timeout).await,
+            Condition::NetworkIdle { idle_ms } => {
+                let Some((no_pending, detail)) =
+                    page_check(app, webview, json!({ "mode": "network_idle" }), timeout).await?
+                else {
+                    return Ok(None);
+                };
+                // Quiet for idle_ms, counting from the start of the wait if nothing happened before
+                let idle_for = detail
+                    .as_ref()
+                    .and_then(|detail| detail.get("idle_for_ms"))
+                    .and_then(Value::as_u64)
+                    .map_or(elapsed.as_millis() as u64, |idle_for| idle_for.min(elapsed.as_millis() as u64));
+                Ok(Some((no_pending && idle_for >= *idle_ms, detail)))
+            }
+        }
+    }
+}
+
+async fn page_check<R: Runtime>(
+    app: &AppHandle<R>,
+    webview: &Webview<R>,
+    payload: Value,
+    timeout: Duration,
+) -> Result<Option<(bool, Option<Value>)>, Error> {
+    let response = match bridge::request(app, webview.label(), "wait-for-check", payload, timeout).await {
+        Ok(response) => response,
+        // A page that is loading has no listener yet; try again on the next poll
+        Err(Error::TimeoutError { .. }) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let success = response.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !success {
+        let error = response
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error occurred");
+        return Err(Error::window_operation_failed("wait_for", error));
+    }
+
+    let satisfied = response.pointer("/data/satisfied").and_then(Value::as_bool).unwrap_or(false);
+    Ok(Some((satisfied, response.pointer("/data/detail").cloned())))
+}
+
+/// Poll a condition until it holds or the timeout expires
+pub async fn handle_wait_for<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: WaitForRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for wait_for: {}", e)))?;
+
+    let timeout_ms = request.timeout_ms.unwrap_or(10000);
+    let interval = Duration::from_millis(request.interval_ms.unwrap_or(100));
+    if interval.is_zero() {
+        return Err(Error::invalid_parameter("interval_ms", "a positive number", "0"));
+    }
+
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+    let condition = Condition::from_request(&request, &webview)?;
+
+    info!(
+        "[TAURI_MCP] Waiting up to {} ms for {} in {}",
+        timeout_ms,
+        request.mode,
+        webview.label()
+    );
+
+    let started = Instant::now();
+    let deadline = started + Duration::from_millis(timeout_ms);
+    let mut checks = 0;
+    loop {
+        let elapsed = started.elapsed();
+        // A single check never outlives the wait
+        let remaining = deadline.saturating_duration_since(Instant::now()).max(Duration::from_millis(1));
+        checks += 1;
+        if let Some((true, detail)) = condition.check(app, &webview, elapsed, remaining).await? {
+            let waited_ms = started.elapsed().as_millis() as u64;
+            info!("[TAURI_MCP] {} satisfied after {} ms ({} checks)", request.mode, waited_ms, checks);
+
+            let data = serde_json::to_value(WaitForResponse {
+                mode: request.mode,
+                waited_ms,
+                checks,
+                detail,
+            })
+            .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+            return Ok(SocketResponse {
+                success: true,
+                data: Some(data),
+                error: None,
+            });
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(Error::timeout_error(format!("wait_for {}", request.mode), timeout_ms));
+        }
+        tokio::time::sleep(interval.min(deadline - now)).await;
+    }
+}
@@ -1,8 +1,13 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, Serializer}; // Add Deserialize for parsing payload
 use serde_json::Value;
 use std::fmt;
-use std::sync::mpsc;
-use tauri::{AppHandle, Error as TauriError, Listener, Manager, Runtime, WebviewWindow};
+use std::time::Duration;
+use tauri::{AppHandle, Error as TauriError, Runtime, Webview};
+
+use crate::bridge;
+use crate::shared::CssRect;
+use crate::tools::windows::resolve_webview;
 
 // Custom error enum for the get_dom_text command
 #[derive(Debug)] // Add Serialize for the enum itself if it needs to be directly serialized
@@ -44,27 +49,36 @@ impl From<TauriError> for GetDomError {
     }
 }
 
+// Object form of the get_dom payload (a bare window label string is accepted too)
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetDomRequest {
+    window_label: Option<String>,
+    /// Webview to read inside a multi-webview window
+    webview_label: Option<String>,
+}
+
 // Handler function for the getDom command, following the take_screenshot pattern
 pub async fn handle_get_dom<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<crate::socket_server::SocketResponse, crate::error::Error> {
     // Parse the window label from the payload - handle both string and object formats
-    let window_label = if payload.is_string() {
+    let request = if payload.is_string() {
         // Direct string format
-        payload
+        let window_label = payload
             .as_str()
             .ok_or_else(|| {
                 crate::error::Error::Anyhow { message: "Invalid string payload for getDom".to_string() }
             })?
-            .to_string()
+            .to_string();
+        GetDomRequest {
+            window_label: Some(window_label),
+            webview_label: None,
+        }
     } else if payload.is_object() {
-        // Object with window_label property
-        payload
-            .get("window_label")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .ok_or_else(|| {
+        // Object with window_label and/or webview_label properties
+        serde_json::from_value::<GetDomRequest>(payload)
+            .map_err(|_| {
                 crate::error::Error::Anyhow {
                     message: "Missing or invalid window_label in payload object".to_string(),
                 }
@@ -78,11 +92,9 @@ pub async fn handle_get_dom<R: Runtime>(
         });
     };
 
-    // Get the window by label using the Manager trait
-    let window = app.get_webview_window(&window_label).ok_or_else(|| {
-        crate::error::Error::window_not_found(&window_label)
-    })?;
-    let result = get_dom_text(app.clone(), window).await;
+    // Get the webview by label
+    let webview = resolve_webview(app, request.window_label.as_deref(), request.webview_label.as_deref())?;
+    let result = get_dom_text(app.clone(), webview).await;
     match result {
         Ok(dom_text) => {
             let data = serde_json::to_value(dom_text).map_err(|e| {
@@ -101,49 +113,47 @@ pub async fn handle_get_dom<R: Runtime>(
         }),
     }
 }
-use tauri::Emitter;
+
 #[tauri::command]
 pub async fn get_dom_text<R: Runtime>(
     app: AppHandle<R>,
-    _window: WebviewWindow<R>,
+    webview: Webview<R>,
 ) -> Result<String, GetDomError> {
-    app.emit_to("main", "got-dom-content", "test").unwrap();
-
-    let (tx, rx) = mpsc::channel();
-
-    app.once("got-dom-content-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
-
-    // Wait for the content
-    match rx.recv_timeout(std::time::Duration::from_secs(5)) {
-        Ok(dom_string) => {
-            if dom_string.is_empty() {
-                Err(GetDomError::DomIsEmpty)
-            } else {
-                Ok(dom_string)
-            }
-        }
-        Err(e) => {
-            // This error (e: tauri::Error) could be from the eval call itself
-            // or an error from the JavaScript execution (Promise rejection).
-            Err(GetDomError::from(e))
-        }
+    let response = bridge::request(
+        &app,
+        webview.label(),
+        "got-dom-content",
+        Value::Null,
+        Duration::from_secs(5),
+    )
+    .await?;
+
+    let dom_string = response
+        .get("domContent")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+
+    if dom_string.is_empty() {
+        Err(GetDomError::DomIsEmpty)
+    } else {
+        Ok(dom_string.to_string())
     }
 }
 
-// Second fix: add From implementation for RecvTimeoutError
-impl From<mpsc::RecvTimeoutError> for GetDomError {
-    fn from(err: mpsc::RecvTimeoutError) -> Self {
-        GetDomError::WebviewOperation(format!("Timeout waiting for DOM: {}", err))
+// Bridge failures (emit errors, timeouts) surface as webview operation errors
+impl From<crate::error::Error> for GetDomError {
+    fn from(err: crate::error::Error) -> Self {
+        GetDomError::WebviewOperation(format!("Failed waiting for DOM: {}", err))
     }
 }
 
 // Define the structure for get_element_position payload
-#[derive(Debug, Deserialize)]
-struct GetElementPositionPayload {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetElementPositionPayload {
     window_label: String,
+    /// Webview to search inside a multi-webview window
+    webview_label: Option<String>,
+    /// "id", "class", "tag", "text", "xpath", "css", "ref" (from get_accessibility_tree) or "mark" (from take_annotated_screenshot)
     selector_type: String,
     selector_value: String,
     #[serde(default)]
@@ -152,6 +162,65 @@ struct GetElementPositionPayload {
     raw_coordinates: bool,
 }
 
+// Position of an element as reported by the webview
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementPosition {
+    /// Horizontal center of the element, in CSS pixels
+    x: i32,
+    /// Vertical center of the element, in CSS pixels
+    y: i32,
+    width: i32,
+    height: i32,
+    element: ElementSummary,
+    clicked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    click_result: Option<Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ElementSummary {
+    tag: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    classes: Option<String>,
+}
+
+/// Bounding box of the element a selector finds, scrolled into view first so it is on screen
+pub(crate) async fn find_element_rect<R: Runtime>(
+    app: &AppHandle<R>,
+    webview: &Webview<R>,
+    selector_type: Option<&str>,
+    selector_value: &str,
+    operation: &str,
+) -> crate::Result<CssRect> {
+    let response = bridge::request(
+        app,
+        webview.label(),
+        "get-element-position",
+        serde_json::json!({
+            "selectorType": selector_type.unwrap_or("css"),
+            "selectorValue": selector_value,
+            "scrollIntoView": true,
+        }),
+        Duration::from_secs(5),
+    )
+    .await?;
+
+    let success = response.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !success {
+        let error = response
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error occurred");
+        return Err(crate::error::Error::window_operation_failed(operation, error));
+    }
+
+    serde_json::from_value(response.pointer("/data/rect").cloned().unwrap_or(Value::Null))
+        .map_err(|e| crate::error::Error::serialization_error(format!("Invalid element position result: {}", e)))
+}
+
 // Handle getting element position
 pub async fn handle_get_element_position<R: Runtime>(
     app: &AppHandle<R>,
@@ -161,18 +230,7 @@ pub async fn handle_get_element_position<R: Runtime>(
     let payload = serde_json::from_value::<GetElementPositionPayload>(payload).map_err(|e| {
         crate::error::Error::serialization_error(format!("Invalid payload for get_element_position: {}", e))
     })?;
-
-    // Create a channel to receive the result
-    let (tx, rx) = mpsc::channel();
-
-    // Event name for the response
-    let event_name = "get-element-position-response";
-
-    // Set up the listener for the response
-    app.once(event_name, move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
+    let webview = resolve_webview(app, Some(&payload.window_label), payload.webview_label.as_deref())?;
 
     // Prepare the request payload with selector information
     let js_payload = serde_json::json!({
@@ -183,32 +241,36 @@ pub async fn handle_get_element_position<R: Runtime>(
         "rawCoordinates": payload.raw_coordinates
     });
 
-    // Emit the event to the webview
-    app.emit_to(&payload.window_label, "get-element-position", js_payload)
-        .map_err(|e| {
-            crate::error::Error::communication_error_with_context(
-                "Failed to emit get-element-position event",
-                format!("{}", e),
-            )
-        })?;
-
-    // Wait for the response with a timeout
-    match rx.recv_timeout(std::time::Duration::from_secs(5)) {
-        Ok(result) => {
-            // Parse the result
-            let result_value: Value = serde_json::from_str(&result).map_err(|e| {
-                crate::error::Error::serialization_error(format!("Failed to parse result: {}", e))
-            })?;
+    // Emit the event to the webview and wait for its response
+    let result = bridge::request(
+        app,
+        webview.label(),
+        "get-element-position",
+        js_payload,
+        Duration::from_secs(5),
+    )
+    .await;
 
+    match result {
+        Ok(result_value) => {
             let success = result_value
                 .get("success")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false);
 
             if success {
+                let position: ElementPosition = serde_json::from_value(
+                    result_value.get("data").cloned().unwrap_or(Value::Null),
+                )
+                .map_err(|e| {
+                    crate::error::Error::serialization_error(format!("Invalid element position result: {}", e))
+                })?;
+                let data = serde_json::to_value(position).map_err(|e| {
+                    crate::error::Error::serialization_error(format!("Failed to serialize response: {}", e))
+                })?;
                 Ok(crate::socket_server::SocketResponse {
                     success: true,
-                    data: Some(result_value.get("data").cloned().unwrap_or(Value::Null)),
+                    data: Some(data),
                     error: None,
                 })
             } else {
@@ -227,18 +289,18 @@ pub async fn handle_get_element_position<R: Runtime>(
         Err(e) => Ok(crate::socket_server::SocketResponse {
             success: false,
             data: None,
-            error: Some(format!(
-                "Timeout waiting for element position result: {}",
-                e
-            )),
+            error: Some(format!("Failed to get element position result: {}", e)),
         }),
     }
 }
 
 // Define the structure for send_text_to_element payload
-#[derive(Debug, Deserialize)]
-struct SendTextToElementPayload {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SendTextToElementPayload {
     window_label: String,
+    /// Webview to search inside a multi-webview window
+    webview_label: Option<String>,
+    /// "id", "class", "tag", "text", "xpath", "css", "ref" (from get_accessibility_tree) or "mark" (from take_annotated_screenshot)
     selector_type: String,
     selector_value: String,
     text: String,
@@ -260,19 +322,7 @@ pub async fn handle_send_text_to_element<R: Runtime>(
     let payload = serde_json::from_value::<SendTextToElementPayload>(payload).map_err(|e| {
         crate::error::Error::serialization_error(format!("Invalid payload for send_text_to_element: {}", e))
     })?;
-
-
-    // Create a channel to receive the result
-    let (tx, rx) = mpsc::channel();
-
-    // Event name for the response
-    let event_name = "send-text-to-element-response";
-
-    // Set up the listener for the response
-    app.once(event_name, move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
+    let webview = resolve_webview(app, Some(&payload.window_label), payload.webview_label.as_deref())?;
 
     // Prepare the request payload
     let js_payload = serde_json::json!({
@@ -282,24 +332,19 @@ pub async fn handle_send_text_to_element<R: Runtime>(
         "delayMs": payload.delay_ms
     });
 
-    // Emit the event to the webview
-    app.emit_to(&payload.window_label, "send-text-to-element", js_payload)
-        .map_err(|e| {
-            crate::error::Error::communication_error_with_context(
-                "Failed to emit send-text-to-element event",
-                format!("{}", e),
-            )
-        })?;
-
-    // Wait for the response with a timeout
-    match rx.recv_timeout(std::time::Duration::from_secs(30)) {
-        // Longer timeout for typing text
-        Ok(result) => {
-            // Parse the result
-            let result_value: Value = serde_json::from_str(&result).map_err(|e| {
-                crate::error::Error::serialization_error(format!("Failed to parse result: {}", e))
-            })?;
+    // Emit the event to the webview and wait for its response
+    // (longer timeout for typing text)
+    let result = bridge::request(
+        app,
+        webview.label(),
+        "send-text-to-element",
+        js_payload,
+        Duration::from_secs(30),
+    )
+    .await;
 
+    match result {
+        Ok(result_value) => {
             let success = result_value
                 .get("success")
                 .and_then(|v| v.as_bool())
@@ -327,7 +372,7 @@ pub async fn handle_send_text_to_element<R: Runtime>(
         Err(e) => Ok(crate::socket_server::SocketResponse {
             success: false,
             data: None,
-            error: Some(format!("Timeout waiting for text input completion: {}", e)),
+            error: Some(format!("Failed waiting for text input completion: {}", e)),
         }),
     }
 }
@@ -0,0 +1,172 @@
+use log::info;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, PhysicalPosition, Runtime, Webview, Window};
+
+use crate::error::Error;
+use crate::models::{WebviewInfo, WindowBounds, WindowInfo, WindowListRequest, WindowListResponse};
+use crate::socket_server::SocketResponse;
+
+/// Window targeted when a request does not name one
+pub const DEFAULT_WINDOW_LABEL: &str = "main";
+
+/// Find the webview a request is aimed at.
+///
+/// `webview_label` picks a specific webview, which is how the webviews of a
+/// multi-webview window are addressed (Tauri's `unstable` feature, enabled through
+/// this plugin's `unstable` feature). Otherwise the window named by `window_label`
+/// (default "main") is used; for a multi-webview window that is its first webview.
+pub fn resolve_webview<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: Option<&str>,
+    webview_label: Option<&str>,
+) -> crate::Result<Webview<R>> {
+    if let Some(webview_label) = webview_label {
+        let webview = find_webview(app, webview_label)
+            .ok_or_else(|| Error::window_not_found(format!("webview {}", webview_label)))?;
+
+        if let Some(window_label) = window_label
+            && webview.window().label() != window_label
+        {
+            return Err(Error::invalid_parameter(
+                "webview_label",
+                format!("a webview inside window '{}'", window_label),
+                format!("'{}' in window '{}'", webview_label, webview.window().label()),
+            ));
+        }
+        return Ok(webview);
+    }
+
+    let window_label = window_label.unwrap_or(DEFAULT_WINDOW_LABEL);
+    if let Some(window) = app.get_webview_window(window_label) {
+        return Ok(window.as_ref().clone());
+    }
+
+    #[cfg(feature = "unstable")]
+    if let Some(webview) = app
+        .get_window(window_label)
+        .and_then(|window| window.webviews().into_iter().next())
+    {
+        return Ok(webview);
+    }
+
+    Err(Error::window_not_found(window_label))
+}
+
+/// Find the native window a request is aimed at, see [`resolve_webview`]
+pub fn resolve_window<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: Option<&str>,
+    webview_label: Option<&str>,
+) -> crate::Result<Window<R>> {
+    resolve_webview(app, window_label, webview_label).map(|webview| webview.window())
+}
+
+/// Position of a webview inside its window's client area, in physical pixels.
+/// Zero for the webview of a single-webview window.
+pub fn webview_offset<R: Runtime>(app: &AppHandle<R>, webview: &Webview<R>) -> crate::Result<PhysicalPosition<i32>> {
+    // Tauri reports the window's inner position for those, not an offset
+    if app.get_webview_window(webview.label()).is_some() {
+        return Ok(PhysicalPosition::new(0, 0));
+    }
+    Ok(webview.position()?)
+}
+
+#[cfg(feature = "unstable")]
+fn find_webview<R: Runtime>(app: &AppHandle<R>, label: &str) -> Option<Webview<R>> {
+    app.get_webview(label)
+}
+
+// Without the unstable feature every webview is the single webview of its window
+#[cfg(not(feature = "unstable"))]
+fn find_webview<R: Runtime>(app: &AppHandle<R>, label: &str) -> Option<Webview<R>> {
+    app.get_webview_window(label).map(|window| window.as_ref().clone())
+}
+
+// Every window that hosts at least one webview
+#[cfg(feature = "unstable")]
+fn all_windows<R: Runtime>(app: &AppHandle<R>) -> Vec<Window<R>> {
+    app.windows().into_values().collect()
+}
+
+#[cfg(not(feature = "unstable"))]
+fn all_windows<R: Runtime>(app: &AppHandle<R>) -> Vec<Window<R>> {
+    app.webview_windows()
+        .into_values()
+        .map(|window| window.as_ref().window())
+        .collect()
+}
+
+/// Describe every window, sorted by label
+pub fn list_windows<R: Runtime>(app: &AppHandle<R>) -> crate::Result<Vec<WindowInfo>> {
+    let mut windows = all_windows(app)
+        .iter()
+        .map(|window| window_info(app, window))
+        .collect::<crate::Result<Vec<_>>>()?;
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(windows)
+}
+
+fn window_info<R: Runtime>(app: &AppHandle<R>, window: &Window<R>) -> crate::Result<WindowInfo> {
+    let position = window.outer_position()?;
+    let size = window.inner_size()?;
+
+    let mut webviews = window
+        .webviews()
+        .iter()
+        .map(|webview| {
+            let position = webview_offset(app, webview)?;
+            let size = webview.size()?;
+            Ok(WebviewInfo {
+                label: webview.label().to_string(),
+                url: webview.url().map(|url| url.to_string()).ok(),
+                bounds: WindowBounds {
+                    x: position.x,
+                    y: position.y,
+                    width: size.width,
+                    height: size.height,
+                },
+            })
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+    webviews.sort_by(|a, b| a.label.cmp(&b.label));
+
+    Ok(WindowInfo {
+        label: window.label().to_string(),
+        title: window.title()?,
+        url: webviews.first().and_then(|webview| webview.url.clone()),
+        is_visible: window.is_visible()?,
+        is_focused: window.is_focused()?,
+        is_maximized: window.is_maximized()?,
+        is_minimized: window.is_minimized()?,
+        is_fullscreen: window.is_fullscreen()?,
+        bounds: WindowBounds {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        },
+        scale_factor: window.scale_factor()?,
+        webviews,
+    })
+}
+
+pub fn handle_list_windows<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    // An empty or missing payload is fine
+    let _request: Option<WindowListRequest> = serde_json::from_value(payload)
+        .map_err(|e| Error::serialization_error(format!("Invalid payload for list_windows: {}", e)))?;
+
+    let windows = list_windows(app)?;
+    info!("[TAURI_MCP] Listing {} window(s)", windows.len());
+
+    let data = serde_json::to_value(WindowListResponse { windows })
+        .map_err(|e| Error::serialization_error(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+    })
+}
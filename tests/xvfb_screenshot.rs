@@ -0,0 +1,89 @@
+// Takes a real screenshot of a WebKitGTK webview. It needs a display, so CI runs it
+// under Xvfb:
+//
+//   xvfb-run -a cargo test --test xvfb_screenshot
+//
+// GTK must run on the main thread, so this test has its own `main` instead of the
+// default harness. Without a display it is skipped.
+
+#[cfg(target_os = "linux")]
+fn main() {
+    use std::time::Duration;
+    use tauri::{WebviewUrl, WebviewWindowBuilder};
+    use tauri_plugin_mcp::shared::ScreenshotFormat;
+    use tauri_plugin_mcp::{PluginConfig, ScreenshotRequest, TauriMcpExt};
+
+    if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        println!("No display available, skipping (run under xvfb-run)");
+        return;
+    }
+
+    let app = tauri::Builder::default()
+        .plugin(tauri_plugin_mcp::init_with_config(
+            PluginConfig::new("xvfb-screenshot".to_string()).start_socket_server(false),
+        ))
+        .build(tauri::test::mock_context(tauri::test::noop_assets()))
+        .expect("failed to build app");
+
+    let page = "data:text/html,<body style='margin:0;background:rgb(200,0,0)'></body>";
+    WebviewWindowBuilder::new(&app, "main", WebviewUrl::External(page.parse().expect("valid URL")))
+        .inner_size(400.0, 300.0)
+        .build()
+        .expect("failed to create window");
+
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        // Give WebKit time to load and paint the page
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        let result = handle
+            .tauri_mcp()
+            .take_screenshot_async(ScreenshotRequest {
+                window_label: "main".to_string(),
+                format: Some(ScreenshotFormat::Png),
+                ..Default::default()
+            })
+            .await;
+        let code = match check_screenshot(result) {
+            Ok(()) => {
+                println!("take_screenshot captured the page");
+                0
+            }
+            Err(e) => {
+                eprintln!("take_screenshot failed: {}", e);
+                1
+            }
+        };
+        handle.exit(code);
+    });
+
+    let code = app.run_return(|_, _| {});
+    std::process::exit(code);
+}
+
+// The capture must be the page itself: the size of the webview and the page's color
+#[cfg(target_os = "linux")]
+fn check_screenshot(result: tauri_plugin_mcp::Result<tauri_plugin_mcp::ScreenshotResponse>) -> Result<(), String> {
+    let response = result.map_err(|e| e.to_string())?;
+    if !response.success {
+        return Err(response.error.unwrap_or_else(|| "no error message".to_string()));
+    }
+    let data_url = response.data.ok_or("no image data")?;
+    let (prefix, encoded) = data_url.split_once(',').ok_or("not a data URL")?;
+    if prefix != "data:image/png;base64" {
+        return Err(format!("unexpected data URL prefix {}", prefix));
+    }
+    let bytes = base64::decode(encoded).map_err(|e| e.to_string())?;
+    let image = image::load_from_memory(&bytes).map_err(|e| e.to_string())?.to_rgba8();
+    if image.width() < 400 || image.height() < 300 {
+        return Err(format!("image is only {}x{}", image.width(), image.height()));
+    }
+    let center = image.get_pixel(image.width() / 2, image.height() / 2);
+    if center[0] < 150 || center[1] > 50 || center[2] > 50 {
+        return Err(format!("center pixel is {:?}, expected the page's red background", center));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {}